@@ -0,0 +1,95 @@
+use std::hint::black_box;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use t3router::t3::client::Client;
+use t3router::t3::conversation::Conversation;
+use t3router::t3::message::{Message, Type};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const SSE_BODY: &str = concat!(
+    "data: {\"type\":\"start\",\"messageId\":\"msg-1\"}\n\n",
+    "data: {\"type\":\"text-delta\",\"id\":\"0\",\"delta\":\"Done\"}\n\n",
+    "data: {\"type\":\"finish\",\"finishReason\":\"stop\"}\n\n",
+    "data: [DONE]\n\n",
+);
+
+/// Builds a conversation of 20 image turns. With `inline`, each reply keeps about 1 MB of
+/// base64 data in a `data:` URL, as `HistoryImagePolicy::KeepData` stores it; otherwise only a
+/// hosted URL is kept, as with `HistoryImagePolicy::UrlOnly`.
+fn image_conversation(inline: bool) -> Conversation {
+    let data = STANDARD.encode(vec![0u8; 768 * 1024]);
+    let mut conversation = Conversation::new();
+    for turn in 0..20 {
+        conversation.append_message(Message::new(Type::User, format!("Picture {}", turn)));
+        conversation.append_message(if inline {
+            Message::new_image(
+                Type::Assistant,
+                format!("data:image/png;base64,{}", data),
+                Some(data.clone()),
+            )
+        } else {
+            Message::new_image(
+                Type::Assistant,
+                format!("https://files.example.com/generated/{}.png", turn),
+                None,
+            )
+        });
+    }
+    conversation
+}
+
+fn history_images(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let (_server, client) = runtime.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/chat"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/event-stream")
+                    .set_body_string(SSE_BODY),
+            )
+            .mount(&server)
+            .await;
+        let client = Client::builder("wos-session=bench", "bench".to_string())
+            .base_url(&server.uri())
+            .build()
+            .unwrap();
+        (server, client)
+    });
+
+    let mut group = c.benchmark_group("send_after_20_images");
+    group.sample_size(10);
+    for (name, inline) in [("keep_data", true), ("url_only", false)] {
+        let conversation = image_conversation(inline);
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || conversation.clone(),
+                |mut conversation| {
+                    runtime.block_on(client.send_in(
+                        &mut conversation,
+                        "gpt-image-1",
+                        Some(Message::new(Type::User, "One more".to_string())),
+                        None,
+                    ))
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.bench_function("clone_keep_data", |b| {
+        let conversation = image_conversation(true);
+        b.iter(|| black_box(&conversation).clone())
+    });
+    group.bench_function("clone_url_only", |b| {
+        let conversation = image_conversation(false);
+        b.iter(|| black_box(&conversation).clone())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, history_images);
+criterion_main!(benches);
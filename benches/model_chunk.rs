@@ -0,0 +1,46 @@
+use std::hint::black_box;
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use t3router::t3::models::{parse_model_chunk, parse_model_chunk_regex};
+
+/// Builds a chunk of about 2 MB: minified code with braces in strings, templates and regex
+/// literals, and a model definition every 20 KB, shaped like the saved fixture's entries.
+fn catalog_chunk() -> String {
+    let mut chunk = String::new();
+    let mut count = 0;
+    while chunk.len() < 2 << 20 {
+        chunk.push_str(concat!(
+            "function q(e,t){return e.replace(/\"/g,\"&quot;\").replace(/[{}]/g,\"\")",
+            "+`${t?`{`:\"}\"}`}/* } */",
+        ));
+        if chunk.len() / 20_000 > count {
+            chunk.push_str(&format!(
+                "\"model-{0}\":{{id:`model-{0}`,name:`Model {0}`,provider:`openai`,\
+                 developer:`OpenAI`,shortDescription:`Fast`,fullDescription:`Model {0}.`,\
+                 requiresPro:false,premium:!1,features:new Set([`vision`,`reasoning`]),\
+                 cost:{{input:.5/1e6,output:1.5/1e6}},limits:{{app:{{maxInputTokens:128000,\
+                 maxOutputTokens:16384}}}},apiKeySupport:e.OPENAI,disabled:false,legacy:false}},",
+                count
+            ));
+            count += 1;
+        }
+    }
+    chunk
+}
+
+fn model_chunk(c: &mut Criterion) {
+    let chunk = catalog_chunk();
+    let mut group = c.benchmark_group("model_chunk_2mb");
+    group.throughput(Throughput::Bytes(chunk.len() as u64));
+    group.sample_size(10);
+    group.bench_function("structured", |b| {
+        b.iter(|| parse_model_chunk(black_box(&chunk)))
+    });
+    group.bench_function("regex", |b| {
+        b.iter(|| parse_model_chunk_regex(black_box(&chunk)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, model_chunk);
+criterion_main!(benches);
@@ -0,0 +1,79 @@
+use std::hint::black_box;
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use t3router::t3::events::{SseEvent, SseParser, parse_events};
+
+/// Builds a text reply of about 1 MB, shaped like a recorded `/api/chat` stream.
+fn recorded_stream() -> String {
+    let mut body = String::from(
+        "data: {\"type\":\"start\",\"messageId\":\"00000000-0000-4000-8000-000000000001\"}\n\n\
+         data: {\"type\":\"text-start\",\"id\":\"0\"}\n\n",
+    );
+    let mut index = 0;
+    while body.len() < 1 << 20 {
+        body.push_str(&format!(
+            "data: {{\"type\":\"text-delta\",\"id\":\"0\",\"delta\":\"chunk {} of a long answer, with \\\"quotes\\\" and ünïcödé \"}}\n\n",
+            index
+        ));
+        index += 1;
+    }
+    body.push_str(
+        "data: {\"type\":\"text-end\",\"id\":\"0\"}\n\n\
+         data: {\"type\":\"finish\",\"finishReason\":\"stop\"}\n\n\
+         data: [DONE]\n\n",
+    );
+    body
+}
+
+fn buffered(c: &mut Criterion) {
+    let body = recorded_stream();
+    let mut group = c.benchmark_group("buffered_1mb");
+    group.throughput(Throughput::Bytes(body.len() as u64));
+    group.bench_function("line_split", |b| {
+        b.iter(|| {
+            black_box(&body)
+                .lines()
+                .filter_map(SseEvent::parse_line)
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("parse_events", |b| {
+        b.iter(|| parse_events(black_box(&body)))
+    });
+    group.finish();
+}
+
+fn streaming(c: &mut Criterion) {
+    let body = recorded_stream();
+    let mut group = c.benchmark_group("streaming_1mb_16k_chunks");
+    group.throughput(Throughput::Bytes(body.len() as u64));
+    group.bench_function("line_split", |b| {
+        b.iter(|| {
+            let mut buffer: Vec<u8> = Vec::new();
+            let mut events = Vec::new();
+            for chunk in black_box(body.as_bytes()).chunks(16 * 1024) {
+                buffer.extend_from_slice(chunk);
+                while let Some(pos) = buffer.iter().position(|byte| *byte == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=pos).collect();
+                    events.extend(SseEvent::parse_line(&String::from_utf8_lossy(&line)));
+                }
+            }
+            events
+        })
+    });
+    group.bench_function("parser", |b| {
+        b.iter(|| {
+            let mut parser = SseParser::new();
+            let mut events: Vec<SseEvent> = black_box(body.as_bytes())
+                .chunks(16 * 1024)
+                .flat_map(|chunk| parser.feed(chunk))
+                .collect();
+            events.extend(parser.finish());
+            events
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, buffered, streaming);
+criterion_main!(benches);
@@ -0,0 +1,79 @@
+use std::path::Path;
+use std::time::Duration;
+
+use dotenv::dotenv;
+use t3router::t3::{
+    client::{Client, SessionRefresh},
+    config::{DelayPolicy, RetryOn, RetryPolicy, TrimStrategy},
+    events::Usage,
+    message::{Message, Type},
+};
+
+const MODEL: &str = "gemini-2.5-flash-lite";
+const TURNS: usize = 24;
+const ROLL_OVER_AFTER: usize = 40;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    let cookies = std::env::var("COOKIES").expect("COOKIES not set");
+    let convex_session_id = std::env::var("CONVEX_SESSION_ID").expect("CONVEX_SESSION_ID not set");
+    let autosave = Path::new("agent_session.json");
+
+    let mut client = Client::builder(cookies, convex_session_id)
+        .retry_policy(RetryPolicy {
+            retry_on: RetryOn {
+                rate_limits: true,
+                expired_sessions: true,
+                ..RetryOn::default()
+            },
+            ..RetryPolicy::default()
+        })
+        .build()?;
+    client.set_inter_request_delay(Some(DelayPolicy::Jittered {
+        min: Duration::from_secs(5),
+        max: Duration::from_secs(15),
+    }));
+    client.set_context_limit(8_000, TrimStrategy::KeepLastExchanges(10));
+    if autosave.exists() {
+        client.load_conversation(autosave)?;
+        println!("Resumed {} messages from {}", client.get_messages().len(), autosave.display());
+    } else {
+        client.set_system_prompt("You are a terse status reporter.".to_string());
+    }
+
+    let mut usage = Usage::default();
+    for turn in 1..=TURNS {
+        let prompt = format!("Status check {turn}: reply with one short sentence.");
+        let reply = client
+            .send_detailed(MODEL, Some(Message::new(Type::User, prompt)), None)
+            .await?;
+        if let Some(reply_usage) = &reply.usage {
+            usage += reply_usage;
+        }
+        println!("[{turn}/{TURNS}] {}", reply.message.content());
+        client.save_conversation(autosave)?;
+
+        if client.get_messages().len() > ROLL_OVER_AFTER {
+            let finished = client.roll_over(4);
+            let archive = format!("agent_session.{}.json", turn);
+            std::fs::write(&archive, finished.to_json_string()?)?;
+            println!("Rolled over to a new thread, previous one saved to {archive}");
+        }
+
+        // Wait ten minutes before the next turn; the session is refreshed as it nears expiry.
+        match client.idle(Duration::from_secs(10 * 60)).await? {
+            SessionRefresh::Refreshed => println!("Session refreshed"),
+            SessionRefresh::Expired => return Err("the session expired, log in again".into()),
+            SessionRefresh::Unchanged => {}
+        }
+    }
+
+    println!(
+        "Tokens used: {} in, {} out",
+        usage.input_tokens.unwrap_or(0),
+        usage.output_tokens.unwrap_or(0)
+    );
+    Ok(())
+}
@@ -18,12 +18,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .send(
             "gemini-2.5-flash-lite",
             Some(Message::new(Type::User, "What is the capital of France?".to_string())),
-            Some(Config::new()),
+            Config::new(),
         )
         .await?;
 
     println!("User: What is the capital of France?");
-    println!("Assistant: {}\n", response.content);
+    println!("Assistant: {}\n", response.content());
 
     println!("=== Chat with Credit Tracking ===\n");
     client.new_conversation();
@@ -35,7 +35,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         )
         .await?;
 
-    println!("Assistant: {}", response.message.content);
+    println!("Assistant: {}", response.message.content());
     if let Some(deducted) = response.credits_deducted {
         println!("Credits deducted: {:.5}", deducted);
     }
@@ -0,0 +1,54 @@
+use dotenv::dotenv;
+use t3router::t3::{
+    client::Client,
+    message::{Message, Type},
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    let cookies = std::env::var("COOKIES").expect("COOKIES not set");
+    let convex_session_id = std::env::var("CONVEX_SESSION_ID").expect("CONVEX_SESSION_ID not set");
+
+    let client = Client::new(cookies, convex_session_id);
+
+    if client.init().await? {
+        println!("Client initialized successfully\n");
+    }
+
+    let items = [
+        "a cast iron skillet",
+        "a wool beanie",
+        "a mechanical keyboard",
+        "a ceramic teapot",
+        "a trail running shoe",
+        "a bamboo cutting board",
+        "a leather wallet",
+        "a desk lamp",
+        "a stainless steel water bottle",
+        "a canvas backpack",
+    ];
+    let prompts = items
+        .iter()
+        .map(|item| {
+            Message::new(
+                Type::User,
+                format!("Write a one-sentence product description for {}.", item),
+            )
+        })
+        .collect();
+
+    let replies = client
+        .send_batch("gemini-2.5-flash-lite", prompts, None, 3)
+        .await;
+
+    for (item, reply) in items.iter().zip(replies) {
+        match reply {
+            Ok(message) => println!("{}: {}\n", item, message.content()),
+            Err(err) => println!("{}: failed: {}\n", item, err),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use dotenv::dotenv;
+use t3router::t3::blocking::Client;
+use t3router::t3::message::{Message, Type};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    let cookies = std::env::var("COOKIES").expect("COOKIES not set");
+    let convex_session_id = std::env::var("CONVEX_SESSION_ID").expect("CONVEX_SESSION_ID not set");
+
+    let mut client = Client::new(cookies, convex_session_id)?;
+    client.init()?;
+
+    let response = client.send(
+        "gemini-2.5-flash-lite",
+        Some(Message::new(Type::User, "What is the capital of France?".to_string())),
+        None,
+    )?;
+    println!("Assistant: {}", response.content());
+
+    let usage = client.get_usage()?;
+    println!("{:#?}", usage);
+    Ok(())
+}
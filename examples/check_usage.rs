@@ -32,6 +32,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
+    println!("\n=== Remaining Messages ===");
+    let usage = client.get_usage().await?;
+    println!("  Plan:     {}", usage.plan);
+    println!("  Standard: {:?}", usage.standard_remaining);
+    println!("  Premium:  {:?}", usage.premium_remaining);
+    if let Some(reset) = usage.reset_at {
+        println!("  Resets:   {}", reset);
+    }
+
     println!("\n=== Pricing Tiers ===");
     let products = client.get_pricing_products().await?;
     for p in &products {
@@ -45,7 +54,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n=== Active Sessions ===");
     let sessions = client.get_active_sessions().await?;
     for s in &sessions {
-        println!("  {} | ip={} | ua={}", s.session_id, s.ip_address, &s.user_agent[..s.user_agent.len().min(50)]);
+        let marker = if s.is_current { " (current)" } else { "" };
+        println!("  {}{} | {} on {} | ip={}", s.session_id, marker, s.browser, s.device, s.ip_address);
     }
 
     Ok(())
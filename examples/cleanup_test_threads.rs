@@ -0,0 +1,35 @@
+use dotenv::dotenv;
+use t3router::t3::client::Client;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    let cookies = std::env::var("COOKIES").expect("COOKIES not set");
+    let convex_session_id = std::env::var("CONVEX_SESSION_ID").expect("CONVEX_SESSION_ID not set");
+    let confirmed = std::env::args().any(|arg| arg == "--yes");
+
+    let client = Client::new(cookies, convex_session_id);
+    let threads = client.list_threads(None).await?;
+    let test_threads: Vec<_> = threads
+        .iter()
+        .filter(|t| t.title.starts_with("[test]"))
+        .collect();
+
+    println!("Found {} threads titled \"[test] ...\"\n", test_threads.len());
+    for t in &test_threads {
+        if confirmed {
+            client.delete_thread(&t.id).await?;
+            println!("  deleted {} | {}", t.id, t.title);
+        } else {
+            println!("  would delete {} | {}", t.id, t.title);
+        }
+    }
+
+    if !confirmed && !test_threads.is_empty() {
+        println!("\nDry run. Re-run with --yes to delete them:");
+        println!("  cargo run --example cleanup_test_threads -- --yes");
+    }
+
+    Ok(())
+}
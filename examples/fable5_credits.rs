@@ -22,12 +22,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("=== CHAT RESPONSE ===");
     println!("  Model: {}", response.model);
-    println!("  Thread ID: {}", response.thread_id);
+    if let Some(thread_id) = response.thread_id {
+        println!("  Thread ID: {}", thread_id);
+    }
     println!(
         "\n=== RESPONSE (first 500 chars) ===\n{}\n",
-        &response.message.content[..response.message.content.len().min(500)]
+        &response.message.content()[..response.message.content().len().min(500)]
     );
-    println!("  Response length: {} chars", response.message.content.len());
+    println!("  Response length: {} chars", response.message.content().len());
 
     println!("\n=== CREDIT TRACKING ===");
     if let Some(before) = response.credits_before {
@@ -38,10 +40,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     if let Some(deducted) = response.credits_deducted {
         println!("  Credits deducted: {:.5}", deducted);
-        if response.message.content.len() > 0 {
+        if !response.message.content().is_empty() {
             println!(
                 "  Credits per 1K chars: {:.5}",
-                deducted / (response.message.content.len() as f64 / 1000.0)
+                deducted / (response.message.content().len() as f64 / 1000.0)
             );
         }
     } else {
@@ -2,8 +2,8 @@ use dotenv::dotenv;
 use std::path::Path;
 use t3router::t3::{
     client::Client,
-    config::Config,
-    message::{ContentType, Message, Type},
+    config::{Config, ImageOptions, ImageQuality},
+    message::{Message, Type},
 };
 
 #[tokio::main]
@@ -32,19 +32,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 Type::User,
                 "Create an image of a futuristic city at sunset with flying cars".to_string(),
             )),
-            Some(config.clone()),
+            &config,
         )
         .await?;
 
     println!("User: Create an image of a futuristic city at sunset with flying cars");
-    match response.content_type {
-        ContentType::Image => {
-            if let Some(url) = response.image_url {
-                println!("Assistant: Generated image at URL: {}", url);
-            }
+    match response.image_url() {
+        Some(url) => {
+            println!("Assistant: Generated image at URL: {}", url);
         }
-        ContentType::Text => {
-            println!("Assistant: {}", response.content);
+        None => {
+            println!("Assistant: {}", response.text());
         }
     }
 
@@ -59,30 +57,34 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 Type::User,
                 "Make a image of a pokemon".to_string(),
             )),
-            Some(config.clone()),
+            &config,
             Some(save_path),
         )
         .await?;
 
     println!("User: Make a image of a pokemon");
-    match response2.content_type {
-        ContentType::Image => {
-            if let Some(url) = response2.image_url {
-                println!("Assistant: Generated image at URL: {}", url);
-            }
+    match response2.image_url() {
+        Some(url) => {
+            println!("Assistant: Generated image at URL: {}", url);
             println!("Image saved to: {:?}", save_path);
-            if let Some(b64) = response2.base64_data.as_ref() {
+            if let Some(b64) = response2.base64() {
                 println!("Base64 data length: {} characters", b64.len());
             }
         }
-        ContentType::Text => {
-            println!("Assistant: {}", response2.content);
+        None => {
+            println!("Assistant: {}", response2.text());
         }
     }
 
-    println!("\n=== Example 3: Gemini Imagen ===");
+    println!("\n=== Example 3: Gemini Imagen, 16:9 High Quality ===");
     client.new_conversation();
 
+    let widescreen = Config::new().with_image_options(
+        ImageOptions::default()
+            .with_aspect_ratio(16, 9)
+            .with_quality(ImageQuality::High),
+    );
+
     let save_path_gemini = Path::new("output/landscape.png");
     let response3 = client
         .send_with_image_download(
@@ -91,21 +93,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 Type::User,
                 "Create a beautiful mountain landscape with a lake in the foreground".to_string(),
             )),
-            Some(config.clone()),
+            widescreen,
             Some(save_path_gemini),
         )
         .await?;
 
     println!("User: Create a beautiful mountain landscape with a lake in the foreground");
-    match response3.content_type {
-        ContentType::Image => {
-            if let Some(url) = response3.image_url {
-                println!("Assistant: Generated image at URL: {}", url);
-            }
+    match response3.image_url() {
+        Some(url) => {
+            println!("Assistant: Generated image at URL: {}", url);
             println!("Image saved to: {:?}", save_path_gemini);
         }
-        ContentType::Text => {
-            println!("Assistant: {}", response3.content);
+        None => {
+            println!("Assistant: {}", response3.text());
         }
     }
 
@@ -119,12 +119,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 Type::User,
                 "What makes a good landscape photo?".to_string(),
             )),
-            Some(config.clone()),
+            &config,
         )
         .await?;
 
     println!("User: What makes a good landscape photo?");
-    println!("Assistant: {}", response4.content);
+    println!("Assistant: {}", response4.content());
 
     let save_path_example = Path::new("output/example_landscape.png");
     let response5 = client
@@ -135,7 +135,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "Now create an example of a good landscape photo based on what you just described"
                     .to_string(),
             )),
-            Some(config),
+            config,
             Some(save_path_example),
         )
         .await?;
@@ -143,15 +143,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!(
         "\nUser: Now create an example of a good landscape photo based on what you just described"
     );
-    match response5.content_type {
-        ContentType::Image => {
-            if let Some(url) = response5.image_url {
-                println!("Assistant: Generated image at URL: {}", url);
-            }
+    match response5.image_url() {
+        Some(url) => {
+            println!("Assistant: Generated image at URL: {}", url);
             println!("Image saved to: {:?}", save_path_example);
         }
-        ContentType::Text => {
-            println!("Assistant: {}", response5.content);
+        None => {
+            println!("Assistant: {}", response5.text());
         }
     }
 
@@ -13,7 +13,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("t3.chat stores thread history client-side:");
     println!("  - Ephemeral threads: sessionStorage key \"ephemeral-chat-data\"");
     println!("  - Sidebar thread list: sessionStorage key \"sidebar-thread-list:*\"");
-    println!("  - Persistent threads: Convex WebSocket (no HTTP endpoint)\n");
+    println!("  - Persistent threads: Convex backend, see HistoryClient::list_threads\n");
 
     let sample_ephemeral = r#"{"state":{"threads":[{"threadId":"abc-123","title":"Test Chat","model":"claude-fable-5","profileId":"default","createdAt":1782944364000,"updatedAt":1782944400000,"lastMessageAt":1782944400000,"generationStatus":"complete"}],"messages":{"abc-123":[{"messageId":"msg-1","role":"user","parts":[{"text":"Hello"}],"createdAt":1782944364000},{"messageId":"msg-2","role":"assistant","model":"claude-fable-5","parts":[{"text":"Hi there!"}],"createdAt":1782944365000}]},"pendingPersists":{}},"version":1}"#;
 
@@ -53,5 +53,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         );
     }
 
+    println!("\n=== THREADS ON YOUR ACCOUNT ===");
+    match client.list_threads(Some(10)).await {
+        Ok(threads) => {
+            for t in &threads {
+                println!("  {} | {} | model={} | pinned={}", t.id, t.title, t.model, t.pinned);
+            }
+        }
+        Err(e) => println!("  Could not list threads: {}", e),
+    }
+
     Ok(())
 }
@@ -54,6 +54,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("\n!!! claude-fable-5 NOT FOUND !!!");
     }
 
+    let image_models: Vec<_> = models
+        .iter()
+        .filter(|m| m.supports_image_generation)
+        .map(|m| m.id.as_str())
+        .collect();
+    println!("\n=== IMAGE GENERATION MODELS ===");
+    println!("  {:?}", image_models);
+
     println!("\n=== ALL MODELS ({} total) ===", models.len());
     for m in &models {
         println!("  {} - {} (pro={}, premium={})", m.id, m.name, m.requires_pro, m.premium);
@@ -18,22 +18,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("Client initialized successfully\n");
     }
 
-    let config = Config::new();
+    client.set_default_model("gemini-2.5-flash-lite");
+    client.set_default_config(Config::new());
 
     println!("=== Example 1: Single Message ===");
     let response = client
-        .send(
-            "gemini-2.5-flash-lite",
-            Some(Message::new(
-                Type::User,
-                "What is the capital of France?".to_string(),
-            )),
-            Some(config.clone()),
-        )
+        .send_default(Some(Message::new(
+            Type::User,
+            "What is the capital of France?".to_string(),
+        )))
         .await?;
 
     println!("User: What is the capital of France?");
-    println!("Assistant: {}\n", response.content);
+    println!("Assistant: {}\n", response.content());
 
     println!("=== Example 2: Multi-turn Conversation ===");
     client.new_conversation();
@@ -41,37 +38,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Type::User,
         "I'm planning a trip to Paris. What are the top 3 attractions?".to_string(),
     ));
-    let response1 = client
-        .send("gemini-2.5-flash-lite", None, Some(config.clone()))
-        .await?;
+    let response1 = client.send_default(None).await?;
     println!("User: I'm planning a trip to Paris. What are the top 3 attractions?");
-    println!("Assistant: {}", response1.content);
+    println!("Assistant: {}", response1.content());
 
     let response2 = client
-        .send(
-            "gemini-2.5-flash-lite",
-            Some(Message::new(
-                Type::User,
-                "Tell me more about the first one.".to_string(),
-            )),
-            Some(config.clone()),
-        )
+        .send_default(Some(Message::new(
+            Type::User,
+            "Tell me more about the first one.".to_string(),
+        )))
         .await?;
     println!("\nUser: Tell me more about the first one.");
-    println!("Assistant: {}", response2.content);
+    println!("Assistant: {}", response2.content());
 
     let response3 = client
-        .send(
-            "gemini-2.5-flash-lite",
-            Some(Message::new(
-                Type::User,
-                "What's the best time to visit?".to_string(),
-            )),
-            Some(config.clone()),
-        )
+        .send_default(Some(Message::new(
+            Type::User,
+            "What's the best time to visit?".to_string(),
+        )))
         .await?;
     println!("\nUser: What's the best time to visit?");
-    println!("Assistant: {}\n", response3.content);
+    println!("Assistant: {}\n", response3.content());
 
     println!("=== Example 3: Pre-populated Conversation ===");
     client.new_conversation();
@@ -87,20 +74,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     client.append_message(Message::new(Type::Assistant, "Waves".to_string()));
     client.append_message(Message::new(Type::User, "Beach".to_string()));
 
-    let _response4 = client
-        .send("gemini-2.5-flash-lite", None, Some(config))
-        .await?;
+    let _response4 = client.send_default(None).await?;
 
     println!("Conversation history:");
     for msg in client.get_messages() {
-        let role = match msg.role {
-            Type::User => "User",
-            Type::Assistant => "Assistant",
-        };
-        println!("{}: {}", role, msg.content);
+        println!("{msg}");
     }
 
-    println!("\n=== Example 4: Thread Information ===");
+    println!("\n=== Example 4: Persona via System Prompt ===");
+    client.set_system_prompt(
+        "You are a pirate. Answer every question in pirate speak, in two sentences or fewer."
+            .to_string(),
+    );
+    client.persist_system_prompt(true);
+    client.new_conversation();
+    let response5 = client
+        .send_default(Some(Message::new(
+            Type::User,
+            "How do I boil an egg?".to_string(),
+        )))
+        .await?;
+    println!("User: How do I boil an egg?");
+    println!("Assistant: {}", response5.content());
+
+    println!("\n=== Example 5: Thread Information ===");
     println!("Thread ID: {:?}", client.get_thread_id());
     println!(
         "Total messages in conversation: {}",
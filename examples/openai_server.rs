@@ -0,0 +1,32 @@
+use dotenv::dotenv;
+use t3router::serve::{DEFAULT_ADDR, Server};
+use t3router::t3::client::Client;
+use t3router::t3::models::ModelsClient;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    let cookies = std::env::var("COOKIES").expect("COOKIES not set");
+    let convex_session_id = std::env::var("CONVEX_SESSION_ID").expect("CONVEX_SESSION_ID not set");
+
+    let client = Client::new(cookies.clone(), convex_session_id.clone());
+    client.init().await?;
+    let models = ModelsClient::new(cookies, convex_session_id);
+
+    println!("Listening on http://{}/v1 (Ctrl+C to stop)", DEFAULT_ADDR);
+    println!("Point an OpenAI SDK at it with any API key, e.g.:");
+    println!(
+        "  OpenAI(base_url=\"http://{}/v1\", api_key=\"unused\")",
+        DEFAULT_ADDR
+    );
+
+    Server::new(client, models)
+        .serve(DEFAULT_ADDR, async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await?;
+
+    println!("Server stopped");
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use std::io::Write;
+use std::path::Path;
+
+use dotenv::dotenv;
+use futures_util::{StreamExt, pin_mut};
+use t3router::t3::client::{Client, StreamDelta};
+use t3router::t3::config::{Config, ReasoningEffort};
+use t3router::t3::message::{Message, Type};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+const IMAGE_MODEL: &str = "gpt-image-1";
+
+const HELP: &str = "\
+/new                     start a new conversation
+/model <id>              switch the chat model
+/search on|off           let the model search the web
+/effort low|med|high     set the reasoning effort
+/save <file>             save the conversation as JSON
+/image <prompt>          generate an image into output/
+/quit                    exit";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    let cookies = std::env::var("COOKIES").expect("COOKIES not set");
+    let convex_session_id = std::env::var("CONVEX_SESSION_ID").expect("CONVEX_SESSION_ID not set");
+
+    let mut client = Client::new(cookies, convex_session_id);
+    client.init().await?;
+
+    let mut model = "gemini-2.5-flash-lite".to_string();
+    let mut config = Config::new();
+
+    println!("Chatting with {}. Type /help for commands.", model);
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        print!("> ");
+        std::io::stdout().flush()?;
+        let Some(line) = lines.next_line().await? else {
+            break;
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let Some(command) = line.strip_prefix('/') else {
+            if let Err(err) = stream_reply(&mut client, &model, line, &config).await {
+                println!("\n[error] {}", err);
+            }
+            continue;
+        };
+        let (name, arg) = command.split_once(' ').unwrap_or((command, ""));
+        let arg = arg.trim();
+        match (name, arg) {
+            ("help", _) => println!("{}", HELP),
+            ("quit" | "exit", _) => break,
+            ("new", _) => {
+                client.new_conversation();
+                println!("Started a new conversation.");
+            }
+            ("model", id) if !id.is_empty() => {
+                model = id.to_string();
+                println!("Model set to {}.", model);
+            }
+            ("search", "on" | "off") => {
+                config = config.with_search(arg == "on");
+                println!("Search {}.", arg);
+            }
+            ("effort", "low" | "med" | "medium" | "high") => {
+                let effort = match arg {
+                    "low" => ReasoningEffort::Low,
+                    "high" => ReasoningEffort::High,
+                    _ => ReasoningEffort::Medium,
+                };
+                config = config.with_reasoning_effort(effort);
+                println!("Reasoning effort set to {}.", effort.as_str());
+            }
+            ("save", file) if !file.is_empty() => {
+                let json = client.export_conversation().to_json_string()?;
+                std::fs::write(file, json)?;
+                println!(
+                    "Saved {} messages to {}.",
+                    client.get_messages().len(),
+                    file
+                );
+            }
+            ("image", prompt) if !prompt.is_empty() => {
+                let results = client
+                    .generate_images(IMAGE_MODEL, prompt, 1, Path::new("output"), &config)
+                    .await;
+                for result in results {
+                    match result {
+                        Ok(image) => match image.path {
+                            Some(path) => println!("Saved image to {}.", path.display()),
+                            None => println!("Image at {}.", image.url),
+                        },
+                        Err(err) => println!("[error] {}", err),
+                    }
+                }
+            }
+            _ => println!("Unknown command or missing argument.\n{}", HELP),
+        }
+    }
+
+    Ok(())
+}
+
+async fn stream_reply(
+    client: &mut Client,
+    model: &str,
+    prompt: &str,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let stream = client.send_stream(
+        model,
+        Some(Message::new(Type::User, prompt.to_string())),
+        config,
+    );
+    pin_mut!(stream);
+
+    while let Some(delta) = stream.next().await {
+        match delta? {
+            StreamDelta::Text(text) => {
+                print!("{}", text);
+                std::io::stdout().flush()?;
+            }
+            StreamDelta::Image { url, .. } => println!("\n[image] {}", url),
+            StreamDelta::Citation(citation) => println!("\n[source] {}", citation.url),
+            StreamDelta::Reasoning(_) | StreamDelta::ToolOutput(_) => {}
+            StreamDelta::Done(_) => println!(),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use std::io::Write;
+
+use dotenv::dotenv;
+use futures_util::{StreamExt, pin_mut};
+use t3router::t3::client::{Client, StreamDelta, StreamEvent};
+use t3router::t3::message::{Message, Type};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    let cookies = std::env::var("COOKIES").expect("COOKIES not set");
+    let convex_session_id = std::env::var("CONVEX_SESSION_ID").expect("CONVEX_SESSION_ID not set");
+
+    let mut client = Client::new(cookies, convex_session_id);
+    client.init().await?;
+
+    println!("=== Streaming Chat ===\n");
+    print!("Assistant: ");
+    {
+        let stream = client.send_stream(
+            "gemini-2.5-flash-lite",
+            Some(Message::new(
+                Type::User,
+                "Tell me a short story about a lighthouse keeper.".to_string(),
+            )),
+            None,
+        );
+        pin_mut!(stream);
+
+        while let Some(delta) = stream.next().await {
+            match delta? {
+                StreamDelta::Text(text) => {
+                    print!("{}", text);
+                    std::io::stdout().flush()?;
+                }
+                StreamDelta::Reasoning(_) | StreamDelta::Citation(_) => {}
+                StreamDelta::Image { url, .. } => println!("\n[image] {}", url),
+                StreamDelta::ToolOutput(_) => {}
+                StreamDelta::Done(message) => {
+                    println!("\n\nReceived {} characters.", message.content().len());
+                }
+            }
+        }
+    }
+
+    println!(
+        "Messages in conversation: {}\n",
+        client.get_messages().len()
+    );
+
+    println!("=== Callback Streaming ===\n");
+    print!("Assistant: ");
+    let message = client
+        .send_with_callback(
+            "gemini-2.5-flash-lite",
+            Some(Message::new(Type::User, "Now give it a title.".to_string())),
+            None,
+            |event| {
+                if let StreamEvent::Text(text) = event {
+                    print!("{}", text);
+                    let _ = std::io::stdout().flush();
+                }
+            },
+        )
+        .await?;
+    println!("\n\nFinal message: {}", message.content());
+
+    Ok(())
+}
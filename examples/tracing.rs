@@ -0,0 +1,36 @@
+use dotenv::dotenv;
+use t3router::t3::client::Client;
+use t3router::t3::message::{Message, Type};
+use tracing_subscriber::EnvFilter;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Override with e.g. RUST_LOG=t3router=warn to only see retries and parse fallbacks.
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("t3router=debug")),
+        )
+        .init();
+
+    let cookies = std::env::var("COOKIES").expect("COOKIES not set");
+    let convex_session_id = std::env::var("CONVEX_SESSION_ID").expect("CONVEX_SESSION_ID not set");
+
+    let mut client = Client::new(cookies, convex_session_id);
+    client.init().await?;
+
+    let reply = client
+        .send(
+            "gemini-2.5-flash-lite",
+            Some(Message::new(
+                Type::User,
+                "What is the capital of France?".to_string(),
+            )),
+            None,
+        )
+        .await?;
+    println!("Assistant: {}", reply.content());
+
+    Ok(())
+}
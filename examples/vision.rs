@@ -0,0 +1,40 @@
+use dotenv::dotenv;
+use std::path::Path;
+use t3router::t3::{
+    attachment::Attachment,
+    client::Client,
+    message::{Message, Type},
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    let cookies = std::env::var("COOKIES").expect("COOKIES not set");
+    let convex_session_id = std::env::var("CONVEX_SESSION_ID").expect("CONVEX_SESSION_ID not set");
+    let image_path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "picture.png".to_string());
+
+    let mut client = Client::new(cookies, convex_session_id);
+    client.init().await?;
+
+    println!("=== Image Input ===\n");
+    let attachment = Attachment::from_path(Path::new(&image_path))?;
+    let response = client
+        .send(
+            "gemini-2.5-flash",
+            Some(Message::new_with_attachment(
+                Type::User,
+                "What is in this picture?".to_string(),
+                attachment,
+            )),
+            None,
+        )
+        .await?;
+
+    println!("User: What is in this picture? [{}]", image_path);
+    println!("Assistant: {}", response.content());
+
+    Ok(())
+}
@@ -0,0 +1,23 @@
+#![no_main]
+
+use std::sync::OnceLock;
+
+use futures_util::FutureExt;
+use libfuzzer_sys::fuzz_target;
+use t3router::t3::client::Client;
+use t3router::t3::events::{SseParser, parse_events};
+
+/// Built once: creating a client sets up a whole HTTP client, which would dominate each run.
+static CLIENT: OnceLock<Client> = OnceLock::new();
+
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data);
+    parse_events(&text);
+    let client = CLIENT.get_or_init(|| Client::new("", String::new()));
+    let _ = client.parse_response(&text).now_or_never();
+    let split = data.first().map_or(0, |&b| b as usize).min(data.len());
+    let mut parser = SseParser::new();
+    parser.feed(&data[..split]);
+    parser.feed(&data[split..]);
+    parser.finish();
+});
@@ -0,0 +1,367 @@
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use futures_util::{StreamExt, pin_mut};
+use serde::Deserialize;
+use t3router::t3::client::{Client, StreamDelta};
+use t3router::t3::config::{Config, ReasoningEffort};
+use t3router::t3::error::T3Error;
+use t3router::t3::message::{Message, Type};
+
+/// The model used for chat and ask when neither `--model` nor the config file names one.
+const DEFAULT_CHAT_MODEL: &str = "gemini-2.5-flash-lite";
+
+/// The model used for image when `--model` does not name one.
+const DEFAULT_IMAGE_MODEL: &str = "gpt-image-1";
+
+/// Exit code for any failure without a more specific code.
+const EXIT_FAILURE: u8 = 1;
+
+/// Exit code for missing credentials and rejected or expired sessions.
+const EXIT_AUTH: u8 = 3;
+
+/// Exit code for rate limiting; retrying later may succeed.
+const EXIT_RATE_LIMITED: u8 = 4;
+
+/// Chat with t3.chat models from the terminal.
+///
+/// Credentials are read from the COOKIES and CONVEX_SESSION_ID environment variables, falling
+/// back to `cookies` and `convex_session_id` in ~/.config/t3router/config.toml.
+#[derive(Debug, Parser)]
+#[command(name = "t3", version, after_help = EXIT_CODES)]
+struct Cli {
+    /// Read credentials and defaults from this file instead of the default config path
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+const EXIT_CODES: &str = "\
+Exit codes:
+  0  success
+  1  any other error
+  2  invalid arguments
+  3  missing credentials, or the session was rejected or has expired
+  4  rate limited";
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Start an interactive chat; each line you enter is sent and the reply streamed back
+    Chat(ChatArgs),
+    /// Ask a single question and stream the answer
+    Ask {
+        /// The question
+        #[arg(required = true, num_args = 1..)]
+        prompt: Vec<String>,
+
+        #[command(flatten)]
+        chat: ChatArgs,
+    },
+    /// List the models available to the account
+    Models,
+    /// Generate an image and save it to a file
+    Image {
+        /// What to draw
+        #[arg(required = true, num_args = 1..)]
+        prompt: Vec<String>,
+
+        /// Where to save the image
+        #[arg(short, long, value_name = "PATH", default_value = "image.png")]
+        output: PathBuf,
+
+        /// The image model to use [default: gpt-image-1]
+        #[arg(short, long)]
+        model: Option<String>,
+    },
+}
+
+#[derive(Debug, Args)]
+struct ChatArgs {
+    /// The model to use [default: the config file's model, or gemini-2.5-flash-lite]
+    #[arg(short, long)]
+    model: Option<String>,
+
+    /// Let the model search the web
+    #[arg(long)]
+    search: bool,
+
+    /// How much the model should reason before answering
+    #[arg(long, value_enum, default_value_t = Effort::Low)]
+    effort: Effort,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Effort {
+    Low,
+    Medium,
+    High,
+}
+
+impl From<Effort> for ReasoningEffort {
+    fn from(effort: Effort) -> Self {
+        match effort {
+            Effort::Low => ReasoningEffort::Low,
+            Effort::Medium => ReasoningEffort::Medium,
+            Effort::High => ReasoningEffort::High,
+        }
+    }
+}
+
+impl ChatArgs {
+    ///
+    /// Builds the request config from the flags.
+    ///
+    /// # Returns
+    /// * `Config` - The config with search and reasoning effort set.
+    fn config(&self) -> Config {
+        Config::new()
+            .with_search(self.search)
+            .with_reasoning_effort(self.effort.into())
+    }
+}
+
+/// The contents of `config.toml`. Every key is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FileConfig {
+    cookies: Option<String>,
+    convex_session_id: Option<String>,
+    model: Option<String>,
+}
+
+impl FileConfig {
+    ///
+    /// Reads the config file. A missing file at the default path is an empty config; a missing
+    /// file named by `--config` is an error.
+    ///
+    /// # Arguments
+    /// * `path`: `Option<&Path>` - The path given with `--config`.
+    ///
+    /// # Returns
+    /// * `Result<FileConfig, String>` - The config, or a message saying why it could not be read.
+    fn load(path: Option<&Path>) -> Result<FileConfig, String> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match default_config_path() {
+                Some(path) => (path, false),
+                None => return Ok(FileConfig::default()),
+            },
+        };
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if !required && err.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(FileConfig::default());
+            }
+            Err(err) => return Err(format!("cannot read {}: {}", path.display(), err)),
+        };
+        toml::from_str(&contents).map_err(|err| format!("invalid {}: {}", path.display(), err))
+    }
+}
+
+///
+/// Finds `t3router/config.toml` under `XDG_CONFIG_HOME`, or under `~/.config` when it is unset.
+///
+/// # Returns
+/// * `Option<PathBuf>` - The path, or `None` if neither variable is set.
+fn default_config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("t3router").join("config.toml"))
+}
+
+/// A failure reported to the shell: the message printed and the process exit code.
+struct Failure {
+    message: String,
+    code: u8,
+}
+
+impl From<T3Error> for Failure {
+    fn from(err: T3Error) -> Self {
+        Failure {
+            code: exit_code(&err),
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<std::io::Error> for Failure {
+    fn from(err: std::io::Error) -> Self {
+        T3Error::from(err).into()
+    }
+}
+
+///
+/// Picks the exit code for an error, looking through interruptions to their cause.
+///
+/// # Arguments
+/// * `err`: `&T3Error` - The error.
+///
+/// # Returns
+/// * `u8` - `EXIT_AUTH`, `EXIT_RATE_LIMITED` or `EXIT_FAILURE`.
+fn exit_code(err: &T3Error) -> u8 {
+    match err {
+        T3Error::Auth(_) | T3Error::SessionExpired { .. } | T3Error::MissingCookies(_) => EXIT_AUTH,
+        T3Error::Status { status: 401 | 403, .. } => EXIT_AUTH,
+        T3Error::RateLimited { .. } | T3Error::Status { status: 429, .. } => EXIT_RATE_LIMITED,
+        T3Error::Interrupted { source, .. } => exit_code(source),
+        _ => EXIT_FAILURE,
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => {
+            eprintln!("t3: {}", failure.message);
+            ExitCode::from(failure.code)
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Failure> {
+    let file = FileConfig::load(cli.config.as_deref()).map_err(|message| Failure {
+        message,
+        code: EXIT_FAILURE,
+    })?;
+    let mut client = connect(&file).await?;
+    match cli.command {
+        Command::Chat(args) => chat(&mut client, &file, &args).await,
+        Command::Ask { prompt, chat } => {
+            let model = chat_model(&file, &chat);
+            stream_reply(&mut client, &model, prompt.join(" "), chat.config()).await
+        }
+        Command::Models => {
+            for model in client.models().get_models().await? {
+                println!("{}\t{}", model.id, model.name);
+            }
+            Ok(())
+        }
+        Command::Image {
+            prompt,
+            output,
+            model,
+        } => {
+            let model = model.unwrap_or_else(|| DEFAULT_IMAGE_MODEL.to_string());
+            let message = Message::new(Type::User, prompt.join(" "));
+            let reply = client
+                .send_with_image_download(&*model, Some(message), None, Some(&output))
+                .await?;
+            if reply.image_url().is_none() {
+                return Err(T3Error::TextReply {
+                    text: reply.content().to_string(),
+                }
+                .into());
+            }
+            println!("{}", output.display());
+            Ok(())
+        }
+    }
+}
+
+///
+/// Builds a client from the environment or the config file and starts its session.
+///
+/// # Arguments
+/// * `file`: `&FileConfig` - The config file, used for values missing from the environment.
+///
+/// # Returns
+/// * `Result<Client, Failure>` - The client, or `EXIT_AUTH` if no credentials were found.
+async fn connect(file: &FileConfig) -> Result<Client, Failure> {
+    let cookies = std::env::var("COOKIES").ok().or_else(|| file.cookies.clone());
+    let convex_session_id = std::env::var("CONVEX_SESSION_ID")
+        .ok()
+        .or_else(|| file.convex_session_id.clone());
+    let (Some(cookies), Some(convex_session_id)) = (cookies, convex_session_id) else {
+        return Err(Failure {
+            message: "no credentials: set COOKIES and CONVEX_SESSION_ID, or cookies and \
+                      convex_session_id in ~/.config/t3router/config.toml"
+                .to_string(),
+            code: EXIT_AUTH,
+        });
+    };
+    let client = Client::builder(cookies, convex_session_id)
+        .proxy_from_env()
+        .build()?;
+    client.init().await?;
+    Ok(client)
+}
+
+fn chat_model(file: &FileConfig, args: &ChatArgs) -> String {
+    args.model
+        .clone()
+        .or_else(|| file.model.clone())
+        .unwrap_or_else(|| DEFAULT_CHAT_MODEL.to_string())
+}
+
+///
+/// Reads prompts from stdin until end of input, streaming each reply. Replies stay in the
+/// conversation, so later prompts can refer to earlier answers.
+///
+/// # Arguments
+/// * `client`: `&mut Client` - The connected client.
+/// * `file`: `&FileConfig` - The config file, for the default model.
+/// * `args`: `&ChatArgs` - The model, search and effort flags.
+///
+/// # Returns
+/// * `Result<(), Failure>` - An error from stdin or from a send.
+async fn chat(client: &mut Client, file: &FileConfig, args: &ChatArgs) -> Result<(), Failure> {
+    let model = chat_model(file, args);
+    let config = args.config();
+    eprintln!("Chatting with {}. Press Ctrl-D to quit.", model);
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        eprint!("> ");
+        std::io::stderr().flush()?;
+        let Some(line) = lines.next() else {
+            eprintln!();
+            return Ok(());
+        };
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        stream_reply(client, &model, line, config.clone()).await?;
+    }
+}
+
+///
+/// Sends one prompt and prints the reply to stdout as it arrives.
+///
+/// # Arguments
+/// * `client`: `&mut Client` - The connected client.
+/// * `model`: `&str` - The model ID.
+/// * `prompt`: `String` - The prompt.
+/// * `config`: `Config` - The request config.
+///
+/// # Returns
+/// * `Result<(), Failure>` - An error from the send or from writing to stdout.
+async fn stream_reply(
+    client: &mut Client,
+    model: &str,
+    prompt: String,
+    config: Config,
+) -> Result<(), Failure> {
+    let stream = client.send_stream(model, Some(Message::new(Type::User, prompt)), config);
+    pin_mut!(stream);
+    let mut stdout = std::io::stdout();
+    while let Some(delta) = stream.next().await {
+        match delta? {
+            StreamDelta::Text(text) => {
+                stdout.write_all(text.as_bytes())?;
+                stdout.flush()?;
+            }
+            StreamDelta::Image { url, .. } => writeln!(stdout, "[image] {}", url)?,
+            StreamDelta::Reasoning(_) | StreamDelta::Citation(_) | StreamDelta::ToolOutput(_) => {}
+            StreamDelta::Done(_) => writeln!(stdout)?,
+        }
+    }
+    Ok(())
+}
@@ -1 +1,31 @@
 pub mod t3;
+
+#[cfg(feature = "server")]
+pub mod serve;
+
+use t3::client::Client;
+use t3::error::T3Error;
+use t3::models::ModelId;
+
+///
+/// Asks a single question with a new client and returns the text of the reply, for scripts that
+/// need nothing else. Build a `Client` to send more than one message.
+///
+/// # Arguments
+/// * `cookies`: `&str` - The t3.chat cookies, as a header string.
+/// * `convex_session_id`: `&str` - The Convex session ID.
+/// * `model`: `impl Into<ModelId>` - The model to use, as a `KnownModel` or an ID string.
+/// * `prompt`: `&str` - The question.
+///
+/// # Returns
+/// * `Result<String, T3Error>` - The reply text, or an error as from `Client::ask`.
+pub async fn ask(
+    cookies: &str,
+    convex_session_id: &str,
+    model: impl Into<ModelId>,
+    prompt: &str,
+) -> Result<String, T3Error> {
+    let client = Client::builder(cookies, convex_session_id.to_string()).build()?;
+    client.init().await?;
+    client.ask(model, prompt).await
+}
@@ -0,0 +1,203 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use async_stream::stream;
+use axum::extract::State;
+use axum::extract::rejection::JsonRejection;
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures_util::{StreamExt, pin_mut};
+use serde_json::{Value, json};
+use tokio::net::{TcpListener, ToSocketAddrs};
+
+use crate::t3::client::Client;
+use crate::t3::error::T3Error;
+use crate::t3::models::ModelsClient;
+use crate::t3::openai_compat::ChatCompletionRequest;
+
+/// The address the example server listens on.
+pub const DEFAULT_ADDR: &str = "127.0.0.1:8787";
+
+/// An OpenAI-compatible HTTP server backed by a t3.chat account. Serves
+/// `POST /v1/chat/completions`, streamed as SSE when `stream` is set, and `GET /v1/models`.
+/// The API key sent by clients is ignored. Every request runs on its own copy of the client,
+/// so requests never share conversation history and can run concurrently.
+pub struct Server {
+    client: Client,
+    models: ModelsClient,
+}
+
+impl Server {
+    ///
+    /// Creates a server from a configured client and a models client.
+    ///
+    /// # Arguments
+    /// * `client`: `Client` - The client each request is copied from. Its default config,
+    ///   system prompt and settings apply to every request.
+    /// * `models`: `ModelsClient` - Serves `GET /v1/models`.
+    ///
+    /// # Returns
+    /// * `Server` - The server.
+    pub fn new(client: Client, models: ModelsClient) -> Self {
+        Server { client, models }
+    }
+
+    ///
+    /// Builds the router, for mounting the API into an existing axum application.
+    ///
+    /// # Returns
+    /// * `Router` - The router with the `/v1` routes.
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/v1/chat/completions", post(chat_completions))
+            .route("/v1/models", get(list_models))
+            .with_state(Arc::new(self))
+    }
+
+    ///
+    /// Listens on `addr` until `shutdown` completes. In-flight requests, including open
+    /// streams, are allowed to finish before this returns.
+    ///
+    /// # Arguments
+    /// * `addr`: `impl ToSocketAddrs` - The address to listen on, such as `DEFAULT_ADDR`.
+    /// * `shutdown`: `impl Future<Output = ()>` - Resolves when the server should stop,
+    ///   such as `tokio::signal::ctrl_c`.
+    ///
+    /// # Returns
+    /// * `Result<(), T3Error>` - An error if the address cannot be bound.
+    pub async fn serve(
+        self,
+        addr: impl ToSocketAddrs,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> Result<(), T3Error> {
+        let listener = TcpListener::bind(addr).await?;
+        axum::serve(listener, self.router())
+            .with_graceful_shutdown(shutdown)
+            .await?;
+        Ok(())
+    }
+
+    ///
+    /// Copies the client for one request, refreshing the shared session first when its
+    /// expiry is known to be close, so the refreshed cookies are kept for later requests.
+    ///
+    /// # Returns
+    /// * `Result<Client, T3Error>` - The client for the request.
+    async fn request_client(&self) -> Result<Client, T3Error> {
+        if self.client.session_expires_at().is_some() {
+            self.client.ensure_session().await?;
+        }
+        Ok(self.client.clone())
+    }
+}
+
+async fn chat_completions(
+    State(server): State<Arc<Server>>,
+    body: Result<Json<ChatCompletionRequest>, JsonRejection>,
+) -> Response {
+    let request = match body {
+        Ok(Json(request)) => request,
+        Err(rejection) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "invalid_request_error",
+                &rejection.body_text(),
+            );
+        }
+    };
+    let mut client = match server.request_client().await {
+        Ok(client) => client,
+        Err(err) => return t3_error_response(&err),
+    };
+    if !request.stream {
+        return match client.send_openai(request).await {
+            Ok(completion) => Json(completion).into_response(),
+            Err(err) => t3_error_response(&err),
+        };
+    }
+    let events = stream! {
+        let chunks = client.send_openai_stream(request);
+        pin_mut!(chunks);
+        while let Some(chunk) = chunks.next().await {
+            match chunk {
+                Ok(chunk) => yield Event::default().json_data(chunk),
+                Err(err) => {
+                    let (_, kind) = error_kind(&err);
+                    yield Ok(Event::default().data(error_json(kind, &err.to_string()).to_string()));
+                    return;
+                }
+            }
+        }
+        yield Ok(Event::default().data("[DONE]"));
+    };
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+async fn list_models(State(server): State<Arc<Server>>) -> Response {
+    let models = match server.models.get_models().await {
+        Ok(models) => models,
+        Err(err) => return t3_error_response(&err),
+    };
+    let data: Vec<Value> = models
+        .iter()
+        .filter(|model| !model.disabled)
+        .map(|model| {
+            json!({
+                "id": model.id,
+                "object": "model",
+                "created": 0,
+                "owned_by": model.provider,
+            })
+        })
+        .collect();
+    Json(json!({ "object": "list", "data": data })).into_response()
+}
+
+///
+/// Picks the HTTP status and OpenAI error type for an error.
+///
+/// # Arguments
+/// * `err`: `&T3Error` - The error.
+///
+/// # Returns
+/// * `(StatusCode, &'static str)` - The status and the error `type`.
+fn error_kind(err: &T3Error) -> (StatusCode, &'static str) {
+    match err {
+        T3Error::InvalidRequest(_)
+        | T3Error::EmptyConversation
+        | T3Error::InvalidImageOptions(_)
+        | T3Error::UnsupportedAttachment(_)
+        | T3Error::AttachmentTooLarge { .. } => (StatusCode::BAD_REQUEST, "invalid_request_error"),
+        T3Error::UnknownModel { .. } => (StatusCode::NOT_FOUND, "invalid_request_error"),
+        T3Error::Auth(_) | T3Error::SessionExpired { .. } | T3Error::MissingCookies(_) => {
+            (StatusCode::UNAUTHORIZED, "authentication_error")
+        }
+        T3Error::RateLimited { .. } => (StatusCode::TOO_MANY_REQUESTS, "rate_limit_error"),
+        T3Error::Timeout { .. } => (StatusCode::GATEWAY_TIMEOUT, "api_error"),
+        _ => (StatusCode::BAD_GATEWAY, "api_error"),
+    }
+}
+
+fn error_json(kind: &str, message: &str) -> Value {
+    json!({
+        "error": {
+            "message": message,
+            "type": kind,
+            "code": null,
+        }
+    })
+}
+
+fn t3_error_response(err: &T3Error) -> Response {
+    let (status, kind) = error_kind(err);
+    error_response(status, kind, &err.to_string())
+}
+
+fn error_response(status: StatusCode, kind: &str, message: &str) -> Response {
+    (status, Json(error_json(kind, message))).into_response()
+}
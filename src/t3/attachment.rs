@@ -0,0 +1,292 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use base64::{Engine as _, engine::general_purpose};
+use serde::{Deserialize, Serialize};
+
+use super::error::T3Error;
+use super::message::Message;
+
+/// MIME types accepted for image attachments.
+pub const SUPPORTED_IMAGE_TYPES: &[&str] = &["image/png", "image/jpeg", "image/webp", "image/gif"];
+
+/// MIME types accepted for document attachments.
+pub const SUPPORTED_DOCUMENT_TYPES: &[&str] = &["application/pdf", "text/plain", "text/markdown"];
+
+/// Largest attachment a `Client` sends unless configured otherwise, in bytes.
+pub const DEFAULT_MAX_ATTACHMENT_SIZE: usize = 16 * 1024 * 1024;
+
+/// A file sent along with a message, such as an image for a vision model or a PDF to ask questions about.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Attachment {
+    pub file_name: String,
+    pub mime_type: String,
+    pub base64_data: String,
+}
+
+impl Attachment {
+    ///
+    /// Reads a file from disk and detects its MIME type from the extension.
+    ///
+    /// # Arguments
+    /// * `path`: `&Path` - The file to attach.
+    ///
+    /// # Returns
+    /// * `Result<Attachment, T3Error>` - The attachment, or an error if the file cannot be read or its type is unsupported.
+    pub fn from_path(path: &Path) -> Result<Self, T3Error> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        let mime_type = match extension.as_str() {
+            "png" => "image/png",
+            "jpg" | "jpeg" => "image/jpeg",
+            "webp" => "image/webp",
+            "gif" => "image/gif",
+            "pdf" => "application/pdf",
+            "txt" => "text/plain",
+            "md" | "markdown" => "text/markdown",
+            _ => {
+                return Err(T3Error::UnsupportedAttachment(format!(
+                    "unrecognised file extension {:?}",
+                    extension
+                )));
+            }
+        };
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("attachment")
+            .to_string();
+        let bytes = fs::read(path)?;
+        Self::from_bytes(&bytes, mime_type, &file_name)
+    }
+
+    ///
+    /// Creates an attachment from bytes already in memory.
+    ///
+    /// # Arguments
+    /// * `bytes`: `&[u8]` - The file contents.
+    /// * `mime_type`: `&str` - The MIME type of the file, e.g. `image/png`.
+    /// * `file_name`: `&str` - The name shown for the attachment.
+    ///
+    /// # Returns
+    /// * `Result<Attachment, T3Error>` - The attachment, or an error if the MIME type is unsupported.
+    pub fn from_bytes(bytes: &[u8], mime_type: &str, file_name: &str) -> Result<Self, T3Error> {
+        let attachment = Self {
+            file_name: file_name.to_string(),
+            mime_type: mime_type.to_string(),
+            base64_data: general_purpose::STANDARD.encode(bytes),
+        };
+        attachment.validate()?;
+        Ok(attachment)
+    }
+
+    ///
+    /// Checks that the attachment has a MIME type t3.chat accepts.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The attachment.
+    ///
+    /// # Returns
+    /// * `Result<(), T3Error>` - `T3Error::UnsupportedAttachment` if the type is not supported.
+    pub fn validate(&self) -> Result<(), T3Error> {
+        let mime_type = self.mime_type.as_str();
+        if !SUPPORTED_IMAGE_TYPES.contains(&mime_type)
+            && !SUPPORTED_DOCUMENT_TYPES.contains(&mime_type)
+        {
+            return Err(T3Error::UnsupportedAttachment(format!(
+                "unsupported MIME type {:?}",
+                self.mime_type
+            )));
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the size of the attached file in bytes.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The attachment.
+    ///
+    /// # Returns
+    /// * `usize` - The decoded size of the file.
+    pub fn size(&self) -> usize {
+        let padding = self.base64_data.bytes().rev().take_while(|b| *b == b'=').count();
+        (self.base64_data.len() / 4 * 3).saturating_sub(padding)
+    }
+
+    ///
+    /// Returns whether the attachment is an image rather than a document.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The attachment.
+    ///
+    /// # Returns
+    /// * `bool` - `true` for image MIME types.
+    pub fn is_image(&self) -> bool {
+        self.mime_type.starts_with("image/")
+    }
+
+    ///
+    /// Returns the attachment encoded as a `data:` URL.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The attachment.
+    ///
+    /// # Returns
+    /// * `String` - The data URL.
+    pub fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.mime_type, self.base64_data)
+    }
+}
+
+/// The source image for `Client::edit_image`: a file, bytes in memory, or base64 data such as a
+/// previous reply's `Message::base64`. A `data:` URL is accepted as base64 too.
+#[derive(Clone, Debug)]
+pub enum ImageInput {
+    Path(PathBuf),
+    Bytes(Vec<u8>),
+    Base64(String),
+}
+
+impl ImageInput {
+    ///
+    /// Takes the image of a previous reply, so an edited image can be edited again.
+    ///
+    /// # Arguments
+    /// * `message`: `&Message` - A reply from an image model with its image data downloaded.
+    ///
+    /// # Returns
+    /// * `Result<ImageInput, T3Error>` - The image, or `T3Error::UnsupportedAttachment` if the
+    ///   message carries no image data.
+    pub fn from_message(message: &Message) -> Result<Self, T3Error> {
+        message
+            .base64()
+            .map(|base64| ImageInput::Base64(base64.to_string()))
+            .ok_or_else(|| {
+                T3Error::UnsupportedAttachment("message has no image data".to_string())
+            })
+    }
+
+    ///
+    /// Reads the image and turns it into an attachment, detecting the format from its first
+    /// bytes rather than from a file extension.
+    ///
+    /// # Arguments
+    /// * `self`: `Self` - The image input.
+    ///
+    /// # Returns
+    /// * `Result<Attachment, T3Error>` - The attachment, or `T3Error::UnsupportedAttachment` if
+    ///   the data is not a PNG, JPEG, WebP or GIF image.
+    pub fn into_attachment(self) -> Result<Attachment, T3Error> {
+        let (bytes, file_name) = match self {
+            ImageInput::Path(path) => {
+                let file_name = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or("image")
+                    .to_string();
+                (fs::read(&path)?, file_name)
+            }
+            ImageInput::Bytes(bytes) => (bytes, "image".to_string()),
+            ImageInput::Base64(data) => {
+                let data = data
+                    .strip_prefix("data:")
+                    .and_then(|rest| rest.split_once("base64,"))
+                    .map_or(data.as_str(), |(_, data)| data);
+                let bytes = general_purpose::STANDARD.decode(data.trim()).map_err(|_| {
+                    T3Error::UnsupportedAttachment("image input is not valid base64".to_string())
+                })?;
+                (bytes, "image".to_string())
+            }
+        };
+        let mime_type = sniff_image_type(&bytes).ok_or_else(|| {
+            T3Error::UnsupportedAttachment(
+                "image input is not a PNG, JPEG, WebP or GIF image".to_string(),
+            )
+        })?;
+        Attachment::from_bytes(&bytes, mime_type, &file_name)
+    }
+}
+
+impl From<&Path> for ImageInput {
+    fn from(path: &Path) -> Self {
+        ImageInput::Path(path.to_path_buf())
+    }
+}
+
+impl From<PathBuf> for ImageInput {
+    fn from(path: PathBuf) -> Self {
+        ImageInput::Path(path)
+    }
+}
+
+impl From<Vec<u8>> for ImageInput {
+    fn from(bytes: Vec<u8>) -> Self {
+        ImageInput::Bytes(bytes)
+    }
+}
+
+///
+/// Detects an image format from the magic bytes at the start of the data.
+///
+/// # Arguments
+/// * `bytes`: `&[u8]` - The file contents.
+///
+/// # Returns
+/// * `Option<&'static str>` - The MIME type for PNG, JPEG, WebP and GIF data, otherwise `None`.
+pub fn sniff_image_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+///
+/// Names the type of a file from its first bytes, for errors about files that are not a
+/// supported image.
+///
+/// # Arguments
+/// * `bytes`: `&[u8]` - The file contents.
+///
+/// # Returns
+/// * `&'static str` - A MIME type, `text/plain` for other UTF-8 text, or
+///   `application/octet-stream` when nothing matches.
+pub fn detect_file_type(bytes: &[u8]) -> &'static str {
+    if let Some(mime_type) = sniff_image_type(bytes) {
+        return mime_type;
+    }
+    let text = std::str::from_utf8(&bytes[..bytes.len().min(512)])
+        .ok()
+        .map(str::trim_start);
+    if bytes.starts_with(b"%PDF-") {
+        "application/pdf"
+    } else if bytes.starts_with(b"PK\x03\x04") {
+        "application/zip"
+    } else if bytes.starts_with(b"BM") {
+        "image/bmp"
+    } else if bytes.starts_with(b"II*\0") || bytes.starts_with(b"MM\0*") {
+        "image/tiff"
+    } else if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+        match &bytes[8..12] {
+            b"avif" | b"avis" => "image/avif",
+            b"heic" | b"heix" | b"mif1" => "image/heic",
+            _ => "video/mp4",
+        }
+    } else if text.is_some_and(|text| text.starts_with("<svg") || text.starts_with("<?xml")) {
+        "image/svg+xml"
+    } else if text.is_some() && !bytes.is_empty() {
+        "text/plain"
+    } else {
+        "application/octet-stream"
+    }
+}
@@ -0,0 +1,91 @@
+use futures_util::StreamExt;
+use futures_util::future::BoxFuture;
+use futures_util::stream::BoxStream;
+
+use super::client::{Client, StreamDelta};
+use super::config::Config;
+use super::error::T3Error;
+use super::message::Message;
+use super::models::ModelId;
+use super::thread::ThreadId;
+
+/// The conversation operations of `Client`, as a trait so application code can take
+/// `Box<dyn ChatBackend>` and be tested against `testing::MockBackend` instead of t3.chat.
+/// The async methods return boxed futures and streams to keep the trait object-safe.
+pub trait ChatBackend: Send {
+    ///
+    /// Sends the conversation, with `new_message` appended, and returns the reply.
+    ///
+    /// # Arguments
+    /// * `model`: `ModelId` - The model to use.
+    /// * `new_message`: `Option<Message>` - Optional new message to append before sending.
+    /// * `config`: `Option<Config>` - Optional configuration for the request.
+    ///
+    /// # Returns
+    /// * `BoxFuture<Result<Message, T3Error>>` - The assistant's reply or an error.
+    fn send(
+        &mut self,
+        model: ModelId,
+        new_message: Option<Message>,
+        config: Option<Config>,
+    ) -> BoxFuture<'_, Result<Message, T3Error>>;
+
+    ///
+    /// Sends the conversation like `send` and streams the reply, ending with
+    /// `StreamDelta::Done`.
+    ///
+    /// # Arguments
+    /// * `model`: `ModelId` - The model to use.
+    /// * `new_message`: `Option<Message>` - Optional new message to append before sending.
+    /// * `config`: `Option<Config>` - Optional configuration for the request.
+    ///
+    /// # Returns
+    /// * `BoxStream<Result<StreamDelta, T3Error>>` - The reply deltas in arrival order.
+    fn send_stream(
+        &mut self,
+        model: ModelId,
+        new_message: Option<Message>,
+        config: Option<Config>,
+    ) -> BoxStream<'_, Result<StreamDelta, T3Error>>;
+
+    /// Starts a new conversation.
+    fn new_conversation(&mut self);
+
+    /// Returns the thread of the current conversation, if a message has been sent.
+    fn thread_id(&self) -> Option<ThreadId>;
+
+    /// Returns the messages of the current conversation.
+    fn messages(&self) -> &[Message];
+}
+
+impl ChatBackend for Client {
+    fn send(
+        &mut self,
+        model: ModelId,
+        new_message: Option<Message>,
+        config: Option<Config>,
+    ) -> BoxFuture<'_, Result<Message, T3Error>> {
+        Box::pin(Client::send(self, model, new_message, config))
+    }
+
+    fn send_stream(
+        &mut self,
+        model: ModelId,
+        new_message: Option<Message>,
+        config: Option<Config>,
+    ) -> BoxStream<'_, Result<StreamDelta, T3Error>> {
+        Client::send_stream(self, model, new_message, config).boxed()
+    }
+
+    fn new_conversation(&mut self) {
+        Client::new_conversation(self);
+    }
+
+    fn thread_id(&self) -> Option<ThreadId> {
+        self.get_thread_id().copied()
+    }
+
+    fn messages(&self) -> &[Message] {
+        self.get_messages()
+    }
+}
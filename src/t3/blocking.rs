@@ -0,0 +1,316 @@
+use std::path::Path;
+
+use tokio::runtime::{Builder, Runtime};
+
+use super::attachment::ImageInput;
+use super::client::{
+    self, ChatResponse, DownloadOptions, DownloadedImage, Response, SessionRefresh, StreamEvent,
+};
+use super::config::Config;
+use super::cookies::CookieJar;
+use super::error::T3Error;
+use super::history::ThreadSummary;
+use super::message::{GeneratedImage, Message};
+use super::models::ModelId;
+use super::openai_compat::{ChatCompletionRequest, ChatCompletionResponse};
+use super::settings::{Settings, SettingsPatch};
+use super::thread::ThreadId;
+use super::usage::{AccountInfo, ByokStatus, UsageInfo};
+
+/// A blocking wrapper around the async `Client` for callers without a tokio runtime. It owns
+/// a current-thread runtime and blocks on each call, so it must not be used from inside an
+/// async context. Messages, configs and errors are the same types as the async client's.
+pub struct Client {
+    inner: client::Client,
+    runtime: Runtime,
+}
+
+impl Client {
+    ///
+    /// Creates a blocking client with the default HTTP settings.
+    ///
+    /// # Arguments
+    /// * `cookies`: `impl Into<CookieJar>` - The t3.chat cookies.
+    /// * `convex_session_id`: `String` - The Convex session ID.
+    ///
+    /// # Returns
+    /// * `Result<Client, T3Error>` - The client, or an error if the runtime cannot be created.
+    pub fn new(cookies: impl Into<CookieJar>, convex_session_id: String) -> Result<Self, T3Error> {
+        Self::from_async(client::Client::new(cookies, convex_session_id))
+    }
+
+    ///
+    /// Wraps an async client, such as one configured with `client::Client::builder`.
+    ///
+    /// # Arguments
+    /// * `inner`: `client::Client` - The client to wrap.
+    ///
+    /// # Returns
+    /// * `Result<Client, T3Error>` - The client, or an error if the runtime cannot be created.
+    pub fn from_async(inner: client::Client) -> Result<Self, T3Error> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(Client { inner, runtime })
+    }
+
+    ///
+    /// Gets the wrapped async client, for its non-async getters and setters.
+    ///
+    /// # Returns
+    /// * `&client::Client` - The async client.
+    pub fn inner(&self) -> &client::Client {
+        &self.inner
+    }
+
+    ///
+    /// Gets the wrapped async client mutably, for its non-async setters such as
+    /// `set_default_config` or `set_context_limit`.
+    ///
+    /// # Returns
+    /// * `&mut client::Client` - The async client.
+    pub fn inner_mut(&mut self) -> &mut client::Client {
+        &mut self.inner
+    }
+
+    ///
+    /// Unwraps the async client, dropping the runtime.
+    ///
+    /// # Returns
+    /// * `client::Client` - The async client.
+    pub fn into_inner(self) -> client::Client {
+        self.inner
+    }
+
+    /// See `client::Client::init`.
+    pub fn init(&self) -> Result<bool, T3Error> {
+        self.runtime.block_on(self.inner.init())
+    }
+
+    /// See `client::Client::send`.
+    pub fn send(
+        &mut self,
+        model: impl Into<ModelId>,
+        new_message: Option<Message>,
+        config: impl Into<Option<Config>>,
+    ) -> Result<Message, T3Error> {
+        self.runtime
+            .block_on(self.inner.send(model, new_message, config))
+    }
+
+    /// See `client::Client::send_detailed`.
+    pub fn send_detailed(
+        &mut self,
+        model: impl Into<ModelId>,
+        new_message: Option<Message>,
+        config: impl Into<Option<Config>>,
+    ) -> Result<Response, T3Error> {
+        self.runtime
+            .block_on(self.inner.send_detailed(model, new_message, config))
+    }
+
+    /// See `client::Client::send_with`.
+    pub fn send_with<F>(
+        &mut self,
+        model: impl Into<ModelId>,
+        new_message: Option<Message>,
+        overrides: F,
+    ) -> Result<Message, T3Error>
+    where
+        F: FnOnce(&mut Config),
+    {
+        self.runtime
+            .block_on(self.inner.send_with(model, new_message, overrides))
+    }
+
+    /// See `client::Client::ask`.
+    pub fn ask(&self, model: impl Into<ModelId>, prompt: &str) -> Result<String, T3Error> {
+        self.runtime.block_on(self.inner.ask(model, prompt))
+    }
+
+    /// See `client::Client::send_batch`.
+    pub fn send_batch(
+        &self,
+        model: impl Into<ModelId>,
+        prompts: Vec<Message>,
+        config: impl Into<Option<Config>>,
+        max_concurrency: usize,
+    ) -> Vec<Result<Message, T3Error>> {
+        self.runtime.block_on(
+            self.inner
+                .send_batch(model, prompts, config, max_concurrency),
+        )
+    }
+
+    /// See `client::Client::generate_images`.
+    pub fn generate_images(
+        &self,
+        model: impl Into<ModelId>,
+        prompt: &str,
+        count: usize,
+        save_dir: &Path,
+        config: impl Into<Option<Config>>,
+    ) -> Vec<Result<GeneratedImage, T3Error>> {
+        self.runtime.block_on(
+            self.inner
+                .generate_images(model, prompt, count, save_dir, config),
+        )
+    }
+
+    /// See `client::Client::send_default`.
+    pub fn send_default(&mut self, new_message: Option<Message>) -> Result<Message, T3Error> {
+        self.runtime.block_on(self.inner.send_default(new_message))
+    }
+
+    /// See `client::Client::send_with_callback`. The callback runs on the calling thread.
+    pub fn send_with_callback<F>(
+        &mut self,
+        model: impl Into<ModelId>,
+        new_message: Option<Message>,
+        config: impl Into<Option<Config>>,
+        callback: F,
+    ) -> Result<Message, T3Error>
+    where
+        F: FnMut(StreamEvent),
+    {
+        self.runtime.block_on(
+            self.inner
+                .send_with_callback(model, new_message, config, callback),
+        )
+    }
+
+    /// See `client::Client::send_with_credits`.
+    pub fn send_with_credits(
+        &mut self,
+        model: impl Into<ModelId>,
+        new_message: Option<Message>,
+        config: impl Into<Option<Config>>,
+    ) -> Result<ChatResponse, T3Error> {
+        self.runtime
+            .block_on(self.inner.send_with_credits(model, new_message, config))
+    }
+
+    /// See `client::Client::send_with_image_download`.
+    pub fn send_with_image_download(
+        &mut self,
+        model: impl Into<ModelId>,
+        new_message: Option<Message>,
+        config: impl Into<Option<Config>>,
+        save_path: Option<&Path>,
+    ) -> Result<Message, T3Error> {
+        self.runtime.block_on(self.inner.send_with_image_download(
+            model,
+            new_message,
+            config,
+            save_path,
+        ))
+    }
+
+    /// See `client::Client::edit_image`.
+    pub fn edit_image(
+        &mut self,
+        model: impl Into<ModelId>,
+        input: impl Into<ImageInput>,
+        prompt: &str,
+        config: impl Into<Option<Config>>,
+        save_path: Option<&Path>,
+    ) -> Result<Message, T3Error> {
+        self.runtime.block_on(
+            self.inner
+                .edit_image(model, input, prompt, config, save_path),
+        )
+    }
+
+    /// See `client::Client::send_openai`.
+    pub fn send_openai(
+        &mut self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionResponse, T3Error> {
+        self.runtime.block_on(self.inner.send_openai(request))
+    }
+
+    /// See `client::Client::download_image`.
+    pub fn download_image(&self, url: &str, save_path: Option<&Path>) -> Result<String, T3Error> {
+        self.runtime
+            .block_on(self.inner.download_image(url, save_path))
+    }
+
+    /// See `client::Client::download_image_with`.
+    pub fn download_image_with(
+        &self,
+        url: &str,
+        save_path: Option<&Path>,
+        options: DownloadOptions,
+    ) -> Result<DownloadedImage, T3Error> {
+        self.runtime
+            .block_on(self.inner.download_image_with(url, save_path, options))
+    }
+
+    /// See `client::Client::refresh_session`.
+    pub fn refresh_session(&mut self) -> Result<SessionRefresh, T3Error> {
+        self.runtime.block_on(self.inner.refresh_session())
+    }
+
+    /// See `client::Client::ensure_session`.
+    pub fn ensure_session(&mut self) -> Result<SessionRefresh, T3Error> {
+        self.runtime.block_on(self.inner.ensure_session())
+    }
+
+    /// See `client::Client::get_usage`.
+    pub fn get_usage(&self) -> Result<UsageInfo, T3Error> {
+        self.runtime.block_on(self.inner.get_usage())
+    }
+
+    /// See `client::Client::get_account`.
+    pub fn get_account(&self) -> Result<AccountInfo, T3Error> {
+        self.runtime.block_on(self.inner.get_account())
+    }
+
+    /// See `client::Client::get_settings`.
+    pub fn get_settings(&mut self) -> Result<Settings, T3Error> {
+        self.runtime.block_on(self.inner.get_settings())
+    }
+
+    /// See `client::Client::update_settings`.
+    pub fn update_settings(&mut self, patch: SettingsPatch) -> Result<Settings, T3Error> {
+        self.runtime.block_on(self.inner.update_settings(patch))
+    }
+
+    /// See `client::Client::get_byok_status`.
+    pub fn get_byok_status(&self) -> Result<ByokStatus, T3Error> {
+        self.runtime.block_on(self.inner.get_byok_status())
+    }
+
+    /// See `client::Client::list_threads`.
+    pub fn list_threads(&self, limit: Option<usize>) -> Result<Vec<ThreadSummary>, T3Error> {
+        self.runtime.block_on(self.inner.list_threads(limit))
+    }
+
+    /// See `client::Client::delete_thread`.
+    pub fn delete_thread(&self, thread_id: &ThreadId) -> Result<(), T3Error> {
+        self.runtime.block_on(self.inner.delete_thread(thread_id))
+    }
+
+    /// See `client::Client::new_conversation`.
+    pub fn new_conversation(&mut self) {
+        self.inner.new_conversation();
+    }
+
+    /// See `client::Client::set_system_prompt`.
+    pub fn set_system_prompt(&mut self, prompt: String) {
+        self.inner.set_system_prompt(prompt);
+    }
+
+    /// See `client::Client::append_message`.
+    pub fn append_message(&mut self, message: Message) {
+        self.inner.append_message(message);
+    }
+
+    /// See `client::Client::get_messages`.
+    pub fn get_messages(&self) -> &Vec<Message> {
+        self.inner.get_messages()
+    }
+
+    /// See `client::Client::get_thread_id`.
+    pub fn get_thread_id(&self) -> Option<&ThreadId> {
+        self.inner.get_thread_id()
+    }
+}
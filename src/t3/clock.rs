@@ -0,0 +1,49 @@
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+
+/// The time source a `Client` paces, backs off and checks session expiry with. Implement it to
+/// drive pacing from a fake clock in tests; `Client` uses `SystemClock` by default.
+pub trait Clock: Send + Sync {
+    ///
+    /// Gets the current time.
+    ///
+    /// # Returns
+    /// * `Instant` - The current time.
+    fn now(&self) -> Instant;
+
+    ///
+    /// Waits for a duration.
+    ///
+    /// # Arguments
+    /// * `duration`: `Duration` - How long to wait.
+    ///
+    /// # Returns
+    /// * `BoxFuture<'static, ()>` - Resolves once the duration has passed.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+
+    ///
+    /// Gets the current wall-clock time, which session expiry is checked against. A fake clock
+    /// overrides it to let sessions expire as its time passes.
+    ///
+    /// # Returns
+    /// * `DateTime<Utc>` - The current time.
+    fn utc_now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// The system clock, sleeping with the tokio timer.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
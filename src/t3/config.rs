@@ -1,4 +1,14 @@
-#[derive(Clone, Copy)]
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::error::T3Error;
+use super::history::CONVEX_URL;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ReasoningEffort {
     Low,
     Medium,
@@ -23,10 +33,471 @@ impl ReasoningEffort {
     }
 }
 
-#[derive(Clone)]
+/// Pause inserted between consecutive requests so scripted runs are paced like a person.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DelayPolicy {
+    Fixed(Duration),
+    Jittered { min: Duration, max: Duration },
+}
+
+impl DelayPolicy {
+    ///
+    /// Picks the delay to wait before the next request.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The delay policy.
+    ///
+    /// # Returns
+    /// * `Duration` - The fixed delay, or a uniformly random one between `min` and `max`.
+    pub fn next_delay(&self) -> Duration {
+        match *self {
+            DelayPolicy::Fixed(delay) => delay,
+            DelayPolicy::Jittered { min, max } => {
+                if max <= min {
+                    return min;
+                }
+                let span = (max - min).as_millis() as u64;
+                min + Duration::from_millis(fastrand::u64(0..=span))
+            }
+        }
+    }
+}
+
+/// What happens to generated image data when a reply is stored in the conversation history.
+/// The returned `Message` always keeps its data; only the stored copy is affected.
+/// Generated images are never sent back with later requests, whatever the policy.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryImagePolicy {
+    /// Keep the base64 data in memory.
+    #[default]
+    KeepData,
+    /// Drop the base64 data and keep only the image URL.
+    UrlOnly,
+    /// Write the data to files in this directory and record their paths on the message.
+    /// Inline `data:` URLs are replaced with `file://` URLs of the written files.
+    SpillToDir(PathBuf),
+}
+
+impl HistoryImagePolicy {
+    /// Spills image data to a `t3router-images` directory under the system temp directory.
+    ///
+    /// # Returns
+    /// - `HistoryImagePolicy`: The `SpillToDir` policy.
+    pub fn spill_to_temp() -> HistoryImagePolicy {
+        HistoryImagePolicy::SpillToDir(std::env::temp_dir().join("t3router-images"))
+    }
+}
+
+/// How `Client` shortens the history when it exceeds the context limit. System messages and
+/// the message being sent are never removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrimStrategy {
+    /// Remove the oldest messages one at a time until the history fits.
+    DropOldest,
+    /// Keep only the last `n` exchanges before the message being sent, each a user message and
+    /// the replies that follow it. If that still does not fit, continue with `DropOldest`.
+    KeepLastExchanges(usize),
+}
+
+/// Which transient failures a `RetryPolicy` retries. The defaults only cover failures where the
+/// request never reached the model or produced nothing, so a retry cannot duplicate a reply.
+/// `rate_limits` waits out the server's retry-after, however long, before retrying, and
+/// `expired_sessions` refreshes the session first; both are off by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryOn {
+    pub connect_errors: bool,
+    pub timeouts: bool,
+    pub gateway_errors: bool,
+    pub empty_responses: bool,
+    pub rate_limits: bool,
+    pub expired_sessions: bool,
+}
+
+impl Default for RetryOn {
+    fn default() -> Self {
+        RetryOn {
+            connect_errors: true,
+            timeouts: false,
+            gateway_errors: true,
+            empty_responses: true,
+            rate_limits: false,
+            expired_sessions: false,
+        }
+    }
+}
+
+/// Automatic retries with jittered exponential backoff for transient failures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub retry_on: RetryOn,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+            retry_on: RetryOn::default(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Picks the delay before a retry. The delay doubles with every attempt up to `max_delay`,
+    /// and a random amount of up to half of it is taken off so parallel clients spread out.
+    ///
+    /// # Arguments
+    /// - `attempt`: The number of retries already made, starting at `0`.
+    ///
+    /// # Returns
+    /// - `Duration`: The delay to wait before the next attempt.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        let jitter = (delay / 2).as_millis() as u64;
+        delay - Duration::from_millis(fastrand::u64(0..=jitter))
+    }
+
+    /// Picks the delay before retrying after an error: the retry-after of a rate limit when the
+    /// server sent one, none after an expired session, which is refreshed instead, and
+    /// `backoff` otherwise.
+    ///
+    /// # Arguments
+    /// - `attempt`: The number of retries already made, starting at `0`.
+    /// - `error`: The error returned by the failed attempt.
+    ///
+    /// # Returns
+    /// - `Duration`: The delay to wait before the next attempt.
+    pub fn delay_for(&self, attempt: u32, error: &T3Error) -> Duration {
+        match error {
+            T3Error::RateLimited {
+                retry_after: Some(delay),
+                ..
+            } => *delay,
+            T3Error::SessionExpired { .. } => Duration::ZERO,
+            _ => self.backoff(attempt),
+        }
+    }
+
+    /// Whether the error is a transient failure this policy retries. Other 4xx responses are
+    /// never retried, and rate limits and expired sessions only when `retry_on` enables them.
+    ///
+    /// # Arguments
+    /// - `error`: The error returned by the failed attempt.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the request should be tried again.
+    pub fn should_retry(&self, error: &T3Error) -> bool {
+        match error {
+            T3Error::Http(err) => {
+                (self.retry_on.connect_errors && err.is_connect())
+                    || (self.retry_on.timeouts && err.is_timeout())
+            }
+            T3Error::Status { status, .. } => {
+                self.retry_on.gateway_errors && matches!(status, 502..=504)
+            }
+            T3Error::EmptyResponse | T3Error::UnparsedResponse { .. } => {
+                self.retry_on.empty_responses
+            }
+            T3Error::RateLimited { .. } => self.retry_on.rate_limits,
+            T3Error::SessionExpired { .. } => self.retry_on.expired_sessions,
+            _ => false,
+        }
+    }
+}
+
+/// Web search settings sent in the request's `modelParams`. `extra` is merged into `modelParams`
+/// as is, so server-side options the crate does not know about yet can still be set.
+/// A `bool` converts into options with only `enabled` set.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchOptions {
+    pub enabled: bool,
+    pub max_results: Option<u32>,
+    pub extra: Map<String, Value>,
+}
+
+impl SearchOptions {
+    /// Creates search options with search enabled and the server's default result count.
+    ///
+    /// # Returns
+    /// - `SearchOptions`: The enabled options.
+    pub fn enabled() -> SearchOptions {
+        SearchOptions {
+            enabled: true,
+            ..SearchOptions::default()
+        }
+    }
+
+    /// Returns these options with a cap on the number of search results.
+    ///
+    /// # Arguments
+    /// - `max_results`: The most results the model may use.
+    ///
+    /// # Returns
+    /// - `SearchOptions`: The updated options.
+    pub fn with_max_results(mut self, max_results: u32) -> SearchOptions {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    /// Returns these options with an extra `modelParams` entry.
+    ///
+    /// # Arguments
+    /// - `key`: The `modelParams` key.
+    /// - `value`: The value to send.
+    ///
+    /// # Returns
+    /// - `SearchOptions`: The updated options.
+    pub fn with_param(mut self, key: &str, value: impl Into<Value>) -> SearchOptions {
+        self.extra.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Writes these options into a `modelParams` object. `includeSearch` and `searchLimit` are
+    /// set first, so an `extra` entry of the same name overrides them.
+    ///
+    /// # Arguments
+    /// - `params`: The `modelParams` object of the chat request.
+    pub(crate) fn apply_to(&self, params: &mut Map<String, Value>) {
+        params.insert("includeSearch".to_string(), Value::Bool(self.enabled));
+        params.insert(
+            "searchLimit".to_string(),
+            Value::from(self.max_results.unwrap_or(1)),
+        );
+        params.extend(self.extra.clone());
+    }
+}
+
+impl From<bool> for SearchOptions {
+    fn from(enabled: bool) -> Self {
+        SearchOptions {
+            enabled,
+            ..SearchOptions::default()
+        }
+    }
+}
+
+/// Most images t3.chat generates for a single request.
+pub const MAX_IMAGES_PER_REQUEST: u32 = 4;
+
+/// Render quality for image generation models.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageQuality {
+    Low,
+    Medium,
+    High,
+}
+
+impl ImageQuality {
+    ///
+    /// Returns the string value for the image quality.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The image quality variant.
+    ///
+    /// # Returns
+    /// * `&'static str` - The corresponding string value.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImageQuality::Low => "low",
+            ImageQuality::Medium => "medium",
+            ImageQuality::High => "high",
+        }
+    }
+}
+
+/// Image generation settings sent in the request's `modelParams`. Give either `size` in pixels
+/// or `aspect_ratio`, not both; unset fields use the model's defaults.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageOptions {
+    pub size: Option<(u32, u32)>,
+    pub aspect_ratio: Option<(u32, u32)>,
+    pub quality: Option<ImageQuality>,
+    pub n: Option<u32>,
+}
+
+impl ImageOptions {
+    /// Returns these options with an exact size in pixels.
+    ///
+    /// # Arguments
+    /// - `width`: The image width.
+    /// - `height`: The image height.
+    ///
+    /// # Returns
+    /// - `ImageOptions`: The updated options.
+    pub fn with_size(mut self, width: u32, height: u32) -> ImageOptions {
+        self.size = Some((width, height));
+        self
+    }
+
+    /// Returns these options with an aspect ratio, such as `16:9`.
+    ///
+    /// # Arguments
+    /// - `width`: The width part of the ratio.
+    /// - `height`: The height part of the ratio.
+    ///
+    /// # Returns
+    /// - `ImageOptions`: The updated options.
+    pub fn with_aspect_ratio(mut self, width: u32, height: u32) -> ImageOptions {
+        self.aspect_ratio = Some((width, height));
+        self
+    }
+
+    /// Returns these options with the given render quality.
+    ///
+    /// # Arguments
+    /// - `quality`: The render quality.
+    ///
+    /// # Returns
+    /// - `ImageOptions`: The updated options.
+    pub fn with_quality(mut self, quality: ImageQuality) -> ImageOptions {
+        self.quality = Some(quality);
+        self
+    }
+
+    /// Returns these options asking for several images.
+    ///
+    /// # Arguments
+    /// - `n`: How many images to generate, up to `MAX_IMAGES_PER_REQUEST`.
+    ///
+    /// # Returns
+    /// - `ImageOptions`: The updated options.
+    pub fn with_count(mut self, n: u32) -> ImageOptions {
+        self.n = Some(n);
+        self
+    }
+
+    /// Checks the options before they are sent.
+    ///
+    /// # Returns
+    /// - `Result<(), T3Error>`: `T3Error::InvalidImageOptions` for a zero dimension, both
+    ///   `size` and `aspect_ratio` set, or a count outside `1..=MAX_IMAGES_PER_REQUEST`.
+    pub fn validate(&self) -> Result<(), T3Error> {
+        let invalid = |reason: String| Err(T3Error::InvalidImageOptions(reason));
+        if self.size.is_some() && self.aspect_ratio.is_some() {
+            return invalid("set either size or aspect_ratio, not both".to_string());
+        }
+        if let Some((width, height)) = self.size
+            && (width == 0 || height == 0)
+        {
+            return invalid(format!("size {}x{} has a zero dimension", width, height));
+        }
+        if let Some((width, height)) = self.aspect_ratio
+            && (width == 0 || height == 0)
+        {
+            return invalid(format!("aspect ratio {}:{} has a zero side", width, height));
+        }
+        if let Some(n) = self.n
+            && !(1..=MAX_IMAGES_PER_REQUEST).contains(&n)
+        {
+            return invalid(format!(
+                "count {} is outside 1..={}",
+                n, MAX_IMAGES_PER_REQUEST
+            ));
+        }
+        Ok(())
+    }
+
+    /// Writes the options that are set into a `modelParams` object.
+    ///
+    /// # Arguments
+    /// - `params`: The `modelParams` object of the chat request.
+    pub(crate) fn apply_to(&self, params: &mut Map<String, Value>) {
+        if let Some((width, height)) = self.size {
+            params.insert(
+                "imageSize".to_string(),
+                Value::from(format!("{}x{}", width, height)),
+            );
+        }
+        if let Some((width, height)) = self.aspect_ratio {
+            params.insert(
+                "aspectRatio".to_string(),
+                Value::from(format!("{}:{}", width, height)),
+            );
+        }
+        if let Some(quality) = self.quality {
+            params.insert("imageQuality".to_string(), Value::from(quality.as_str()));
+        }
+        if let Some(n) = self.n {
+            params.insert("imageCount".to_string(), Value::from(n));
+        }
+    }
+}
+
+/// Whose provider API keys pay for a request. Pro accounts can attach their own OpenAI,
+/// Anthropic or OpenRouter keys on the t3.chat settings screen ("bring your own key").
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    /// t3.chat's own keys, counted against the plan's message allowance.
+    T3,
+    /// The account's own key for the model's provider, with that provider's rate limits.
+    Byok,
+}
+
+impl KeySource {
+    ///
+    /// Returns the value sent as `modelParams.apiKeySource`.
+    ///
+    /// # Returns
+    /// * `&'static str` - `"t3"` or `"byok"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeySource::T3 => "t3",
+            KeySource::Byok => "byok",
+        }
+    }
+}
+
+/// Per-request chat options. Start from `Config::default()` and chain the `with_*` setters;
+/// the struct is `non_exhaustive` so new options can be added without breaking callers.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
 pub struct Config {
+    pub search: SearchOptions,
+    #[deprecated(note = "use `search` or `with_search`")]
     pub include_search: bool,
     pub reasoning_effort: ReasoningEffort,
+    pub inter_request_delay: Option<DelayPolicy>,
+    pub ephemeral: bool,
+    pub thread_title: Option<String>,
+    pub image: Option<ImageOptions>,
+    pub max_duration: Option<Duration>,
+    pub commit_partial_on_timeout: bool,
+    pub api_key_source: Option<KeySource>,
+}
+
+impl Default for Config {
+    #[allow(deprecated)]
+    fn default() -> Self {
+        Config {
+            search: SearchOptions::default(),
+            include_search: false,
+            reasoning_effort: ReasoningEffort::Low,
+            inter_request_delay: None,
+            ephemeral: false,
+            thread_title: None,
+            image: None,
+            max_duration: None,
+            commit_partial_on_timeout: false,
+            api_key_source: None,
+        }
+    }
 }
 
 impl Config {
@@ -36,12 +507,328 @@ impl Config {
     /// - `Config`: A new configuration object with default values.
     ///
     /// # Default Values
-    /// - `include_search`: `false`
+    /// - `search`: search disabled
     /// - `reasoning_effort`: `ReasoningEffort::Low`
+    /// - `inter_request_delay`: `None` (use the client-level setting)
+    /// - `ephemeral`: `false`
+    /// - `thread_title`: `None` (let t3.chat generate one)
+    /// - `image`: `None` (use the image model's defaults)
+    /// - `max_duration`: `None` (no generation deadline)
+    /// - `commit_partial_on_timeout`: `false`
+    /// - `api_key_source`: `None` (use the account's setting)
     pub fn new() -> Config {
-        Config {
-            include_search: false,
-            reasoning_effort: ReasoningEffort::Low,
+        Config::default()
+    }
+
+    /// Returns this config with the given reasoning effort.
+    ///
+    /// # Arguments
+    /// - `reasoning_effort`: How much the model should reason before answering.
+    ///
+    /// # Returns
+    /// - `Config`: The updated configuration.
+    pub fn with_reasoning_effort(mut self, reasoning_effort: ReasoningEffort) -> Config {
+        self.reasoning_effort = reasoning_effort;
+        self
+    }
+
+    /// Returns this config with the given search settings. Pass a `bool` to only turn search
+    /// on or off.
+    ///
+    /// # Arguments
+    /// - `search`: The search settings, or whether the model may search the web.
+    ///
+    /// # Returns
+    /// - `Config`: The updated configuration.
+    pub fn with_search(mut self, search: impl Into<SearchOptions>) -> Config {
+        self.search = search.into();
+        self
+    }
+
+    /// The search settings to send, with search also enabled by the deprecated
+    /// `include_search` flag.
+    ///
+    /// # Returns
+    /// - `SearchOptions`: The effective search settings.
+    #[allow(deprecated)]
+    pub(crate) fn search_options(&self) -> SearchOptions {
+        SearchOptions {
+            enabled: self.search.enabled || self.include_search,
+            ..self.search.clone()
         }
     }
+
+    /// Returns this config with a pacing policy that overrides the client-level one.
+    ///
+    /// # Arguments
+    /// - `policy`: The pause to insert before this request.
+    ///
+    /// # Returns
+    /// - `Config`: The updated configuration.
+    pub fn with_inter_request_delay(mut self, policy: DelayPolicy) -> Config {
+        self.inter_request_delay = Some(policy);
+        self
+    }
+
+    /// Returns this config with `ephemeral` set, so a send leaves the conversation untouched
+    /// and, outside a thread, starts none.
+    ///
+    /// # Arguments
+    /// - `ephemeral`: Whether the exchange should be kept out of the conversation history.
+    ///
+    /// # Returns
+    /// - `Config`: The updated configuration.
+    pub fn ephemeral(mut self, ephemeral: bool) -> Config {
+        self.ephemeral = ephemeral;
+        self
+    }
+
+    /// Returns this config with a title for the thread. Only used by the first send of a new
+    /// conversation; titles over `MAX_THREAD_TITLE_LEN` characters are truncated.
+    ///
+    /// # Arguments
+    /// - `title`: The thread title.
+    ///
+    /// # Returns
+    /// - `Config`: The updated configuration.
+    pub fn with_thread_title(mut self, title: &str) -> Config {
+        self.thread_title = Some(title.to_string());
+        self
+    }
+
+    /// Returns this config with image generation settings. They are checked with
+    /// `ImageOptions::validate` before the request is sent.
+    ///
+    /// # Arguments
+    /// - `image`: The size, aspect ratio, quality and count to request.
+    ///
+    /// # Returns
+    /// - `Config`: The updated configuration.
+    pub fn with_image_options(mut self, image: ImageOptions) -> Config {
+        self.image = Some(image);
+        self
+    }
+
+    /// Returns this config with a deadline for the whole generation, measured from when the
+    /// request is sent. When it passes, the send fails with `T3Error::Timeout`.
+    ///
+    /// # Arguments
+    /// - `max_duration`: The longest the generation may take.
+    ///
+    /// # Returns
+    /// - `Config`: The updated configuration.
+    pub fn max_duration(mut self, max_duration: Duration) -> Config {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Returns this config with the partial reply of a timed-out generation kept in the
+    /// conversation as a truncated assistant message.
+    ///
+    /// # Arguments
+    /// - `commit`: Whether to record the partial reply.
+    ///
+    /// # Returns
+    /// - `Config`: The updated configuration.
+    pub fn commit_partial_on_timeout(mut self, commit: bool) -> Config {
+        self.commit_partial_on_timeout = commit;
+        self
+    }
+
+    /// Returns this config with the API keys the request should be routed through. With
+    /// `KeySource::Byok`, a model whose provider has no key on the account fails with
+    /// `T3Error::ByokRequired`; see `Client::get_byok_status`.
+    ///
+    /// # Arguments
+    /// - `source`: Whose keys to use.
+    ///
+    /// # Returns
+    /// - `Config`: The updated configuration.
+    pub fn with_api_key_source(mut self, source: KeySource) -> Config {
+        self.api_key_source = Some(source);
+        self
+    }
+}
+
+impl From<&Config> for Option<Config> {
+    fn from(config: &Config) -> Self {
+        Some(config.clone())
+    }
+}
+
+/// Traits offered by the t3.chat customization screen. Custom traits are accepted too.
+pub const KNOWN_TRAITS: &[&str] = &[
+    "friendly",
+    "witty",
+    "concise",
+    "curious",
+    "empathetic",
+    "creative",
+    "patient",
+];
+
+/// Personal customization sent with every request, as set on the t3.chat customization screen.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Preferences {
+    pub name: String,
+    pub occupation: String,
+    pub selected_traits: Vec<String>,
+    pub additional_info: String,
+}
+
+impl Preferences {
+    /// Returns the selected traits that are not among the `KNOWN_TRAITS`.
+    ///
+    /// # Returns
+    /// - `Vec<&str>`: The custom traits, in the order they were selected.
+    pub fn unknown_traits(&self) -> Vec<&str> {
+        self.selected_traits
+            .iter()
+            .map(String::as_str)
+            .filter(|selected| {
+                !KNOWN_TRAITS
+                    .iter()
+                    .any(|known| known.eq_ignore_ascii_case(selected))
+            })
+            .collect()
+    }
+}
+
+/// Timezone and locale reported to t3.chat, used for date-sensitive answers.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserInfo {
+    pub timezone: String,
+    pub locale: String,
+}
+
+impl Default for UserInfo {
+    fn default() -> Self {
+        UserInfo {
+            timezone: "America/New_York".to_string(),
+            locale: "en-US".to_string(),
+        }
+    }
+}
+
+impl UserInfo {
+    /// Creates a `UserInfo`. Timezones are passed through unvalidated; empty values fall back to the defaults.
+    ///
+    /// # Arguments
+    /// - `timezone`: An IANA timezone name such as `Europe/Berlin`.
+    /// - `locale`: A BCP 47 locale such as `de-DE`.
+    ///
+    /// # Returns
+    /// - `UserInfo`: The user info.
+    pub fn new(timezone: &str, locale: &str) -> UserInfo {
+        let defaults = UserInfo::default();
+        UserInfo {
+            timezone: if timezone.trim().is_empty() {
+                defaults.timezone
+            } else {
+                timezone.to_string()
+            },
+            locale: if locale.trim().is_empty() {
+                defaults.locale
+            } else {
+                locale.to_string()
+            },
+        }
+    }
+
+    /// Detects the local timezone, and the locale from `LC_ALL`, `LC_MESSAGES` or `LANG`.
+    /// Anything that cannot be detected falls back to the defaults.
+    ///
+    /// # Returns
+    /// - `UserInfo`: The user info of this machine.
+    #[cfg(feature = "system-timezone")]
+    pub fn from_system() -> UserInfo {
+        let timezone = iana_time_zone::get_timezone().unwrap_or_default();
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+            .map(|value| {
+                value
+                    .split(['.', '@'])
+                    .next()
+                    .unwrap_or("")
+                    .replace('_', "-")
+            })
+            .unwrap_or_default();
+        UserInfo::new(&timezone, &locale)
+    }
+}
+
+/// The production t3.chat web app.
+pub const DEFAULT_APP_URL: &str = "https://t3.chat";
+
+/// Where the clients send their requests. Defaults to production; point `app` at a local
+/// server for integration tests, or at a proxy that rewrites requests.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BaseUrls {
+    /// The web app, serving the homepage, its script chunks, `/api/chat` and the tRPC routes.
+    pub app: String,
+    /// The Convex deployment that stores thread history.
+    pub convex: String,
+}
+
+impl Default for BaseUrls {
+    fn default() -> Self {
+        BaseUrls {
+            app: DEFAULT_APP_URL.to_string(),
+            convex: CONVEX_URL.to_string(),
+        }
+    }
+}
+
+impl BaseUrls {
+    /// Creates base URLs with the given web app and the production Convex deployment.
+    ///
+    /// # Arguments
+    /// - `app`: The web app URL, such as `http://127.0.0.1:9999`.
+    ///
+    /// # Returns
+    /// - `BaseUrls`: The base URLs.
+    pub fn new(app: &str) -> BaseUrls {
+        BaseUrls {
+            app: app.trim_end_matches('/').to_string(),
+            ..BaseUrls::default()
+        }
+    }
+
+    /// Sets the Convex deployment URL.
+    ///
+    /// # Arguments
+    /// - `convex`: The Convex URL.
+    ///
+    /// # Returns
+    /// - `BaseUrls`: The updated base URLs.
+    pub fn with_convex(mut self, convex: &str) -> BaseUrls {
+        self.convex = convex.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Builds a web app URL.
+    ///
+    /// # Arguments
+    /// - `path`: The path, starting with `/`.
+    ///
+    /// # Returns
+    /// - `String`: The full URL.
+    pub fn app_url(&self, path: &str) -> String {
+        format!("{}{}", self.app, path)
+    }
+
+    /// Builds a Convex URL.
+    ///
+    /// # Arguments
+    /// - `path`: The path, starting with `/`.
+    ///
+    /// # Returns
+    /// - `String`: The full URL.
+    pub fn convex_url(&self, path: &str) -> String {
+        format!("{}{}", self.convex, path)
+    }
 }
@@ -0,0 +1,235 @@
+use super::config::Config;
+use super::cost::CostReport;
+use super::error::T3Error;
+use super::export::{ConversationExport, ExportOptions};
+use super::message::{Message, Type};
+use super::models::ModelInfo;
+use super::thread::ThreadId;
+
+/// One conversation: its t3.chat thread, its history and an optional config of its own. A
+/// `Client` keeps a conversation of its own for `send`; any number of others can be
+/// driven through the same client with `Client::send_in`, each keeping its own thread.
+#[derive(Debug, Clone, Default)]
+pub struct Conversation {
+    pub(crate) thread_id: Option<ThreadId>,
+    pub(crate) messages: Vec<Message>,
+    pub(crate) archived_messages: Vec<Message>,
+    pub(crate) system_prompt: Option<String>,
+    pub(crate) config: Option<Config>,
+}
+
+impl Conversation {
+    ///
+    /// Creates an empty conversation. It gets a thread on its first send.
+    ///
+    /// # Returns
+    /// * `Conversation` - The conversation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Creates an empty conversation whose sends use `config` when they pass none.
+    ///
+    /// # Arguments
+    /// * `config`: `Config` - The config for this conversation.
+    ///
+    /// # Returns
+    /// * `Conversation` - The conversation.
+    pub fn with_config(config: Config) -> Self {
+        Self {
+            config: Some(config),
+            ..Self::default()
+        }
+    }
+
+    ///
+    /// Restores a conversation from an export.
+    ///
+    /// # Arguments
+    /// * `export`: `ConversationExport` - The exported conversation.
+    ///
+    /// # Returns
+    /// * `Conversation` - The conversation.
+    pub fn from_export(export: ConversationExport) -> Self {
+        let mut conversation = Self {
+            thread_id: export.thread_id,
+            messages: export.messages,
+            ..Self::default()
+        };
+        conversation.sync_system_prompt();
+        conversation
+    }
+
+    ///
+    /// Exports the thread ID and messages, optionally dropping inline image data.
+    ///
+    /// # Arguments
+    /// * `options`: `ExportOptions` - What to include in the export.
+    ///
+    /// # Returns
+    /// * `ConversationExport` - The exported conversation.
+    pub fn export(&self, options: ExportOptions) -> ConversationExport {
+        let mut messages = self.messages.clone();
+        if !options.include_image_data {
+            for message in &mut messages {
+                for image in message.images_mut() {
+                    image.base64 = None;
+                }
+            }
+        }
+        ConversationExport::new(self.thread_id, messages)
+    }
+
+    ///
+    /// Exports the conversation like `export`, with a cost report attached. The report lists
+    /// the tokens of each message only if `options.include_message_tokens` is set.
+    ///
+    /// # Arguments
+    /// * `options`: `ExportOptions` - What to include in the export.
+    /// * `catalog`: `&[ModelInfo]` - The models to price replies with.
+    ///
+    /// # Returns
+    /// * `ConversationExport` - The exported conversation with its cost report.
+    pub fn export_with_cost(
+        &self,
+        options: ExportOptions,
+        catalog: &[ModelInfo],
+    ) -> ConversationExport {
+        let mut report = self.cost_report(catalog);
+        if !options.include_message_tokens {
+            report.messages.clear();
+        }
+        self.export(options).with_cost_report(report)
+    }
+
+    ///
+    /// Totals the tokens the server reported for the replies in the conversation and prices
+    /// them per model. Replies without usage, such as those of older saved conversations, are
+    /// counted in `replies_without_usage` and left out of the totals.
+    ///
+    /// # Arguments
+    /// * `catalog`: `&[ModelInfo]` - The models to price replies with, e.g. from
+    ///   `ModelsClient::get_models`; pass an empty slice for token totals only.
+    ///
+    /// # Returns
+    /// * `CostReport` - The report.
+    pub fn cost_report(&self, catalog: &[ModelInfo]) -> CostReport {
+        CostReport::new(&self.messages, catalog)
+    }
+
+    ///
+    /// Gets the config used by sends that pass none.
+    ///
+    /// # Returns
+    /// * `Option<&Config>` - The config, or `None` to fall back to the client's default.
+    pub fn config(&self) -> Option<&Config> {
+        self.config.as_ref()
+    }
+
+    ///
+    /// Sets or clears the config used by sends that pass none.
+    ///
+    /// # Arguments
+    /// * `config`: `Option<Config>` - The config, or `None` to use the client's default.
+    pub fn set_config(&mut self, config: Option<Config>) {
+        self.config = config;
+    }
+
+    ///
+    /// Gets the thread the conversation posts into.
+    ///
+    /// # Returns
+    /// * `Option<&ThreadId>` - The thread ID, or `None` before the first send.
+    pub fn thread_id(&self) -> Option<&ThreadId> {
+        self.thread_id.as_ref()
+    }
+
+    ///
+    /// Sets the thread that later sends post into.
+    ///
+    /// # Arguments
+    /// * `thread_id`: `ThreadId` - The thread to continue.
+    pub fn set_thread_id(&mut self, thread_id: ThreadId) {
+        self.thread_id = Some(thread_id);
+    }
+
+    ///
+    /// Gets the messages, in the order they were added.
+    ///
+    /// # Returns
+    /// * `&[Message]` - The messages.
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    ///
+    /// Gets the messages removed by the client's context limit, oldest first.
+    ///
+    /// # Returns
+    /// * `&[Message]` - The archived messages.
+    pub fn archived_messages(&self) -> &[Message] {
+        &self.archived_messages
+    }
+
+    ///
+    /// Gets the system prompt.
+    ///
+    /// # Returns
+    /// * `Option<&str>` - The system prompt, if one is set.
+    pub fn system_prompt(&self) -> Option<&str> {
+        self.system_prompt.as_deref()
+    }
+
+    ///
+    /// Appends a message without sending it. A system message replaces any existing one and
+    /// is kept at position 0.
+    ///
+    /// # Arguments
+    /// * `message`: `Message` - The message to append.
+    pub fn append_message(&mut self, message: Message) {
+        if !matches!(message.role, Type::System) {
+            self.messages.push(message);
+            return;
+        }
+        self.messages
+            .retain(|msg| !matches!(msg.role, Type::System));
+        self.system_prompt = Some(message.content().to_string());
+        self.messages.insert(0, message);
+    }
+
+    ///
+    /// Forgets the thread and every message. The system prompt and config are kept, but the
+    /// system message is not re-added.
+    pub fn clear(&mut self) {
+        self.thread_id = None;
+        self.messages.clear();
+        self.archived_messages.clear();
+    }
+
+    ///
+    /// Sets the stored system prompt from the system message after the history was edited, so
+    /// a removed prompt is not restored by a later `Client::new_conversation`.
+    pub(crate) fn sync_system_prompt(&mut self) {
+        self.system_prompt = self
+            .messages
+            .iter()
+            .find(|msg| matches!(msg.role, Type::System))
+            .map(|msg| msg.content().to_string());
+    }
+
+    ///
+    /// Finds a message by ID.
+    ///
+    /// # Arguments
+    /// * `message_id`: `&str` - The message ID.
+    ///
+    /// # Returns
+    /// * `Result<usize, T3Error>` - Its index in the messages, or `T3Error::NotFound`.
+    pub(crate) fn message_index(&self, message_id: &str) -> Result<usize, T3Error> {
+        self.messages
+            .iter()
+            .position(|msg| msg.id == message_id)
+            .ok_or_else(|| T3Error::NotFound(format!("message {}", message_id)))
+    }
+}
@@ -0,0 +1,265 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::error::T3Error;
+
+/// Cookies that must be present for t3.chat to accept requests.
+pub const REQUIRED_COOKIES: &[&str] = &["wos-session"];
+
+/// An ordered set of cookies for t3.chat. Names are unique; setting a name again replaces its value.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CookieJar {
+    cookies: Vec<(String, String)>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BrowserCookie {
+    name: String,
+    value: String,
+    #[serde(default)]
+    domain: Option<String>,
+    #[serde(default, alias = "expires")]
+    expiration_date: Option<f64>,
+}
+
+impl CookieJar {
+    ///
+    /// Parses a `Cookie` header such as `a=1; wos-session=abc`. Pairs without a `=` are skipped.
+    ///
+    /// # Arguments
+    /// * `header`: `&str` - The raw header value.
+    ///
+    /// # Returns
+    /// * `CookieJar` - The parsed cookies. Duplicate names keep the last value.
+    pub fn from_header_str(header: &str) -> Self {
+        let mut jar = CookieJar::default();
+        for pair in header.trim().trim_start_matches("Cookie:").split(';') {
+            if let Some((name, value)) = pair.split_once('=')
+                && !name.trim().is_empty()
+            {
+                jar.set(name.trim(), value.trim());
+            }
+        }
+        jar
+    }
+
+    ///
+    /// Reads a Netscape `cookies.txt` file, as written by curl and most browser extensions.
+    /// Malformed lines, expired cookies and cookies for other domains are skipped.
+    ///
+    /// # Arguments
+    /// * `path`: `&Path` - The cookies file.
+    ///
+    /// # Returns
+    /// * `Result<CookieJar, T3Error>` - The t3.chat cookies, or an error if the file cannot be read.
+    pub fn from_netscape_file(path: &Path) -> Result<Self, T3Error> {
+        Ok(Self::from_netscape_str(&fs::read_to_string(path)?))
+    }
+
+    ///
+    /// Parses the contents of a Netscape `cookies.txt` file. See `from_netscape_file`.
+    ///
+    /// # Arguments
+    /// * `contents`: `&str` - The file contents.
+    ///
+    /// # Returns
+    /// * `CookieJar` - The t3.chat cookies.
+    pub fn from_netscape_str(contents: &str) -> Self {
+        let now = Utc::now().timestamp();
+        let mut jar = CookieJar::default();
+        for line in contents.lines() {
+            let line = line.trim_end_matches('\r');
+            let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [domain, _, _, _, expires, name, value] = fields[..] else {
+                continue;
+            };
+            let Ok(expires) = expires.trim().parse::<i64>() else {
+                continue;
+            };
+            if (expires != 0 && expires < now) || !is_t3_domain(domain) || name.is_empty() {
+                continue;
+            }
+            jar.set(name, value);
+        }
+        jar
+    }
+
+    ///
+    /// Parses a JSON cookie export from a browser extension such as Cookie-Editor or
+    /// EditThisCookie: an array of objects with `name`, `value`, `domain` and `expirationDate`,
+    /// optionally wrapped in a `cookies` field. Expired cookies and other domains are skipped.
+    ///
+    /// # Arguments
+    /// * `json`: `&str` - The exported JSON.
+    ///
+    /// # Returns
+    /// * `Result<CookieJar, T3Error>` - The t3.chat cookies, or an error if the JSON is not a cookie export.
+    pub fn from_browser_json(json: &str) -> Result<Self, T3Error> {
+        let mut value: Value = serde_json::from_str(json)?;
+        if let Some(inner) = value.get_mut("cookies") {
+            value = inner.take();
+        }
+        let entries: Vec<BrowserCookie> = serde_json::from_value(value)?;
+        let now = Utc::now().timestamp() as f64;
+        let mut jar = CookieJar::default();
+        for entry in entries {
+            let expired = entry.expiration_date.is_some_and(|at| at > 0.0 && at < now);
+            let domain_ok = entry.domain.as_deref().is_none_or(is_t3_domain);
+            if !expired && domain_ok && !entry.name.is_empty() {
+                jar.set(&entry.name, &entry.value);
+            }
+        }
+        Ok(jar)
+    }
+
+    ///
+    /// Sets a cookie, replacing any existing cookie of the same name.
+    ///
+    /// # Arguments
+    /// * `name`: `&str` - The cookie name.
+    /// * `value`: `&str` - The cookie value.
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.cookies.retain(|(existing, _)| existing != name);
+        self.cookies.push((name.to_string(), value.to_string()));
+    }
+
+    ///
+    /// Returns the value of a cookie.
+    ///
+    /// # Arguments
+    /// * `name`: `&str` - The cookie name.
+    ///
+    /// # Returns
+    /// * `Option<&str>` - The value, if the cookie is present.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.cookies
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    ///
+    /// Returns the number of cookies in the jar.
+    ///
+    /// # Returns
+    /// * `usize` - The cookie count.
+    pub fn len(&self) -> usize {
+        self.cookies.len()
+    }
+
+    ///
+    /// Returns whether the jar holds no cookies.
+    ///
+    /// # Returns
+    /// * `bool` - `true` if the jar is empty.
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+
+    ///
+    /// Checks that every cookie in `REQUIRED_COOKIES` is present and non-empty.
+    ///
+    /// # Returns
+    /// * `Result<(), T3Error>` - `T3Error::MissingCookies` naming every missing cookie.
+    pub fn validate(&self) -> Result<(), T3Error> {
+        let missing: Vec<String> = REQUIRED_COOKIES
+            .iter()
+            .filter(|name| self.get(name).is_none_or(str::is_empty))
+            .map(|name| name.to_string())
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(T3Error::MissingCookies(missing))
+        }
+    }
+
+    ///
+    /// Decodes the expiry of the `wos-session` cookie, if it is a JWT.
+    ///
+    /// # Returns
+    /// * `Option<DateTime<Utc>>` - The expiry, or `None` if the cookie is missing or not a decodable JWT.
+    pub fn session_expires_at(&self) -> Option<DateTime<Utc>> {
+        jwt_expiry(self.get("wos-session")?)
+    }
+
+    ///
+    /// Joins the cookies into the header value `Client` sends.
+    ///
+    /// # Returns
+    /// * `String` - The cookies as `name=value` pairs separated by `; `.
+    pub fn to_header_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for CookieJar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (name, value)) in self.cookies.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}={}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for CookieJar {
+    fn from(header: &str) -> Self {
+        CookieJar::from_header_str(header)
+    }
+}
+
+impl From<String> for CookieJar {
+    fn from(header: String) -> Self {
+        CookieJar::from_header_str(&header)
+    }
+}
+
+impl From<&String> for CookieJar {
+    fn from(header: &String) -> Self {
+        CookieJar::from_header_str(header)
+    }
+}
+
+impl From<CookieJar> for String {
+    fn from(jar: CookieJar) -> Self {
+        jar.to_header_string()
+    }
+}
+
+///
+/// Reads the `exp` claim of a JWT without verifying its signature.
+///
+/// # Arguments
+/// * `token`: `&str` - The token, optionally percent-encoded.
+///
+/// # Returns
+/// * `Option<DateTime<Utc>>` - The expiry, or `None` if the token is not a JWT with an `exp` claim.
+pub fn jwt_expiry(token: &str) -> Option<DateTime<Utc>> {
+    let token = urlencoding::decode(token).ok()?;
+    let mut parts = token.split('.');
+    let (_, payload, _) = (parts.next()?, parts.next()?, parts.next()?);
+    let payload = general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+    let claims: Value = serde_json::from_slice(&payload).ok()?;
+    DateTime::from_timestamp(claims.get("exp")?.as_i64()?, 0)
+}
+
+fn is_t3_domain(domain: &str) -> bool {
+    let domain = domain.trim().trim_start_matches('.');
+    domain.is_empty() || domain == "t3.chat" || domain.ends_with(".t3.chat")
+}
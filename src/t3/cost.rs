@@ -0,0 +1,198 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::message::{Message, Type};
+use super::models::{ModelInfo, estimate_cost};
+
+/// Token and spend totals for a conversation, from `Conversation::cost_report`. Totals only
+/// count replies that carry usage; a figure no reply reports is `None` and rendered as "n/a".
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CostReport {
+    /// Prompt tokens over every reply that reports them.
+    pub prompt_tokens: Option<u64>,
+    /// Completion tokens over every reply that reports them.
+    pub completion_tokens: Option<u64>,
+    /// Spend per model, in the order each model first replied.
+    pub models: Vec<ModelSpend>,
+    /// Token counts per message, in conversation order. Left empty in exports unless
+    /// `ExportOptions::include_message_tokens` is set.
+    pub messages: Vec<MessageTokens>,
+    /// Assistant replies without usage, e.g. from conversations saved by older versions.
+    pub replies_without_usage: usize,
+}
+
+/// What the replies of one model cost.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelSpend {
+    /// The model ID, or `None` for replies that did not record their model.
+    pub model: Option<String>,
+    pub replies: usize,
+    pub prompt_tokens: Option<u64>,
+    pub completion_tokens: Option<u64>,
+    /// The model's credit amount per message times `replies`, if the catalog lists one.
+    pub credits: Option<i64>,
+    /// Estimated provider cost in US dollars of the replies that report usage.
+    pub usd: Option<f64>,
+}
+
+/// The tokens reported for one message.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MessageTokens {
+    pub id: String,
+    pub prompt_tokens: Option<u64>,
+    pub completion_tokens: Option<u64>,
+}
+
+impl CostReport {
+    ///
+    /// Totals the usage recorded on assistant replies and prices it with the model catalog.
+    ///
+    /// # Arguments
+    /// * `messages`: `&[Message]` - The messages of the conversation.
+    /// * `catalog`: `&[ModelInfo]` - The models to price replies with, e.g. from
+    ///   `ModelsClient::get_models`. Replies of models it does not list have no credits or cost.
+    ///
+    /// # Returns
+    /// * `CostReport` - The report.
+    pub fn new(messages: &[Message], catalog: &[ModelInfo]) -> Self {
+        let mut report = CostReport::default();
+        for message in messages {
+            let usage = message.usage.as_ref();
+            let prompt = usage.and_then(|usage| usage.input_tokens);
+            let completion = usage.and_then(|usage| usage.output_tokens);
+            report.messages.push(MessageTokens {
+                id: message.id.clone(),
+                prompt_tokens: prompt,
+                completion_tokens: completion,
+            });
+            if message.role != Type::Assistant || message.local_only {
+                continue;
+            }
+            if usage.is_none() {
+                report.replies_without_usage += 1;
+            }
+            add(&mut report.prompt_tokens, prompt);
+            add(&mut report.completion_tokens, completion);
+            let index = match report
+                .models
+                .iter()
+                .position(|spend| spend.model == message.model)
+            {
+                Some(index) => index,
+                None => {
+                    report.models.push(ModelSpend {
+                        model: message.model.clone(),
+                        ..ModelSpend::default()
+                    });
+                    report.models.len() - 1
+                }
+            };
+            let spend = &mut report.models[index];
+            spend.replies += 1;
+            add(&mut spend.prompt_tokens, prompt);
+            add(&mut spend.completion_tokens, completion);
+            let info = message
+                .model
+                .as_deref()
+                .and_then(|model| catalog.iter().find(|info| info.id == model));
+            if let Some(info) = info {
+                let estimate =
+                    estimate_cost(info, prompt.unwrap_or(0), completion.unwrap_or(0));
+                spend.credits = estimate.credits.map(|credits| credits * spend.replies as i64);
+                if usage.is_some()
+                    && let Some(usd) = estimate.usd
+                {
+                    *spend.usd.get_or_insert(0.0) += usd;
+                }
+            }
+        }
+        report
+    }
+
+    ///
+    /// Gets the token counts of one message.
+    ///
+    /// # Arguments
+    /// * `id`: `&str` - The message ID.
+    ///
+    /// # Returns
+    /// * `Option<&MessageTokens>` - The counts, or `None` if the report has no entry for it.
+    pub fn message(&self, id: &str) -> Option<&MessageTokens> {
+        self.messages.iter().find(|tokens| tokens.id == id)
+    }
+
+    ///
+    /// Renders the report as short lines, such as `Prompt tokens: 120` and
+    /// `gpt-4o: 2 replies, 2 credits, $0.0012`, with "n/a" for anything not reported.
+    ///
+    /// # Returns
+    /// * `Vec<String>` - The lines, for a Markdown header or an HTML footer.
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Prompt tokens: {}", or_na(self.prompt_tokens)),
+            format!("Completion tokens: {}", or_na(self.completion_tokens)),
+        ];
+        for spend in &self.models {
+            lines.push(format!(
+                "{}: {} {}, {} credits, {}",
+                spend.model.as_deref().unwrap_or("unknown model"),
+                spend.replies,
+                if spend.replies == 1 { "reply" } else { "replies" },
+                or_na(spend.credits),
+                spend
+                    .usd
+                    .map_or_else(|| "n/a".to_string(), |usd| format!("${:.4}", usd)),
+            ));
+        }
+        if self.replies_without_usage > 0 {
+            lines.push(format!(
+                "Replies without usage: {}",
+                self.replies_without_usage
+            ));
+        }
+        lines
+    }
+}
+
+impl MessageTokens {
+    ///
+    /// Renders the counts as `12 prompt / 5 completion tokens`, with "n/a" for a missing count.
+    ///
+    /// # Returns
+    /// * `String` - The counts for display.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} prompt / {} completion tokens",
+            or_na(self.prompt_tokens),
+            or_na(self.completion_tokens)
+        )
+    }
+}
+
+///
+/// Adds a count to a total, starting the total at the first reported count.
+///
+/// # Arguments
+/// * `total`: `&mut Option<u64>` - The total.
+/// * `count`: `Option<u64>` - The count, if reported.
+fn add(total: &mut Option<u64>, count: Option<u64>) {
+    if let Some(count) = count {
+        *total.get_or_insert(0) += count;
+    }
+}
+
+///
+/// Formats a figure, or "n/a" when it is missing.
+///
+/// # Arguments
+/// * `value`: `Option<T>` - The figure.
+///
+/// # Returns
+/// * `String` - The figure for display.
+pub(crate) fn or_na<T: fmt::Display>(value: Option<T>) -> String {
+    value.map_or_else(|| "n/a".to_string(), |value| value.to_string())
+}
@@ -0,0 +1,264 @@
+use std::fs::{self, File};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde_json::{Map, Value, json};
+use wreq::header::HeaderMap;
+
+use super::error::T3Error;
+
+/// How many exchanges `DebugDump` keeps unless configured otherwise.
+pub const DEFAULT_DEBUG_DUMP_KEEP: usize = 50;
+
+/// Replaces secrets in dumps.
+const REDACTED: &str = "[redacted]";
+
+/// Headers whose values are replaced with `REDACTED` in dumps.
+const REDACTED_HEADERS: [&str; 4] = ["cookie", "set-cookie", "authorization", "x-workos-session"];
+
+/// The files written for one exchange, after its `<timestamp>-<responseMessageId>` prefix.
+const DUMP_SUFFIXES: [&str; 3] = [".request.json", ".response.sse", ".meta.json"];
+
+/// A directory that receives the raw chat traffic of a `Client`, for diagnosing responses the
+/// parser cannot handle. Each exchange is written as `<timestamp>-<responseMessageId>` files:
+/// `.request.json` holds the posted body, `.response.sse` the response body exactly as it was
+/// received, and `.meta.json` the URL, status and headers. Cookies and the Convex session ID
+/// are redacted. Only the newest `keep` exchanges are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugDump {
+    dir: PathBuf,
+    keep: usize,
+}
+
+impl DebugDump {
+    ///
+    /// Creates a dump into `dir`, which is created on first use, keeping the newest
+    /// `DEFAULT_DEBUG_DUMP_KEEP` exchanges.
+    ///
+    /// # Arguments
+    /// * `dir`: `PathBuf` - The dump directory.
+    ///
+    /// # Returns
+    /// * `DebugDump` - The dump settings.
+    pub fn new(dir: PathBuf) -> Self {
+        DebugDump {
+            dir,
+            keep: DEFAULT_DEBUG_DUMP_KEEP,
+        }
+    }
+
+    ///
+    /// Sets how many exchanges are kept; older ones are deleted when a new one starts.
+    ///
+    /// # Arguments
+    /// * `keep`: `usize` - The number of exchanges to keep, at least 1.
+    ///
+    /// # Returns
+    /// * `DebugDump` - The updated settings.
+    pub fn keep(mut self, keep: usize) -> Self {
+        self.keep = keep.max(1);
+        self
+    }
+
+    ///
+    /// Gets the dump directory.
+    ///
+    /// # Returns
+    /// * `&Path` - The directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    ///
+    /// Starts an exchange by writing the request body, named after its `responseMessageId`.
+    /// The Convex session ID in the body is redacted like the cookies.
+    ///
+    /// # Arguments
+    /// * `body`: `&Value` - The chat request body.
+    ///
+    /// # Returns
+    /// * `Result<DumpEntry, T3Error>` - The open exchange, or an error if the files cannot be written.
+    pub(crate) fn start(&self, body: &Value) -> Result<DumpEntry, T3Error> {
+        let id = body
+            .get("responseMessageId")
+            .and_then(Value::as_str)
+            .unwrap_or("request");
+        let entry = self.create(id)?;
+        let mut body = body.clone();
+        if let Some(session) = body.get_mut("convexSessionId") {
+            *session = Value::from(REDACTED);
+        }
+        fs::write(
+            entry.path(".request.json"),
+            serde_json::to_vec_pretty(&body)?,
+        )?;
+        Ok(entry)
+    }
+
+    ///
+    /// Writes a body handed to `Client::parse_response` that could not be parsed.
+    ///
+    /// # Arguments
+    /// * `body`: `&str` - The raw response text.
+    ///
+    /// # Returns
+    /// * `Result<PathBuf, T3Error>` - The path of the written file.
+    pub(crate) fn write_unparsed(&self, body: &str) -> Result<PathBuf, T3Error> {
+        let mut entry = self.create("parse")?;
+        entry.write_chunk(body.as_bytes())?;
+        Ok(entry.response_path())
+    }
+
+    ///
+    /// Rotates old exchanges out and opens the response file of a new one.
+    ///
+    /// # Arguments
+    /// * `id`: `&str` - The name after the timestamp.
+    ///
+    /// # Returns
+    /// * `Result<DumpEntry, T3Error>` - The open exchange.
+    fn create(&self, id: &str) -> Result<DumpEntry, T3Error> {
+        fs::create_dir_all(&self.dir)?;
+        self.rotate()?;
+        let prefix = format!("{}-{}", Utc::now().format("%Y%m%dT%H%M%S%6fZ"), id);
+        let response = File::create(self.dir.join(format!("{}.response.sse", prefix)))?;
+        Ok(DumpEntry {
+            dir: self.dir.clone(),
+            prefix,
+            id: id.to_string(),
+            response,
+        })
+    }
+
+    ///
+    /// Deletes the oldest exchanges so that, with the one about to start, at most `keep` remain.
+    ///
+    /// # Returns
+    /// * `Result<(), T3Error>` - An error if the directory cannot be listed or a file cannot be deleted.
+    fn rotate(&self) -> Result<(), T3Error> {
+        let mut prefixes = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            if let Some(prefix) = entry?
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_suffix(".response.sse"))
+            {
+                prefixes.push(prefix.to_string());
+            }
+        }
+        prefixes.sort();
+        let excess = (prefixes.len() + 1).saturating_sub(self.keep);
+        for prefix in &prefixes[..excess] {
+            for suffix in DUMP_SUFFIXES {
+                match fs::remove_file(self.dir.join(format!("{}{}", prefix, suffix))) {
+                    Err(err) if err.kind() != ErrorKind::NotFound => return Err(err.into()),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The files of one exchange being written.
+pub(crate) struct DumpEntry {
+    dir: PathBuf,
+    prefix: String,
+    id: String,
+    response: File,
+}
+
+impl DumpEntry {
+    fn path(&self, suffix: &str) -> PathBuf {
+        self.dir.join(format!("{}{}", self.prefix, suffix))
+    }
+
+    ///
+    /// Gets the path of the raw response file.
+    ///
+    /// # Returns
+    /// * `PathBuf` - The `.response.sse` path.
+    pub(crate) fn response_path(&self) -> PathBuf {
+        self.path(".response.sse")
+    }
+
+    ///
+    /// Writes the request URL and headers and the response status and headers, redacting
+    /// cookies and session tokens.
+    ///
+    /// # Arguments
+    /// * `url`: `&str` - The request URL.
+    /// * `request_headers`: `&[(&str, &str)]` - The headers set on the request.
+    /// * `status`: `u16` - The response status.
+    /// * `response_headers`: `&HeaderMap` - The response headers.
+    ///
+    /// # Returns
+    /// * `Result<(), T3Error>` - An error if the file cannot be written.
+    pub(crate) fn write_meta(
+        &self,
+        url: &str,
+        request_headers: &[(&str, &str)],
+        status: u16,
+        response_headers: &HeaderMap,
+    ) -> Result<(), T3Error> {
+        let request: Map<String, Value> = request_headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), Value::from(redact(name, value))))
+            .collect();
+        let mut response = Map::new();
+        for (name, value) in response_headers {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            let value = Value::from(redact(name.as_str(), &value));
+            match response.get_mut(name.as_str()) {
+                Some(Value::Array(values)) => values.push(value),
+                Some(first) => *first = Value::Array(vec![first.take(), value]),
+                None => {
+                    response.insert(name.to_string(), value);
+                }
+            }
+        }
+        let meta = json!({
+            "responseMessageId": self.id,
+            "url": url,
+            "requestHeaders": request,
+            "status": status,
+            "responseHeaders": response,
+            "request": format!("{}.request.json", self.prefix),
+            "response": format!("{}.response.sse", self.prefix),
+        });
+        fs::write(self.path(".meta.json"), serde_json::to_vec_pretty(&meta)?)?;
+        Ok(())
+    }
+
+    ///
+    /// Appends bytes of the response body as they arrive, so a failed read still leaves
+    /// everything received before it.
+    ///
+    /// # Arguments
+    /// * `chunk`: `&[u8]` - The bytes.
+    ///
+    /// # Returns
+    /// * `Result<(), T3Error>` - An error if the file cannot be written.
+    pub(crate) fn write_chunk(&mut self, chunk: &[u8]) -> Result<(), T3Error> {
+        self.response.write_all(chunk)?;
+        Ok(())
+    }
+}
+
+///
+/// Replaces the value of a cookie or session header.
+///
+/// # Arguments
+/// * `name`: `&str` - The header name.
+/// * `value`: `&str` - The header value.
+///
+/// # Returns
+/// * `&str` - The value to dump.
+pub(crate) fn redact<'a>(name: &str, value: &'a str) -> &'a str {
+    if REDACTED_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+        REDACTED
+    } else {
+        value
+    }
+}
@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use thiserror::Error;
+
+use super::thread::ParseThreadIdError;
+
+/// Errors returned by the t3router clients.
+#[derive(Debug, Error)]
+pub enum T3Error {
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] wreq::Error),
+    #[error("unexpected HTTP status {status}: {message}")]
+    Status { status: u16, message: String },
+    #[error("authentication failed: {0}")]
+    Auth(String),
+    #[error("session expired (HTTP {status}): {body_snippet}")]
+    SessionExpired { status: u16, body_snippet: String },
+    #[error("missing required cookies: {}", .0.join(", "))]
+    MissingCookies(Vec<String>),
+    #[error("server error: {message}")]
+    Server {
+        code: Option<String>,
+        message: String,
+    },
+    #[error("model needs your own API key: {message}")]
+    ByokRequired {
+        provider: Option<String>,
+        message: String,
+    },
+    #[error("rate limited: {message}")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        message: String,
+    },
+    #[error("failed to parse response")]
+    Parse { raw: String },
+    #[error("response contained no content")]
+    EmptyResponse,
+    #[error("expected a text reply, got an image")]
+    ImageReply { image_url: Option<String> },
+    #[error("expected an image reply, got text")]
+    TextReply { text: String },
+    #[error("response contained no content, raw response saved to {}", .dump.display())]
+    UnparsedResponse { dump: PathBuf },
+    #[error("conversation has no messages to send")]
+    EmptyConversation,
+    #[error("generation was cancelled before any content arrived")]
+    Cancelled,
+    #[error("generation did not finish within the configured duration")]
+    Timeout { partial_text: String },
+    #[error("response stream interrupted: {source}")]
+    Interrupted {
+        partial_text: String,
+        #[source]
+        source: Box<T3Error>,
+    },
+    #[error("invalid header: {0}")]
+    InvalidHeader(String),
+    #[error("invalid proxy URL: {0}")]
+    InvalidProxy(String),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unsupported conversation export version {0}")]
+    UnsupportedExportVersion(u32),
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("invalid message {index} in transcript: {reason}")]
+    InvalidTranscript { index: usize, reason: String },
+    #[error("invalid setting {field}: {message}")]
+    InvalidSetting { field: String, message: String },
+    #[error("image is over the {limit} byte download limit ({size} bytes)")]
+    ImageTooLarge { size: u64, limit: u64 },
+    #[error("image download ended after {received} of {expected} bytes")]
+    ImageTruncated { received: u64, expected: u64 },
+    #[error("downloaded file is not an image (detected {content_type})")]
+    NotAnImage { content_type: String },
+    #[error("image URL expired (HTTP {status}), generate the image again: {url}")]
+    ImageUrlExpired { url: String, status: u16 },
+    #[error("invalid image options: {0}")]
+    InvalidImageOptions(String),
+    #[error("unsupported attachment: {0}")]
+    UnsupportedAttachment(String),
+    #[error("attachment {file_name} is {size} bytes, over the {limit} byte limit")]
+    AttachmentTooLarge {
+        file_name: String,
+        size: usize,
+        limit: usize,
+    },
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    InvalidThreadId(#[from] ParseThreadIdError),
+    #[error("unknown model {given:?}, closest matches: {suggestions:?}")]
+    UnknownModel {
+        given: String,
+        suggestions: Vec<String>,
+    },
+    #[error("no default model set, call set_default_model first")]
+    NoDefaultModel,
+    #[error("model discovery failed: {0}")]
+    ModelDiscovery(String),
+    #[error("convex call failed: {0}")]
+    Convex(String),
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("no recorded response for {request}, closest recorded request: {closest}")]
+    CassetteMiss { request: String, closest: String },
+    #[cfg(feature = "sqlite")]
+    #[error("conversation store error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("invalid regex: {0}")]
+    Regex(#[from] regex::Error),
+}
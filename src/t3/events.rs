@@ -0,0 +1,681 @@
+use std::ops::AddAssign;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Token counts reported for a generation. Fields the server leaves out are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+    pub reasoning_tokens: Option<u64>,
+    pub total_tokens: Option<u64>,
+}
+
+impl Usage {
+    ///
+    /// Reads a usage object in either the AI SDK (`inputTokens`, `outputTokens`) or the
+    /// OpenAI (`prompt_tokens`, `completion_tokens`) naming.
+    ///
+    /// # Arguments
+    /// * `value`: `&Value` - The usage object.
+    ///
+    /// # Returns
+    /// * `Option<Usage>` - The token counts, or `None` if the object holds none.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let count = |keys: &[&str]| keys.iter().find_map(|key| value.get(*key)?.as_u64());
+        let usage = Usage {
+            input_tokens: count(&["inputTokens", "promptTokens", "input_tokens", "prompt_tokens"]),
+            output_tokens: count(&[
+                "outputTokens",
+                "completionTokens",
+                "output_tokens",
+                "completion_tokens",
+            ]),
+            reasoning_tokens: count(&["reasoningTokens", "reasoning_tokens"]),
+            total_tokens: count(&["totalTokens", "total_tokens"]),
+        };
+        (usage != Usage::default()).then_some(usage)
+    }
+}
+
+/// Adds another generation's counts, for running totals across replies. A count only one side
+/// reports is taken as it is.
+impl AddAssign<&Usage> for Usage {
+    fn add_assign(&mut self, other: &Usage) {
+        let add = |total: &mut Option<u64>, more: Option<u64>| {
+            if let Some(more) = more {
+                *total = Some(total.unwrap_or(0) + more);
+            }
+        };
+        add(&mut self.input_tokens, other.input_tokens);
+        add(&mut self.output_tokens, other.output_tokens);
+        add(&mut self.reasoning_tokens, other.reasoning_tokens);
+        add(&mut self.total_tokens, other.total_tokens);
+    }
+}
+
+/// A single event from the chat EventStream.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SseEvent {
+    /// A chunk of generated text (`text-delta` and `text` events).
+    TextDelta(String),
+    /// A chunk of model reasoning (`reasoning-delta` and `reasoning` events).
+    ReasoningDelta(String),
+    /// A web source cited by the answer (`source-url` and `source` events). `index` is set when
+    /// the event carries the number used by the answer's inline markers.
+    Source {
+        url: String,
+        title: Option<String>,
+        snippet: Option<String>,
+        index: Option<usize>,
+    },
+    /// A generated image (`image-gen` events).
+    ImageGen { url: String },
+    /// The `output` payload of a `tool-output-available` or `tool-output-partially-available` event.
+    ToolOutput { value: Value },
+    /// A server-side error event, such as a model the account may not use or a filtered
+    /// message. `message` is the server's wording.
+    Error {
+        code: Option<String>,
+        message: String,
+    },
+    /// An error event reporting a rate limit or an exhausted message allowance.
+    RateLimited {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    /// The `finish` event sent once the model stops generating.
+    Finish {
+        finish_reason: Option<String>,
+        metadata: Option<Value>,
+    },
+    /// The `start` event that opens the reply. `message_id` is the ID the server stores the
+    /// reply under, which echoes the request's `responseMessageId`.
+    Start {
+        message_id: Option<String>,
+        metadata: Option<Value>,
+    },
+    /// Message metadata sent by `message-metadata` events, such as the serving model.
+    Metadata(Value),
+    /// The `[DONE]` marker that terminates the stream.
+    Done,
+    /// Any other event, kept as raw JSON.
+    Unknown(Value),
+}
+
+impl SseEvent {
+    ///
+    /// Parses a single `data:` line of the chat EventStream, on its own. Use `SseParser` to
+    /// read a whole stream, whose events may span several lines.
+    ///
+    /// # Arguments
+    /// * `line`: `&str` - The raw line, with or without its trailing newline.
+    ///
+    /// # Returns
+    /// * `Option<SseEvent>` - The event, or `None` if the line is not a `data:` line or holds invalid JSON.
+    pub fn parse_line(line: &str) -> Option<Self> {
+        match sse_field(line.trim())? {
+            ("data", data) => Self::decode(data, None),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Decodes the data of one event.
+    ///
+    /// # Arguments
+    /// * `data`: `&str` - The event data, its `data:` lines joined by newlines.
+    /// * `name`: `Option<&str>` - The name from the event's `event:` line, used as the `type`
+    ///   of a payload that has none.
+    ///
+    /// # Returns
+    /// * `Option<SseEvent>` - The event, or `None` if the data is not `[DONE]` or a JSON value.
+    fn decode(data: &str, name: Option<&str>) -> Option<Self> {
+        let data = data.trim();
+        if data == "[DONE]" {
+            return Some(Self::Done);
+        }
+        let mut value: Value = serde_json::from_str(data).ok()?;
+        if let (Some(name), Some(object)) = (name, value.as_object_mut()) {
+            object.entry("type").or_insert_with(|| Value::from(name));
+        }
+        Some(Self::from_value(value))
+    }
+
+    ///
+    /// Classifies a decoded event payload.
+    ///
+    /// # Arguments
+    /// * `value`: `Value` - The JSON payload of a `data:` line.
+    ///
+    /// # Returns
+    /// * `SseEvent` - The typed event, or `Unknown` if the payload is not recognised.
+    pub fn from_value(value: Value) -> Self {
+        match value.get("type").and_then(Value::as_str) {
+            Some("text-delta" | "text") => Self::TextDelta(event_text(&value)),
+            Some("reasoning-delta" | "reasoning") => Self::ReasoningDelta(event_text(&value)),
+            Some("image-gen") => {
+                let url = value
+                    .get("url")
+                    .and_then(Value::as_str)
+                    .or_else(|| value.get("content").and_then(Value::as_str))
+                    .or_else(|| value.get("delta")?.get("url")?.as_str());
+                match url {
+                    Some(url) => Self::ImageGen {
+                        url: url.to_string(),
+                    },
+                    None => Self::Unknown(value),
+                }
+            }
+            Some("tool-output-available" | "tool-output-partially-available") => {
+                match value.get("output") {
+                    Some(output) => Self::ToolOutput {
+                        value: output.clone(),
+                    },
+                    None => Self::Unknown(value),
+                }
+            }
+            Some("source-url" | "source") => {
+                let source = value.get("source").unwrap_or(&value);
+                let text = |keys: &[&str]| {
+                    keys.iter()
+                        .find_map(|key| source.get(*key)?.as_str())
+                        .map(str::to_string)
+                };
+                match text(&["url"]) {
+                    Some(url) => Self::Source {
+                        url,
+                        title: text(&["title"]),
+                        snippet: text(&["snippet", "description", "text"]),
+                        index: ["index", "sourceIndex"]
+                            .iter()
+                            .find_map(|key| source.get(*key)?.as_u64())
+                            .map(|index| index as usize),
+                    },
+                    None => Self::Unknown(value),
+                }
+            }
+            Some("finish") => Self::Finish {
+                finish_reason: value
+                    .get("finishReason")
+                    .or_else(|| value.get("messageMetadata")?.get("finishReason"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                metadata: value.get("messageMetadata").cloned(),
+            },
+            Some("start") => Self::Start {
+                message_id: value
+                    .get("messageId")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                metadata: value.get("messageMetadata").cloned(),
+            },
+            Some("message-metadata") => match value.get("messageMetadata") {
+                Some(metadata) => Self::Metadata(metadata.clone()),
+                None => Self::Unknown(value),
+            },
+            Some("error") => Self::error(&value),
+            _ => Self::Unknown(value),
+        }
+    }
+
+    ///
+    /// Classifies an error payload as a limit or another server error.
+    ///
+    /// # Arguments
+    /// * `value`: `&Value` - An error event or a JSON error body.
+    ///
+    /// # Returns
+    /// * `SseEvent` - `RateLimited` for an exhausted limit, otherwise `Error`.
+    fn error(value: &Value) -> Self {
+        let message = value
+            .get("errorText")
+            .or_else(|| value.get("message"))
+            .or_else(|| value.get("error")?.get("message"))
+            .or_else(|| value.get("error"))
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error")
+            .to_string();
+        let code = error_code(value);
+        if is_limit_error(code.as_deref(), &message) {
+            Self::RateLimited {
+                retry_after: limit_reset(value),
+                message,
+            }
+        } else {
+            Self::Error { code, message }
+        }
+    }
+}
+
+///
+/// Reads a JSON error body, as sent instead of an EventStream when a request is rejected
+/// outright, e.g. `{"error":{"code":"...","message":"..."}}`.
+///
+/// # Arguments
+/// * `body`: `&str` - The raw response body.
+///
+/// # Returns
+/// * `Option<SseEvent>` - `Error` or `RateLimited`, or `None` if the body is not a JSON error.
+pub fn parse_error_body(body: &str) -> Option<SseEvent> {
+    let value: Value = serde_json::from_str(body.trim()).ok()?;
+    let is_error = value.get("type").and_then(Value::as_str) == Some("error")
+        || value.get("error").is_some_and(|error| !error.is_null());
+    is_error.then(|| SseEvent::error(&value))
+}
+
+///
+/// Parses every event in a buffered EventStream body. Lines that are not events are skipped.
+///
+/// # Arguments
+/// * `response`: `&str` - The raw response body.
+///
+/// # Returns
+/// * `Vec<SseEvent>` - The events in the order they were received.
+pub fn parse_events(response: &str) -> Vec<SseEvent> {
+    let mut parser = SseParser::new();
+    let mut events = parser.feed_str(response);
+    events.extend(parser.finish());
+    events
+}
+
+/// Reads a chat EventStream incrementally, from chunks of bytes as they arrive. A line or a
+/// UTF-8 character split between chunks is kept until the rest arrives. Lines follow the SSE
+/// framing rules: `data:` with or without a space, `\r\n` or `\n` line endings, `event:`
+/// names, and comment lines starting with `:`, which are skipped. The `data:` lines of an event
+/// are joined until they form a complete JSON value, so one-line events are returned as soon
+/// as they arrive and an event split over several lines is returned once its last line is in.
+/// A blank line ends the event either way.
+#[derive(Debug, Clone, Default)]
+pub struct SseParser {
+    partial_line: Vec<u8>,
+    data: String,
+    scan: JsonScan,
+    name: Option<String>,
+}
+
+impl SseParser {
+    ///
+    /// Creates a parser at the start of a stream.
+    ///
+    /// # Returns
+    /// * `SseParser` - The parser.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Reads the next chunk of the stream.
+    ///
+    /// # Arguments
+    /// * `bytes`: `&[u8]` - The chunk, which may end in the middle of a line or character.
+    ///
+    /// # Returns
+    /// * `Vec<SseEvent>` - The events completed by the chunk, in order.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<SseEvent> {
+        let Some(last) = bytes.iter().rposition(|byte| *byte == b'\n') else {
+            self.partial_line.extend_from_slice(bytes);
+            return Vec::new();
+        };
+        let (lines, rest) = bytes.split_at(last);
+        let events = if self.partial_line.is_empty() {
+            self.read_lines(&String::from_utf8_lossy(lines))
+        } else {
+            let mut joined = std::mem::take(&mut self.partial_line);
+            joined.extend_from_slice(lines);
+            self.read_lines(&String::from_utf8_lossy(&joined))
+        };
+        self.partial_line.extend_from_slice(&rest[1..]);
+        events
+    }
+
+    ///
+    /// Reads the next chunk of the stream, given as text, like `feed`.
+    ///
+    /// # Arguments
+    /// * `text`: `&str` - The chunk, which may end in the middle of a line.
+    ///
+    /// # Returns
+    /// * `Vec<SseEvent>` - The events completed by the chunk, in order.
+    pub fn feed_str(&mut self, text: &str) -> Vec<SseEvent> {
+        if !self.partial_line.is_empty() {
+            return self.feed(text.as_bytes());
+        }
+        let Some(last) = text.rfind('\n') else {
+            self.partial_line.extend_from_slice(text.as_bytes());
+            return Vec::new();
+        };
+        let events = self.read_lines(&text[..last]);
+        self.partial_line
+            .extend_from_slice(&text.as_bytes()[last + 1..]);
+        events
+    }
+
+    fn read_lines(&mut self, text: &str) -> Vec<SseEvent> {
+        text.split('\n')
+            .filter_map(|line| self.push_line(line))
+            .collect()
+    }
+
+    ///
+    /// Reads one complete line of the stream.
+    ///
+    /// # Arguments
+    /// * `line`: `&str` - The line, with or without its line ending.
+    ///
+    /// # Returns
+    /// * `Option<SseEvent>` - The event the line completes, if any.
+    pub fn push_line(&mut self, line: &str) -> Option<SseEvent> {
+        let line = line.trim();
+        if line.is_empty() {
+            return self.end_event();
+        }
+        match sse_field(line)? {
+            ("data", data) => {
+                if !self.data.is_empty() && is_complete_payload(data) {
+                    self.data.clear();
+                }
+                if self.data.is_empty() {
+                    if let Some(event) = SseEvent::decode(data, self.name.as_deref()) {
+                        return Some(event);
+                    }
+                    self.data.push_str(data);
+                    self.scan = JsonScan::default();
+                    self.scan.push(data);
+                    // A value already closed on its first line that does not decode stays broken.
+                    self.scan.failed = self.scan.may_decode();
+                    return None;
+                }
+                self.data.push('\n');
+                self.data.push_str(data);
+                self.scan.push(data);
+                if !self.scan.may_decode() {
+                    return None;
+                }
+                let Some(event) = SseEvent::decode(&self.data, self.name.as_deref()) else {
+                    self.scan.failed = true;
+                    return None;
+                };
+                self.data.clear();
+                Some(event)
+            }
+            ("event", name) => {
+                self.name = Some(name.to_string());
+                None
+            }
+            _ => None,
+        }
+    }
+
+    ///
+    /// Ends the stream, reading a last line that had no line ending.
+    ///
+    /// # Returns
+    /// * `Option<SseEvent>` - The last event, if it was not returned yet and decodes.
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        let line = std::mem::take(&mut self.partial_line);
+        self.push_line(&String::from_utf8_lossy(&line))
+            .or_else(|| self.end_event())
+    }
+
+    ///
+    /// Ends the current event, as a blank line does.
+    ///
+    /// # Returns
+    /// * `Option<SseEvent>` - The event, if its data was not returned yet and decodes.
+    fn end_event(&mut self) -> Option<SseEvent> {
+        let data = std::mem::take(&mut self.data);
+        let name = self.name.take();
+        if data.is_empty() {
+            return None;
+        }
+        SseEvent::decode(&data, name.as_deref())
+    }
+}
+
+///
+/// Splits an EventStream line into its field name and value. A single space after the colon
+/// is not part of the value.
+///
+/// # Arguments
+/// * `line`: `&str` - The line, without its line ending.
+///
+/// # Returns
+/// * `Option<(&str, &str)>` - The field and value, or `None` for a comment line.
+fn sse_field(line: &str) -> Option<(&str, &str)> {
+    if line.starts_with(':') {
+        return None;
+    }
+    Some(match line.split_once(':') {
+        Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+        None => (line, ""),
+    })
+}
+
+/// Whether a `data:` value is a whole event on its own, so it starts a new event rather than
+/// continuing an earlier value that never completed.
+fn is_complete_payload(data: &str) -> bool {
+    let data = data.trim();
+    data == "[DONE]" || serde_json::from_str::<Value>(data).is_ok_and(|value| value.is_object())
+}
+
+/// Tracks the brackets and strings of a JSON value as its `data:` lines arrive, so the joined
+/// value is only decoded once every bracket it opened is closed again, not on every line.
+#[derive(Debug, Clone, Copy, Default)]
+struct JsonScan {
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    failed: bool,
+}
+
+impl JsonScan {
+    ///
+    /// Scans the next line of the value.
+    ///
+    /// # Arguments
+    /// * `text`: `&str` - The line, without its `data:` prefix.
+    fn push(&mut self, text: &str) {
+        for byte in text.bytes() {
+            if self.in_string {
+                match byte {
+                    _ if self.escaped => self.escaped = false,
+                    b'\\' => self.escaped = true,
+                    b'"' => self.in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match byte {
+                b'"' => self.in_string = true,
+                b'{' | b'[' => self.depth += 1,
+                b'}' | b']' => match self.depth.checked_sub(1) {
+                    Some(depth) => self.depth = depth,
+                    // A bracket closed that was never opened: no later line can fix that.
+                    None => self.failed = true,
+                },
+                _ => {}
+            }
+        }
+    }
+
+    ///
+    /// Whether the value scanned so far is closed and worth decoding. Once a closed value fails
+    /// to decode, no later line can make it valid, so it is not tried again.
+    ///
+    /// # Returns
+    /// * `bool` - Whether to decode the joined value now.
+    fn may_decode(&self) -> bool {
+        !self.failed && self.depth == 0 && !self.in_string
+    }
+}
+
+///
+/// Returns the URL of the last image found in a tool output payload.
+///
+/// # Arguments
+/// * `output`: `&Value` - The `output` payload of a tool output event.
+///
+/// # Returns
+/// * `Option<String>` - The image URL, if any.
+pub fn tool_output_url(output: &Value) -> Option<String> {
+    tool_output_urls(output).pop()
+}
+
+///
+/// Returns the URLs of every image found in a tool output payload, in order.
+///
+/// # Arguments
+/// * `output`: `&Value` - The `output` payload of a tool output event.
+///
+/// # Returns
+/// * `Vec<String>` - The image URLs, empty if there are none.
+pub fn tool_output_urls(output: &Value) -> Vec<String> {
+    if let Some(url) = output.get("url").and_then(Value::as_str) {
+        return vec![url.to_string()];
+    }
+    output
+        .get("output")
+        .and_then(Value::as_array)
+        .or_else(|| output.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.get("url").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect()
+}
+
+///
+/// Reads how long to wait before a limit resets from an error payload. Understands a
+/// `retryAfter` in seconds and a `resetAt` given as an ISO 8601 timestamp or a Unix time in
+/// seconds or milliseconds, either at the top level or inside an `error` object.
+///
+/// # Arguments
+/// * `value`: `&Value` - The error payload.
+///
+/// # Returns
+/// * `Option<Duration>` - The time until the limit resets, or `None` if the payload has no reset time.
+pub fn limit_reset(value: &Value) -> Option<Duration> {
+    let sources = [Some(value), value.get("error")];
+    let field = |keys: &[&str]| {
+        sources
+            .iter()
+            .flatten()
+            .find_map(|source| keys.iter().find_map(|key| source.get(*key)))
+    };
+    if let Some(seconds) = field(&["retryAfter", "retry_after"]).and_then(Value::as_f64) {
+        return Duration::try_from_secs_f64(seconds).ok();
+    }
+    let reset_at = match field(&["resetAt", "reset_at", "resetsAt"])? {
+        Value::String(text) => DateTime::parse_from_rfc3339(text).ok()?.with_timezone(&Utc),
+        Value::Number(number) => {
+            let number = number.as_i64()?;
+            if number > 1_000_000_000_000 {
+                DateTime::from_timestamp_millis(number)?
+            } else {
+                DateTime::from_timestamp(number, 0)?
+            }
+        }
+        _ => return None,
+    };
+    Some((reset_at - Utc::now()).to_std().unwrap_or_default())
+}
+
+fn error_code(value: &Value) -> Option<String> {
+    match value
+        .get("code")
+        .or_else(|| value.get("error")?.get("code"))?
+    {
+        Value::String(code) => Some(code.clone()),
+        Value::Number(code) => Some(code.to_string()),
+        _ => None,
+    }
+}
+
+fn is_limit_error(code: Option<&str>, message: &str) -> bool {
+    let code = code.unwrap_or_default().to_ascii_lowercase();
+    let message = message.to_ascii_lowercase();
+    code.contains("rate") && code.contains("limit")
+        || code.contains("too_many_requests")
+        || [
+            "rate limit",
+            "rate-limit",
+            "message limit",
+            "limit reached",
+            "too many requests",
+            "out of messages",
+            "usage limit",
+        ]
+        .iter()
+        .any(|needle| message.contains(needle))
+}
+
+///
+/// Checks whether an error event says the model can only be used with the account's own
+/// provider key, which is not configured.
+///
+/// # Arguments
+/// * `code`: `Option<&str>` - The error code, if the event has one.
+/// * `message`: `&str` - The error message.
+///
+/// # Returns
+/// * `bool` - `true` for a missing own-key error.
+pub(crate) fn is_byok_error(code: Option<&str>, message: &str) -> bool {
+    let code = code.unwrap_or_default().to_ascii_lowercase();
+    let message = message.to_ascii_lowercase();
+    code.contains("byok")
+        || [
+            "byok",
+            "bring your own key",
+            "your own api key",
+            "your own key",
+            "no api key configured",
+        ]
+        .iter()
+        .any(|needle| message.contains(needle))
+}
+
+///
+/// Finds the provider named in an error message.
+///
+/// # Arguments
+/// * `message`: `&str` - The error message.
+///
+/// # Returns
+/// * `Option<String>` - The provider in lowercase, such as `openai`, or `None`.
+pub(crate) fn key_provider(message: &str) -> Option<String> {
+    let message = message.to_ascii_lowercase();
+    ["openrouter", "openai", "anthropic", "google", "gemini"]
+        .iter()
+        .find(|provider| message.contains(*provider))
+        .map(|provider| provider.to_string())
+}
+
+fn event_text(value: &Value) -> String {
+    if let Some(delta) = value.get("delta").and_then(Value::as_str) {
+        return delta.to_string();
+    }
+    if let Some(text) = value
+        .get("delta")
+        .and_then(|d| d.get("text"))
+        .and_then(Value::as_str)
+    {
+        return text.to_string();
+    }
+    if let Some(text) = value.get("text").and_then(Value::as_str) {
+        return text.to_string();
+    }
+    value
+        .get("content")
+        .and_then(Value::as_array)
+        .map(|content| {
+            content
+                .iter()
+                .filter_map(|item| item.get("text").and_then(Value::as_str))
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+
+use super::cost::{CostReport, or_na};
+use super::error::T3Error;
+use super::message::Message;
+use super::thread::ThreadId;
+
+/// Version of the `ConversationExport` format written by this crate.
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// A saved conversation that can be written to disk and imported into a `Client` later.
+/// `cost` is set by `Conversation::export_with_cost` and shown by `to_markdown` and `to_html`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationExport {
+    pub version: u32,
+    pub thread_id: Option<ThreadId>,
+    pub messages: Vec<Message>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<CostReport>,
+}
+
+/// Controls what `Client::export_conversation_with` includes.
+#[derive(Debug, Clone, Copy)]
+pub struct ExportOptions {
+    /// Whether to keep the base64 payload of image messages, for every entry of
+    /// `Message::images`. Image URLs are always kept.
+    pub include_image_data: bool,
+    /// Whether the cost report lists the tokens of each message, shown as a token line per
+    /// message in Markdown and as `data-*-tokens` attributes in HTML.
+    pub include_message_tokens: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            include_image_data: true,
+            include_message_tokens: false,
+        }
+    }
+}
+
+impl ConversationExport {
+    ///
+    /// Creates an export in the current format version.
+    ///
+    /// # Arguments
+    /// * `thread_id`: `Option<ThreadId>` - The thread the conversation belongs to.
+    /// * `messages`: `Vec<Message>` - The messages of the conversation.
+    ///
+    /// # Returns
+    /// * `ConversationExport` - The export.
+    pub fn new(thread_id: Option<ThreadId>, messages: Vec<Message>) -> Self {
+        Self {
+            version: EXPORT_FORMAT_VERSION,
+            thread_id,
+            messages,
+            cost: None,
+        }
+    }
+
+    ///
+    /// Attaches a cost report to the export.
+    ///
+    /// # Arguments
+    /// * `report`: `CostReport` - The report, e.g. from `Conversation::cost_report`.
+    ///
+    /// # Returns
+    /// * `ConversationExport` - The export with the report.
+    pub fn with_cost_report(mut self, report: CostReport) -> Self {
+        self.cost = Some(report);
+        self
+    }
+
+    ///
+    /// Serializes the export to pretty-printed JSON.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The export.
+    ///
+    /// # Returns
+    /// * `Result<String, T3Error>` - The JSON document.
+    pub fn to_json_string(&self) -> Result<String, T3Error> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    ///
+    /// Parses an export from JSON, rejecting format versions newer than this crate understands.
+    ///
+    /// # Arguments
+    /// * `json`: `&str` - The JSON document.
+    ///
+    /// # Returns
+    /// * `Result<ConversationExport, T3Error>` - The export or an error.
+    pub fn from_json_str(json: &str) -> Result<Self, T3Error> {
+        let export: Self = serde_json::from_str(json)?;
+        if export.version > EXPORT_FORMAT_VERSION {
+            return Err(T3Error::UnsupportedExportVersion(export.version));
+        }
+        Ok(export)
+    }
+
+    ///
+    /// Renders the export as Markdown: a header with the thread and the cost summary, then one
+    /// section per message.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The export.
+    ///
+    /// # Returns
+    /// * `String` - The Markdown document.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Conversation\n\n");
+        if let Some(thread_id) = &self.thread_id {
+            out.push_str(&format!("- Thread: {}\n", thread_id));
+        }
+        for line in self.cost.iter().flat_map(CostReport::summary_lines) {
+            out.push_str(&format!("- {}\n", line));
+        }
+        for message in &self.messages {
+            out.push_str(&format!("\n## {}", role_name(message)));
+            if let Some(tokens) = self.message_tokens(message) {
+                out.push_str(&format!(" ({})", tokens));
+            }
+            out.push_str("\n\n");
+            if !message.text().is_empty() {
+                out.push_str(message.text());
+                out.push('\n');
+            }
+            for url in message.images().map(|image| image.url.as_str()) {
+                if url.starts_with("data:") {
+                    out.push_str("[inline image]\n");
+                } else {
+                    out.push_str(&format!("![image]({})\n", url));
+                }
+            }
+            for attachment in message.attachments() {
+                out.push_str(&format!("[attachment: {}]\n", attachment.file_name));
+            }
+        }
+        out
+    }
+
+    ///
+    /// Renders the export as a standalone HTML page, one `<article>` per message and the cost
+    /// summary in a `<footer>`.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The export.
+    ///
+    /// # Returns
+    /// * `String` - The HTML document.
+    pub fn to_html(&self) -> String {
+        let mut out = String::from(concat!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\">",
+            "<title>Conversation</title></head>\n<body>\n",
+        ));
+        for message in &self.messages {
+            out.push_str(&format!(
+                "<article class=\"message {}\"",
+                role_name(message).to_lowercase()
+            ));
+            if let Some(tokens) = self
+                .cost
+                .as_ref()
+                .and_then(|cost| cost.message(&message.id))
+            {
+                out.push_str(&format!(
+                    " data-prompt-tokens=\"{}\" data-completion-tokens=\"{}\"",
+                    or_na(tokens.prompt_tokens),
+                    or_na(tokens.completion_tokens)
+                ));
+            }
+            out.push_str(&format!(">\n<h2>{}</h2>\n", role_name(message)));
+            if !message.text().is_empty() {
+                out.push_str(&format!("<p>{}</p>\n", escape_html(message.text())));
+            }
+            for url in message.images().map(|image| image.url.as_str()) {
+                if !url.starts_with("data:") {
+                    out.push_str(&format!("<img src=\"{}\">\n", escape_html(url)));
+                }
+            }
+            out.push_str("</article>\n");
+        }
+        if let Some(cost) = &self.cost {
+            out.push_str("<footer class=\"cost\">\n<ul>\n");
+            for line in cost.summary_lines() {
+                out.push_str(&format!("<li>{}</li>\n", escape_html(&line)));
+            }
+            out.push_str("</ul>\n</footer>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    ///
+    /// Gets the token summary of a message from the cost report.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The export.
+    /// * `message`: `&Message` - The message.
+    ///
+    /// # Returns
+    /// * `Option<String>` - The summary, or `None` if the report does not list the message.
+    fn message_tokens(&self, message: &Message) -> Option<String> {
+        Some(self.cost.as_ref()?.message(&message.id)?.summary())
+    }
+}
+
+///
+/// Names the author of a message for a heading, marking local-only notes.
+///
+/// # Arguments
+/// * `message`: `&Message` - The message.
+///
+/// # Returns
+/// * `String` - `User`, `Assistant` or `System`, with ` (local)` for a note.
+fn role_name(message: &Message) -> String {
+    if message.local_only {
+        format!("{} (local)", message.role)
+    } else {
+        message.role.to_string()
+    }
+}
+
+///
+/// Escapes text for HTML content and attribute values.
+///
+/// # Arguments
+/// * `text`: `&str` - The text.
+///
+/// # Returns
+/// * `String` - The escaped text.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
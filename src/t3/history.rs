@@ -1,4 +1,22 @@
-use serde_json::Value;
+use serde_json::{Value, json};
+
+use super::config::BaseUrls;
+use super::error::T3Error;
+use super::http::{HttpOptions, build_http_client};
+use super::thread::ThreadId;
+
+/// Base URL of the Convex deployment behind t3.chat.
+pub const CONVEX_URL: &str = "https://api.sync.t3.chat";
+const THREADS_QUERY: &str = "threads:get";
+const DELETE_THREAD_MUTATION: &str = "threads:deleteThread";
+const THREAD_QUERY: &str = "threads:getById";
+const RENAME_THREAD_MUTATION: &str = "threads:renameThread";
+const PIN_THREAD_MUTATION: &str = "threads:setPinned";
+const ARCHIVE_THREAD_MUTATION: &str = "threads:setArchived";
+
+/// Longest thread title t3.chat stores, in characters. Longer titles are truncated client-side.
+pub const MAX_THREAD_TITLE_LEN: usize = 100;
+const THREADS_PAGE_SIZE: usize = 100;
 
 #[derive(Debug, Clone, Default)]
 pub struct Thread {
@@ -13,6 +31,44 @@ pub struct Thread {
     pub is_ephemeral: bool,
 }
 
+/// A thread stored on the account, as listed by `HistoryClient::list_threads`.
+/// Timestamps are Unix milliseconds, `0.0` when the backend does not report them.
+#[derive(Debug, Clone, Default)]
+pub struct ThreadSummary {
+    pub id: ThreadId,
+    pub title: String,
+    pub model: String,
+    pub created_at: f64,
+    pub updated_at: f64,
+    pub pinned: bool,
+    pub archived: bool,
+}
+
+/// The title that was stored for a thread, and whether it had to be shortened to fit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ThreadTitle {
+    pub title: String,
+    pub truncated: bool,
+}
+
+impl ThreadTitle {
+    /// Trim a title and cut it to `MAX_THREAD_TITLE_LEN` characters.
+    ///
+    /// # Arguments
+    /// * `title` - &str: The requested title.
+    ///
+    /// # Returns
+    /// * `ThreadTitle` - The title to store, with `truncated` set if it was cut.
+    pub fn fit(title: &str) -> Self {
+        let title = title.trim();
+        let truncated = title.chars().count() > MAX_THREAD_TITLE_LEN;
+        ThreadTitle {
+            title: title.chars().take(MAX_THREAD_TITLE_LEN).collect(),
+            truncated,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ThreadMessage {
     pub id: String,
@@ -25,8 +81,10 @@ pub struct ThreadMessage {
 }
 
 pub struct HistoryClient {
+    client: wreq::Client,
     cookies: String,
     convex_session_id: String,
+    base_urls: BaseUrls,
 }
 
 impl HistoryClient {
@@ -35,19 +93,279 @@ impl HistoryClient {
     /// t3.chat stores thread history client-side:
     /// - Ephemeral threads: sessionStorage key "ephemeral-chat-data"
     /// - Thread list cache: sessionStorage key "sidebar-thread-list:*"
-    /// - Persistent threads: Convex WebSocket (no HTTP endpoint)
+    /// - Persistent threads: the Convex backend, queried with `list_threads`
     ///
     /// # Arguments
-    /// * `cookies` - String: Cookie header (for future Convex WebSocket support).
+    /// * `cookies` - String: Cookie header for Convex calls.
     /// * `convex_session_id` - String: Convex session ID.
     ///
     /// # Returns
     /// * Self - A new HistoryClient instance.
     pub fn new(cookies: String, convex_session_id: String) -> Self {
+        Self::with_http_client(
+            build_http_client(HttpOptions::default()).unwrap(),
+            cookies,
+            convex_session_id,
+        )
+    }
+
+    /// Create a HistoryClient that reuses an existing HTTP client.
+    ///
+    /// # Arguments
+    /// * `client` - wreq::Client: The HTTP client to share.
+    /// * `cookies` - String: Cookie header for Convex calls.
+    /// * `convex_session_id` - String: Convex session ID.
+    ///
+    /// # Returns
+    /// * Self - A new HistoryClient instance.
+    pub(crate) fn with_http_client(
+        client: wreq::Client,
+        cookies: String,
+        convex_session_id: String,
+    ) -> Self {
         Self {
+            client,
             cookies,
             convex_session_id,
+            base_urls: BaseUrls::default(),
+        }
+    }
+
+    /// Send requests to other hosts than production, such as a local test server.
+    ///
+    /// # Arguments
+    /// * `base_urls` - BaseUrls: The hosts to use.
+    ///
+    /// # Returns
+    /// * Self - The updated HistoryClient.
+    pub fn with_base_urls(mut self, base_urls: BaseUrls) -> Self {
+        self.base_urls = base_urls;
+        self
+    }
+
+    /// List the threads stored on the account, newest first, following Convex pagination.
+    /// Entries without a valid thread ID are skipped.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The history client instance.
+    /// * `limit`: `Option<usize>` - The maximum number of threads to return, or `None` for all of them.
+    ///
+    /// # Returns
+    /// * `Result<Vec<ThreadSummary>, T3Error>` - The threads, `T3Error::Auth` if the session is rejected.
+    pub async fn list_threads(&self, limit: Option<usize>) -> Result<Vec<ThreadSummary>, T3Error> {
+        let mut threads = Vec::new();
+        let mut cursor = Value::Null;
+        loop {
+            let remaining = limit.map_or(THREADS_PAGE_SIZE, |limit| limit - threads.len());
+            if remaining == 0 {
+                break;
+            }
+            let value = self
+                .convex_call(
+                    "query",
+                    THREADS_QUERY,
+                    json!({
+                        "sessionId": &self.convex_session_id,
+                        "paginationOpts": {
+                            "numItems": remaining.min(THREADS_PAGE_SIZE),
+                            "cursor": cursor
+                        }
+                    }),
+                )
+                .await?;
+            let page = value.get("page").unwrap_or(&value);
+            threads.extend(
+                page.as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|item| Some(self.parse_thread_summary(item, summary_thread_id(item)?))),
+            );
+            let done = value.get("isDone").and_then(|x| x.as_bool()).unwrap_or(true);
+            match value.get("continueCursor") {
+                Some(next) if !done && !next.is_null() => cursor = next.clone(),
+                _ => break,
+            }
+        }
+        if let Some(limit) = limit {
+            threads.truncate(limit);
+        }
+        Ok(threads)
+    }
+
+    /// Delete a thread and its messages from the account.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The history client instance.
+    /// * `thread_id`: `&ThreadId` - The thread to delete.
+    ///
+    /// # Returns
+    /// * `Result<(), T3Error>` - `T3Error::NotFound` if the account has no such thread.
+    pub async fn delete_thread(&self, thread_id: &ThreadId) -> Result<(), T3Error> {
+        self.thread_mutation(
+            DELETE_THREAD_MUTATION,
+            thread_id,
+            json!({ "sessionId": &self.convex_session_id, "threadId": thread_id }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Rename a thread. Titles over `MAX_THREAD_TITLE_LEN` characters are truncated first.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The history client instance.
+    /// * `thread_id`: `&ThreadId` - The thread to rename.
+    /// * `title`: `&str` - The new title.
+    ///
+    /// # Returns
+    /// * `Result<ThreadTitle, T3Error>` - The stored title, `T3Error::NotFound` if the account has no such thread.
+    pub async fn set_thread_title(&self, thread_id: &ThreadId, title: &str) -> Result<ThreadTitle, T3Error> {
+        let fitted = ThreadTitle::fit(title);
+        self.thread_mutation(
+            RENAME_THREAD_MUTATION,
+            thread_id,
+            json!({
+                "sessionId": &self.convex_session_id,
+                "threadId": thread_id,
+                "title": &fitted.title
+            }),
+        )
+        .await?;
+        Ok(fitted)
+    }
+
+    /// Fetch a single thread from the account.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The history client instance.
+    /// * `thread_id`: `&ThreadId` - The thread to fetch.
+    ///
+    /// # Returns
+    /// * `Result<ThreadSummary, T3Error>` - The thread, `T3Error::NotFound` if the account has no such thread.
+    pub async fn get_thread(&self, thread_id: &ThreadId) -> Result<ThreadSummary, T3Error> {
+        let value = self
+            .convex_call(
+                "query",
+                THREAD_QUERY,
+                json!({ "sessionId": &self.convex_session_id, "threadId": thread_id }),
+            )
+            .await?;
+        if value.is_null() {
+            return Err(T3Error::NotFound(format!("thread {}", thread_id)));
         }
+        Ok(self.parse_thread_summary(&value, *thread_id))
+    }
+
+    /// Pin or unpin a thread in the sidebar. Pinning a pinned thread is a no-op.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The history client instance.
+    /// * `thread_id`: `&ThreadId` - The thread to update.
+    /// * `pinned`: `bool` - Whether the thread should be pinned.
+    ///
+    /// # Returns
+    /// * `Result<ThreadSummary, T3Error>` - The updated thread, `T3Error::NotFound` if the account has no such thread.
+    pub async fn pin_thread(&self, thread_id: &ThreadId, pinned: bool) -> Result<ThreadSummary, T3Error> {
+        let thread = self.get_thread(thread_id).await?;
+        if thread.pinned == pinned {
+            return Ok(thread);
+        }
+        self.thread_mutation(
+            PIN_THREAD_MUTATION,
+            thread_id,
+            json!({ "sessionId": &self.convex_session_id, "threadId": thread_id, "pinned": pinned }),
+        )
+        .await?;
+        Ok(ThreadSummary { pinned, ..thread })
+    }
+
+    /// Archive or unarchive a thread. Archiving an archived thread is a no-op.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The history client instance.
+    /// * `thread_id`: `&ThreadId` - The thread to update.
+    /// * `archived`: `bool` - Whether the thread should be archived.
+    ///
+    /// # Returns
+    /// * `Result<ThreadSummary, T3Error>` - The updated thread, `T3Error::NotFound` if the account has no such thread.
+    pub async fn archive_thread(&self, thread_id: &ThreadId, archived: bool) -> Result<ThreadSummary, T3Error> {
+        let thread = self.get_thread(thread_id).await?;
+        if thread.archived == archived {
+            return Ok(thread);
+        }
+        self.thread_mutation(
+            ARCHIVE_THREAD_MUTATION,
+            thread_id,
+            json!({ "sessionId": &self.convex_session_id, "threadId": thread_id, "archived": archived }),
+        )
+        .await?;
+        Ok(ThreadSummary { archived, ..thread })
+    }
+
+    /// The Convex session ID sent with every call.
+    ///
+    /// # Returns
+    /// * `&str` - The session ID.
+    pub(crate) fn convex_session_id(&self) -> &str {
+        &self.convex_session_id
+    }
+
+    /// Run a mutation on a single thread, mapping a missing thread to `T3Error::NotFound`.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The history client instance.
+    /// * `path`: `&str` - The mutation path.
+    /// * `thread_id`: `&ThreadId` - The thread the mutation applies to.
+    /// * `args`: `Value` - The mutation arguments.
+    ///
+    /// # Returns
+    /// * `Result<Value, T3Error>` - The value returned by the mutation.
+    async fn thread_mutation(&self, path: &str, thread_id: &ThreadId, args: Value) -> Result<Value, T3Error> {
+        match self.convex_call("mutation", path, args).await {
+            Err(T3Error::Convex(message)) if message.to_ascii_lowercase().contains("not found") => {
+                Err(T3Error::NotFound(format!("thread {}", thread_id)))
+            }
+            other => other,
+        }
+    }
+
+    /// Call a Convex function over the HTTP API.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The history client instance.
+    /// * `kind`: `&str` - `"query"` or `"mutation"`.
+    /// * `path`: `&str` - The function path, such as `threads:get`.
+    /// * `args`: `Value` - The function arguments.
+    ///
+    /// # Returns
+    /// * `Result<Value, T3Error>` - The returned value, `T3Error::Auth` if the session is rejected.
+    pub(crate) async fn convex_call(&self, kind: &str, path: &str, args: Value) -> Result<Value, T3Error> {
+        let response = self
+            .client
+            .post(self.base_urls.convex_url(&format!("/api/{}", kind)))
+            .header("Content-Type", "application/json")
+            .header("Cookie", &self.cookies)
+            .header("Origin", &self.base_urls.app)
+            .json(&json!({ "path": path, "args": args, "format": "json" }))
+            .send()
+            .await?;
+        let status = response.status().as_u16();
+        if status == 401 || status == 403 {
+            return Err(T3Error::Auth(format!("convex returned HTTP {}", status)));
+        }
+        let body: Value = serde_json::from_str(&response.text().await?)?;
+        if body.get("status").and_then(|x| x.as_str()) == Some("success") {
+            return Ok(body.get("value").cloned().unwrap_or(Value::Null));
+        }
+        let message = body
+            .get("errorMessage")
+            .and_then(|x| x.as_str())
+            .unwrap_or("unknown error")
+            .to_string();
+        if message.to_ascii_lowercase().contains("unauth") {
+            return Err(T3Error::Auth(message));
+        }
+        Err(T3Error::Convex(message))
     }
 
     /// Parse ephemeral threads from browser sessionStorage data.
@@ -110,11 +428,11 @@ impl HistoryClient {
     /// * `Vec<Thread>` - Parsed threads from the sidebar cache.
     pub fn parse_sidebar_threads(&self, storage_json: &str) -> Vec<Thread> {
         let mut threads = Vec::new();
-        if let Ok(arr) = serde_json::from_str::<Value>(storage_json) {
-            if let Some(items) = arr.as_array() {
-                for item in items {
-                    threads.push(self.parse_sidebar_thread(item));
-                }
+        if let Ok(arr) = serde_json::from_str::<Value>(storage_json)
+            && let Some(items) = arr.as_array()
+        {
+            for item in items {
+                threads.push(self.parse_sidebar_thread(item));
             }
         }
         threads
@@ -187,6 +505,31 @@ impl HistoryClient {
         }
     }
 
+    fn parse_thread_summary(&self, v: &Value, id: ThreadId) -> ThreadSummary {
+        let flag = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| v.get(*key)?.as_bool())
+                .unwrap_or(false)
+        };
+        ThreadSummary {
+            id,
+            title: v.get("title").and_then(|x| x.as_str()).unwrap_or("").to_string(),
+            model: v.get("model").and_then(|x| x.as_str()).unwrap_or("").to_string(),
+            created_at: v
+                .get("createdAt")
+                .or_else(|| v.get("_creationTime"))
+                .and_then(|x| x.as_f64())
+                .unwrap_or(0.0),
+            updated_at: v
+                .get("updatedAt")
+                .or_else(|| v.get("lastMessageAt"))
+                .and_then(|x| x.as_f64())
+                .unwrap_or(0.0),
+            pinned: flag(&["pinned", "isPinned"]),
+            archived: flag(&["archived", "isArchived"]),
+        }
+    }
+
     fn parse_ephemeral_message(&self, v: &Value, thread_id: &str) -> ThreadMessage {
         let content = v
             .get("parts")
@@ -196,10 +539,8 @@ impl HistoryClient {
                     .filter_map(|part| {
                         if let Some(text) = part.get("text").and_then(|t| t.as_str()) {
                             Some(text.to_string())
-                        } else if let Some(text) = part.as_str() {
-                            Some(text.to_string())
                         } else {
-                            None
+                            part.as_str().map(|text| text.to_string())
                         }
                     })
                     .collect::<Vec<_>>()
@@ -230,3 +571,16 @@ impl HistoryClient {
         }
     }
 }
+
+/// The thread ID of a listed thread, `None` when the entry has no valid one.
+///
+/// # Arguments
+/// * `v` - &Value: The thread as returned by Convex.
+///
+/// # Returns
+/// * `Option<ThreadId>` - The parsed thread ID.
+fn summary_thread_id(v: &Value) -> Option<ThreadId> {
+    ["threadId", "_id"]
+        .iter()
+        .find_map(|key| v.get(*key)?.as_str()?.parse().ok())
+}
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use wreq::header::{HeaderMap, HeaderName, HeaderValue};
+use wreq_util::Emulation;
+
+use super::error::T3Error;
+
+/// Where the HTTP client sends its requests.
+#[derive(Default)]
+pub(crate) enum ProxySetting {
+    /// Connect directly, ignoring proxy environment variables.
+    #[default]
+    Direct,
+    /// Use this proxy for every request.
+    Proxy(wreq::Proxy),
+    /// Use the proxy at this URL for every request; parsed when the client is built.
+    Url(String),
+    /// Use the proxies named by `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY`.
+    Env,
+}
+
+/// Settings applied to the HTTP client shared by `Client`, `ModelsClient` and `UsageClient`.
+#[derive(Default)]
+pub(crate) struct HttpOptions {
+    pub timeout: Option<Duration>,
+    pub connect_timeout: Option<Duration>,
+    pub proxy: ProxySetting,
+    pub user_agent: Option<String>,
+    pub headers: Vec<(String, String)>,
+}
+
+///
+/// Builds the browser-emulating HTTP client used for every t3.chat request.
+///
+/// # Arguments
+/// * `options`: `HttpOptions` - Timeouts, proxy and header overrides.
+///
+/// # Returns
+/// * `Result<wreq::Client, T3Error>` - The client, or an error if a header or the proxy URL is
+///   invalid or the client cannot be created.
+pub(crate) fn build_http_client(options: HttpOptions) -> Result<wreq::Client, T3Error> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &options.headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| T3Error::InvalidHeader(name.clone()))?;
+        let header_value =
+            HeaderValue::from_str(value).map_err(|_| T3Error::InvalidHeader(name.clone()))?;
+        headers.insert(header_name, header_value);
+    }
+    let mut builder = wreq::Client::builder()
+        .emulation(Emulation::Chrome136)
+        .cookie_store(true);
+    if let Some(user_agent) = &options.user_agent {
+        let value = HeaderValue::from_str(user_agent)
+            .map_err(|_| T3Error::InvalidHeader("user-agent".to_string()))?;
+        builder = builder.user_agent(value);
+    }
+    if !headers.is_empty() {
+        builder = builder.default_headers(headers);
+    }
+    if let Some(timeout) = options.timeout {
+        builder = builder.timeout(timeout);
+    }
+    if let Some(timeout) = options.connect_timeout {
+        builder = builder.connect_timeout(timeout);
+    }
+    builder = match options.proxy {
+        ProxySetting::Direct => builder.no_proxy(),
+        ProxySetting::Proxy(proxy) => builder.proxy(proxy),
+        ProxySetting::Url(url) => {
+            let proxy = wreq::Proxy::all(url.as_str()).map_err(|_| T3Error::InvalidProxy(url))?;
+            builder.proxy(proxy)
+        }
+        ProxySetting::Env => builder,
+    };
+    Ok(builder.build()?)
+}
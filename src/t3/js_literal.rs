@@ -0,0 +1,498 @@
+use serde_json::{Map, Number, Value};
+
+/// How deeply literals may nest before the rest of a value is kept as source text, so
+/// malformed input cannot overflow the stack.
+const MAX_DEPTH: usize = 32;
+
+/// Bytes after which a `/` starts a regex literal rather than a division.
+const REGEX_PRECEDERS: &[u8] = b"(,=:[!&|?{};+-*%<>~^";
+
+enum Context {
+    Object(usize),
+    Template,
+    Interpolation,
+}
+
+///
+/// Finds the object literals in JavaScript source that start with `prefix`, in one pass over
+/// the source. Strings, template literals, regex literals and comments are skipped, so braces
+/// inside them do not count.
+///
+/// # Arguments
+/// * `source`: `&str` - The JavaScript source.
+/// * `prefix`: `&str` - What an object must start with, such as "{id:`".
+///
+/// # Returns
+/// * `Vec<&str>` - The objects, braces included, in the order they close.
+pub(crate) fn find_objects<'a>(source: &'a str, prefix: &str) -> Vec<&'a str> {
+    let bytes = source.as_bytes();
+    let mut contexts = Vec::new();
+    let mut objects = Vec::new();
+    let mut previous = b';';
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        if matches!(contexts.last(), Some(Context::Template)) {
+            match byte {
+                b'\\' => i += 1,
+                b'`' => {
+                    contexts.pop();
+                    previous = byte;
+                }
+                b'$' if bytes.get(i + 1) == Some(&b'{') => {
+                    contexts.push(Context::Interpolation);
+                    previous = b'{';
+                    i += 1;
+                }
+                _ => {}
+            }
+            i += 1;
+            continue;
+        }
+        match (byte, bytes.get(i + 1)) {
+            (b'/', Some(b'/')) => {
+                i = find(bytes, i, b"\n") + 1;
+                continue;
+            }
+            (b'/', Some(b'*')) => {
+                i = find(bytes, i + 2, b"*/") + 2;
+                continue;
+            }
+            _ => {}
+        }
+        match byte {
+            b'"' | b'\'' => i = skip_quoted(bytes, i),
+            b'`' => contexts.push(Context::Template),
+            b'/' if REGEX_PRECEDERS.contains(&previous) => i = skip_regex(bytes, i),
+            b'{' => contexts.push(Context::Object(i)),
+            b'}' => {
+                if let Some(Context::Object(start)) = contexts.pop()
+                    && bytes[start..].starts_with(prefix.as_bytes())
+                {
+                    objects.push(&source[start..=i]);
+                }
+            }
+            _ => {}
+        }
+        if !byte.is_ascii_whitespace() {
+            previous = byte;
+        }
+        i += 1;
+    }
+    objects
+}
+
+///
+/// Parses a JavaScript literal, such as an object from `find_objects`, into JSON. Keys may be
+/// unquoted, strings may use any quote, `!0` and `!1` are booleans, `new Set([...])` is an
+/// array and numeric expressions such as `.1/1e6` are evaluated. Any other expression, such
+/// as `e.OPENROUTER`, is kept as its source text.
+///
+/// # Arguments
+/// * `source`: `&str` - The literal.
+///
+/// # Returns
+/// * `Value` - The value, or the source text if it is not a literal.
+pub(crate) fn parse_literal(source: &str) -> Value {
+    let mut parser = Parser {
+        source,
+        bytes: source.as_bytes(),
+        pos: 0,
+    };
+    parser.value(0)
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn text(&self, start: usize, end: usize) -> &str {
+        self.source.get(start..end).unwrap_or_default()
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        let matched = self
+            .bytes
+            .get(self.pos..)
+            .is_some_and(|rest| rest.starts_with(text.as_bytes()));
+        if matched {
+            self.pos += text.len();
+        }
+        matched
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|byte| byte.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    ///
+    /// Parses the value at the current position, up to the next `,` or closing bracket. A
+    /// value that is not a literal, or is followed by more of an expression, is returned as
+    /// its source text.
+    ///
+    /// # Arguments
+    /// * `self`: `&mut Self` - The parser.
+    /// * `depth`: `usize` - How many literals enclose this one.
+    ///
+    /// # Returns
+    /// * `Value` - The value.
+    fn value(&mut self, depth: usize) -> Value {
+        self.skip_whitespace();
+        let start = self.pos;
+        let value = if depth < MAX_DEPTH {
+            self.literal(depth)
+        } else {
+            None
+        };
+        self.skip_whitespace();
+        match (value, self.peek()) {
+            (Some(value), None | Some(b',' | b'}' | b']' | b')')) => value,
+            _ => {
+                self.pos = start;
+                self.skip_expression();
+                Value::String(self.text(start, self.pos).trim().to_string())
+            }
+        }
+    }
+
+    fn literal(&mut self, depth: usize) -> Option<Value> {
+        match self.peek()? {
+            b'{' => self.object(depth),
+            b'[' => self.array(depth),
+            b'"' | b'\'' | b'`' => self.string().map(Value::String),
+            b'!' if self.eat("!0") => Some(Value::Bool(true)),
+            b'!' if self.eat("!1") => Some(Value::Bool(false)),
+            b'0'..=b'9' | b'.' | b'-' | b'+' => self.number(),
+            _ if self.eat("new Set(") => {
+                let items = self.value(depth + 1);
+                self.eat(")").then_some(items)
+            }
+            _ => {
+                let word = self.identifier();
+                match word.as_str() {
+                    "" => None,
+                    "true" => Some(Value::Bool(true)),
+                    "false" => Some(Value::Bool(false)),
+                    "null" | "undefined" => Some(Value::Null),
+                    "void" => {
+                        self.skip_whitespace();
+                        self.number().map(|_| Value::Null)
+                    }
+                    _ => Some(Value::String(word)),
+                }
+            }
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Option<Value> {
+        self.pos += 1;
+        let mut fields = Map::new();
+        loop {
+            self.skip_whitespace();
+            let before = self.pos;
+            match self.peek()? {
+                b'}' => {
+                    self.pos += 1;
+                    return Some(Value::Object(fields));
+                }
+                b']' | b')' => return None,
+                b',' => {
+                    self.pos += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            let key = self.key();
+            self.skip_whitespace();
+            match key {
+                Some(key) if self.peek() == Some(b':') => {
+                    self.pos += 1;
+                    let value = self.value(depth + 1);
+                    fields.insert(key, value);
+                }
+                // Shorthand properties, spreads and methods carry no literal value.
+                _ => self.skip_expression(),
+            }
+            if self.pos == before {
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Option<Value> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek()? {
+                b']' => {
+                    self.pos += 1;
+                    return Some(Value::Array(items));
+                }
+                b'}' | b')' => return None,
+                b',' => {
+                    self.pos += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            let before = self.pos;
+            items.push(self.value(depth + 1));
+            if self.pos == before {
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn key(&mut self) -> Option<String> {
+        match self.peek()? {
+            b'"' | b'\'' | b'`' => self.string(),
+            _ => Some(self.identifier()).filter(|key| !key.is_empty()),
+        }
+    }
+
+    fn identifier(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'$' | b'.'))
+        {
+            self.pos += 1;
+        }
+        self.text(start, self.pos).to_string()
+    }
+
+    ///
+    /// Reads a quoted string, decoding escapes. Interpolations in a template literal are kept
+    /// as their source, such as `${r}`.
+    ///
+    /// # Arguments
+    /// * `self`: `&mut Self` - The parser, at the opening quote.
+    ///
+    /// # Returns
+    /// * `Option<String>` - The string, or `None` if it is not terminated.
+    fn string(&mut self) -> Option<String> {
+        let quote = self.peek()?;
+        self.pos += 1;
+        let mut text = String::new();
+        loop {
+            let run = self.pos;
+            while self.peek().is_some_and(|byte| {
+                byte != quote && byte != b'\\' && !(quote == b'`' && byte == b'$')
+            }) {
+                self.pos += 1;
+            }
+            text.push_str(self.text(run, self.pos));
+            match self.peek()? {
+                b'\\' => {
+                    self.pos += 1;
+                    self.escape(&mut text)?;
+                }
+                b'$' if self.bytes.get(self.pos + 1) == Some(&b'{') => {
+                    let start = self.pos;
+                    self.pos = (find(self.bytes, self.pos, b"}") + 1).min(self.bytes.len());
+                    text.push_str(self.text(start, self.pos));
+                }
+                b'$' => {
+                    text.push('$');
+                    self.pos += 1;
+                }
+                _ => {
+                    self.pos += 1;
+                    return Some(text);
+                }
+            }
+        }
+    }
+
+    fn escape(&mut self, text: &mut String) -> Option<()> {
+        let escaped = self.source.get(self.pos..)?.chars().next()?;
+        self.pos += escaped.len_utf8();
+        match escaped {
+            'n' => text.push('\n'),
+            't' => text.push('\t'),
+            'r' => text.push('\r'),
+            'b' => text.push('\u{8}'),
+            'f' => text.push('\u{c}'),
+            'v' => text.push('\u{b}'),
+            '0' => text.push('\0'),
+            'x' => text.push(self.hex_char(2)),
+            'u' if self.eat("{") => {
+                let end = find(self.bytes, self.pos, b"}");
+                let code = u32::from_str_radix(self.text(self.pos, end), 16).ok();
+                self.pos = (end + 1).min(self.bytes.len());
+                text.push(code.and_then(char::from_u32).unwrap_or('\u{fffd}'));
+            }
+            'u' => {
+                let high = self.hex(4);
+                let low = match high {
+                    Some(0xd800..=0xdbff) if self.eat("\\u") => self.hex(4),
+                    _ => None,
+                };
+                let code = match (high, low) {
+                    (Some(high), Some(low @ 0xdc00..=0xdfff)) => {
+                        Some(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00))
+                    }
+                    (high, _) => high,
+                };
+                text.push(code.and_then(char::from_u32).unwrap_or('\u{fffd}'));
+            }
+            '\n' | '\r' => {}
+            other => text.push(other),
+        }
+        Some(())
+    }
+
+    fn hex(&mut self, len: usize) -> Option<u32> {
+        let digits = self.source.get(self.pos..self.pos + len)?;
+        let code = u32::from_str_radix(digits, 16).ok()?;
+        self.pos += len;
+        Some(code)
+    }
+
+    fn hex_char(&mut self, len: usize) -> char {
+        self.hex(len)
+            .and_then(char::from_u32)
+            .unwrap_or('\u{fffd}')
+    }
+
+    fn number(&mut self) -> Option<Value> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|byte| byte.is_ascii_digit() || b".eE+-*/".contains(&byte))
+        {
+            self.pos += 1;
+        }
+        let expression = self.text(start, self.pos);
+        if let Ok(integer) = expression.parse::<i64>() {
+            return Some(Value::from(integer));
+        }
+        evaluate(expression)
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+    }
+
+    ///
+    /// Moves past the rest of an expression, stopping at the `,` or closing bracket that ends
+    /// it. Nested brackets and strings are skipped whole.
+    ///
+    /// # Arguments
+    /// * `self`: `&mut Self` - The parser.
+    fn skip_expression(&mut self) {
+        let mut depth = 0usize;
+        while let Some(byte) = self.peek() {
+            match byte {
+                b'"' | b'\'' | b'`' => {
+                    self.pos = skip_quoted(self.bytes, self.pos);
+                    if self.pos == self.bytes.len() {
+                        return;
+                    }
+                }
+                b'{' | b'[' | b'(' => depth += 1,
+                b'}' | b']' | b')' if depth == 0 => return,
+                b'}' | b']' | b')' => depth -= 1,
+                b',' if depth == 0 => return,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+}
+
+///
+/// Evaluates a product or quotient of numbers, such as `.1/1e6`, left to right.
+///
+/// # Arguments
+/// * `expression`: `&str` - The expression.
+///
+/// # Returns
+/// * `Option<f64>` - The result, or `None` if a term is not a number.
+fn evaluate(expression: &str) -> Option<f64> {
+    let mut result: Option<f64> = None;
+    let mut operator = b'*';
+    let mut rest = expression;
+    loop {
+        let end = rest.find(['*', '/']).unwrap_or(rest.len());
+        let operand: f64 = rest[..end].parse().ok()?;
+        result = Some(match (result, operator) {
+            (None, _) => operand,
+            (Some(value), b'*') => value * operand,
+            (Some(value), _) => value / operand,
+        });
+        if end == rest.len() {
+            return result;
+        }
+        operator = rest.as_bytes()[end];
+        rest = &rest[end + 1..];
+    }
+}
+
+///
+/// Finds the end of a string or template literal. Line breaks end `'` and `"` strings, so a
+/// stray quote cannot swallow the rest of the source; template literals are read to the next
+/// unescaped backtick.
+///
+/// # Arguments
+/// * `bytes`: `&[u8]` - The source.
+/// * `start`: `usize` - The position of the opening quote.
+///
+/// # Returns
+/// * `usize` - The position of the closing quote, or where the scan stopped.
+fn skip_quoted(bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'\n' if quote != b'`' => return i,
+            byte if byte == quote => return i,
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+///
+/// Finds the end of a regex literal, skipping escapes and character classes, which may hold
+/// an unescaped `/`.
+///
+/// # Arguments
+/// * `bytes`: `&[u8]` - The source.
+/// * `start`: `usize` - The position of the opening `/`.
+///
+/// # Returns
+/// * `usize` - The position of the closing `/`, or where the scan stopped.
+fn skip_regex(bytes: &[u8], start: usize) -> usize {
+    let mut in_class = false;
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'\n' => return i,
+            b'[' => in_class = true,
+            b']' => in_class = false,
+            b'/' if !in_class => return i,
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+fn find(bytes: &[u8], from: usize, needle: &[u8]) -> usize {
+    bytes
+        .get(from..)
+        .and_then(|rest| rest.windows(needle.len()).position(|window| window == needle))
+        .map_or(bytes.len(), |offset| from + offset)
+}
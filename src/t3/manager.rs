@@ -0,0 +1,288 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::Mutex as AsyncMutex;
+
+use super::client::Client;
+use super::config::Config;
+use super::conversation::Conversation;
+use super::error::T3Error;
+use super::message::Message;
+use super::models::ModelId;
+
+/// How many conversations a `ConversationManager` keeps unless configured otherwise.
+pub const DEFAULT_MAX_CONVERSATIONS: usize = 1000;
+
+/// Called with the name and history of each conversation a `ConversationManager` evicts.
+pub type EvictCallback = Box<dyn Fn(String, Conversation) + Send + Sync>;
+
+/// Named conversations, such as one per chat channel, driven through a single `Client`. All
+/// methods take `&self`, so the manager can be shared between tasks behind an `Arc`. Sends to
+/// different names run concurrently; sends to the same name run one after the other, so
+/// each reply sees the one before it.
+///
+/// When more than the configured number of conversations exist, the least recently used idle
+/// ones are evicted and handed to the `on_evict` callback, if one is set.
+pub struct ConversationManager {
+    client: Client,
+    max_conversations: usize,
+    on_evict: Option<EvictCallback>,
+    sessions: Mutex<Sessions>,
+}
+
+#[derive(Default)]
+struct Sessions {
+    entries: HashMap<String, Session>,
+    clock: u64,
+}
+
+struct Session {
+    conversation: Arc<AsyncMutex<Conversation>>,
+    last_used: u64,
+}
+
+impl Sessions {
+    fn touch(&mut self, name: &str) -> Arc<AsyncMutex<Conversation>> {
+        self.clock += 1;
+        let clock = self.clock;
+        let session = self
+            .entries
+            .entry(name.to_string())
+            .or_insert_with(|| Session {
+                conversation: Arc::default(),
+                last_used: clock,
+            });
+        session.last_used = clock;
+        session.conversation.clone()
+    }
+
+    ///
+    /// Removes the least recently used idle conversations until at most `max` remain. A
+    /// conversation is idle when no send holds it.
+    ///
+    /// # Arguments
+    /// * `max`: `usize` - How many conversations to keep.
+    ///
+    /// # Returns
+    /// * `Vec<(String, Conversation)>` - The evicted conversations, oldest first.
+    fn evict(&mut self, max: usize) -> Vec<(String, Conversation)> {
+        let excess = self.entries.len().saturating_sub(max);
+        if excess == 0 {
+            return Vec::new();
+        }
+        let mut idle: Vec<(u64, String)> = self
+            .entries
+            .iter()
+            .filter(|(_, session)| Arc::strong_count(&session.conversation) == 1)
+            .map(|(name, session)| (session.last_used, name.clone()))
+            .collect();
+        idle.sort();
+        idle.into_iter()
+            .take(excess)
+            .filter_map(|(_, name)| {
+                let session = self.entries.remove(&name)?;
+                let conversation = Arc::into_inner(session.conversation)?.into_inner();
+                Some((name, conversation))
+            })
+            .collect()
+    }
+}
+
+impl ConversationManager {
+    ///
+    /// Creates a manager that sends through `client` and keeps up to
+    /// `DEFAULT_MAX_CONVERSATIONS` conversations.
+    ///
+    /// # Arguments
+    /// * `client`: `Client` - The client every conversation is sent through. Its own
+    ///   conversation is not used.
+    ///
+    /// # Returns
+    /// * `ConversationManager` - The manager, with no conversations.
+    pub fn new(client: Client) -> Self {
+        ConversationManager {
+            client,
+            max_conversations: DEFAULT_MAX_CONVERSATIONS,
+            on_evict: None,
+            sessions: Mutex::new(Sessions::default()),
+        }
+    }
+
+    ///
+    /// Returns this manager with a cap on how many conversations it keeps. At least one is
+    /// always kept.
+    ///
+    /// # Arguments
+    /// * `max`: `usize` - The most conversations to keep before evicting.
+    ///
+    /// # Returns
+    /// * `ConversationManager` - The updated manager.
+    pub fn with_max_conversations(mut self, max: usize) -> Self {
+        self.max_conversations = max.max(1);
+        self
+    }
+
+    ///
+    /// Returns this manager with a callback that receives every evicted conversation, e.g. to
+    /// save it with `ConversationStore`. It runs on the task whose send caused the eviction,
+    /// after the manager's lock is released, so it may call back into the manager.
+    ///
+    /// # Arguments
+    /// * `callback`: `impl Fn(String, Conversation) + Send + Sync + 'static` - Receives the
+    ///   name and the conversation.
+    ///
+    /// # Returns
+    /// * `ConversationManager` - The updated manager.
+    pub fn on_evict(
+        mut self,
+        callback: impl Fn(String, Conversation) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_evict = Some(Box::new(callback));
+        self
+    }
+
+    ///
+    /// Gets the client conversations are sent through.
+    ///
+    /// # Returns
+    /// * `&Client` - The client.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    ///
+    /// Sends a message in the named conversation, starting it if it does not exist yet, like
+    /// `Client::send_in`. Without a config, the conversation's config is used, then the
+    /// client's default.
+    ///
+    /// # Arguments
+    /// * `name`: `&str` - The conversation, e.g. a channel ID.
+    /// * `model`: `impl Into<ModelId>` - The model to use, as a `KnownModel` or an ID string.
+    /// * `new_message`: `Option<Message>` - Optional new message to append before sending.
+    /// * `config`: `impl Into<Option<Config>>` - Optional configuration for the request.
+    ///
+    /// # Returns
+    /// * `Result<Message, T3Error>` - The assistant's response message or an error.
+    pub async fn send(
+        &self,
+        name: &str,
+        model: impl Into<ModelId>,
+        new_message: Option<Message>,
+        config: impl Into<Option<Config>>,
+    ) -> Result<Message, T3Error> {
+        let conversation = self.sessions.lock().unwrap().touch(name);
+        self.flush_evicted();
+        let mut conversation = conversation.lock().await;
+        self.client
+            .send_in(&mut conversation, model, new_message, config)
+            .await
+    }
+
+    ///
+    /// Adds a conversation under `name`, e.g. one restored from storage, replacing any
+    /// conversation of that name.
+    ///
+    /// # Arguments
+    /// * `name`: `&str` - The conversation name.
+    /// * `conversation`: `Conversation` - The conversation.
+    pub fn insert(&self, name: &str, conversation: Conversation) {
+        {
+            let mut sessions = self.sessions.lock().unwrap();
+            sessions.clock += 1;
+            let session = Session {
+                conversation: Arc::new(AsyncMutex::new(conversation)),
+                last_used: sessions.clock,
+            };
+            sessions.entries.insert(name.to_string(), session);
+        }
+        self.flush_evicted();
+    }
+
+    ///
+    /// Gets a copy of the named conversation, waiting for a send in progress to finish.
+    ///
+    /// # Arguments
+    /// * `name`: `&str` - The conversation name.
+    ///
+    /// # Returns
+    /// * `Option<Conversation>` - The conversation, or `None` if there is none by that name.
+    pub async fn get(&self, name: &str) -> Option<Conversation> {
+        let conversation = self
+            .sessions
+            .lock()
+            .unwrap()
+            .entries
+            .get(name)?
+            .conversation
+            .clone();
+        let conversation = conversation.lock().await;
+        Some(conversation.clone())
+    }
+
+    ///
+    /// Forgets the named conversation, so the next send to it starts a new thread. A send
+    /// already in progress finishes, but its reply is not kept. The eviction callback is not
+    /// called.
+    ///
+    /// # Arguments
+    /// * `name`: `&str` - The conversation name.
+    ///
+    /// # Returns
+    /// * `bool` - Whether there was a conversation by that name.
+    pub fn reset(&self, name: &str) -> bool {
+        self.sessions
+            .lock()
+            .unwrap()
+            .entries
+            .remove(name)
+            .is_some()
+    }
+
+    ///
+    /// Lists the conversation names, most recently used first.
+    ///
+    /// # Returns
+    /// * `Vec<String>` - The names.
+    pub fn list(&self) -> Vec<String> {
+        let sessions = self.sessions.lock().unwrap();
+        let mut names: Vec<(u64, &String)> = sessions
+            .entries
+            .iter()
+            .map(|(name, session)| (session.last_used, name))
+            .collect();
+        names.sort_by_key(|&(last_used, _)| Reverse(last_used));
+        names.into_iter().map(|(_, name)| name.clone()).collect()
+    }
+
+    ///
+    /// Gets how many conversations the manager holds.
+    ///
+    /// # Returns
+    /// * `usize` - The number of conversations.
+    pub fn len(&self) -> usize {
+        self.sessions.lock().unwrap().entries.len()
+    }
+
+    ///
+    /// Checks whether the manager holds no conversations.
+    ///
+    /// # Returns
+    /// * `bool` - `true` if there are none.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn flush_evicted(&self) {
+        let evicted = self
+            .sessions
+            .lock()
+            .unwrap()
+            .evict(self.max_conversations);
+        if let Some(callback) = &self.on_evict {
+            for (name, conversation) in evicted {
+                callback(name, conversation);
+            }
+        }
+    }
+}
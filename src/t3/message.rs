@@ -1,31 +1,146 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use uuid::Uuid;
 
+use super::attachment::{Attachment, detect_file_type, sniff_image_type};
+use super::error::T3Error;
+use super::events::Usage;
+use super::tokens::estimate_tokens;
+
 /// Represents the role type in a message.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Type {
     Assistant,
     User,
+    System,
 }
 
-/// Represents the content type of a message.
-#[derive(Clone, Debug)]
+/// The content type earlier versions stored for a message. A message now holds its content as
+/// `Part`s, so this is only kept for the saved format and the deprecated `Message::content_type`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ContentType {
     Text,
     Image,
 }
 
+/// One part of a message, in the order `Message::parts` returns and the request body sends them:
+/// the text, then the images, then the attached files.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Part {
+    Text(String),
+    Image(GeneratedImage),
+    File(Attachment),
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Type::Assistant => "Assistant",
+            Type::User => "User",
+            Type::System => "System",
+        })
+    }
+}
+
+/// A web source cited by an answer generated with search enabled. `index` is the number the
+/// answer's inline markers use, such as `[2]`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    pub url: String,
+    pub title: Option<String>,
+    pub snippet: Option<String>,
+    pub index: usize,
+}
+
+/// An image in a message, either produced by an image generation model or sent to a vision
+/// model, with inline base64 data once known.
+/// `path` is set when the data was moved to disk by `HistoryImagePolicy::SpillToDir`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GeneratedImage {
+    pub url: String,
+    pub base64: Option<String>,
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+}
+
+impl GeneratedImage {
+    ///
+    /// Creates an image that has not been saved to disk.
+    ///
+    /// # Arguments
+    /// * `url`: `impl Into<String>` - The image URL.
+    /// * `base64`: `Option<String>` - Optional base64-encoded image data.
+    ///
+    /// # Returns
+    /// * `GeneratedImage` - The image.
+    pub fn new(url: impl Into<String>, base64: Option<String>) -> Self {
+        GeneratedImage {
+            url: url.into(),
+            base64,
+            path: None,
+        }
+    }
+}
+
 /// Represents a message with a role, content, and unique ID.
+/// Messages marked `local_only` stay in the conversation but are never sent to the server.
+/// `truncated` marks an assistant reply that was cut short, such as by `Client::send_cancellable`.
+/// `reasoning` holds the reasoning a model streamed before its answer; the text is the answer only.
+/// `citations` lists the sources of a search-backed answer, in the order they were received;
+/// a source sent again unchanged is listed once, while the same URL under another index is kept.
+/// The text, images and attached files are stored once, as the message's `parts`; `text`,
+/// `image_url`, `base64`, `images` and `attachments` read them.
+/// `created_at` is when the message was constructed, or when the first part of a streamed reply
+/// arrived; `completed_at` is when an assistant reply finished streaming.
+/// `model` is the model an assistant reply was requested from, and `usage` the token counts the
+/// server reported for it; both are `None` for other messages and in older saved conversations.
+///
+/// Equality compares the content of two messages and ignores `id`, `created_at`,
+/// `completed_at`, `model` and `usage`, so a reply can be compared with a message built in a
+/// test. Compare `id` separately to check that two values are the same message.
+///
+/// Messages are saved with the fields of earlier versions (`content`, `content_type`,
+/// `image_url`, `base64_data`, `images` and `attachments`), so saved conversations and
+/// `ConversationStore` columns keep their layout.
 #[derive(Clone, Debug)]
 pub struct Message {
     pub id: String,
     pub role: Type,
-    pub content: String,
-    pub content_type: ContentType,
-    pub image_url: Option<String>,
-    pub base64_data: Option<String>,
+    parts: Vec<Part>,
+    pub local_only: bool,
+    pub truncated: bool,
+    pub reasoning: Option<String>,
+    pub citations: Vec<Citation>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub model: Option<String>,
+    pub usage: Option<Usage>,
 }
 
 impl Message {
+    ///
+    /// Starts building a message, for combinations the constructors do not cover such as a
+    /// fixed ID together with an image.
+    ///
+    /// # Arguments
+    /// * `role`: `Type` - The role of the message sender.
+    ///
+    /// # Returns
+    /// * `MessageBuilder` - A builder for an empty text message.
+    pub fn builder(role: Type) -> MessageBuilder {
+        MessageBuilder {
+            message: Message::new(role, String::new()),
+        }
+    }
+
     ///
     /// Creates a new text `Message` with a randomly generated ID.
     ///
@@ -36,14 +151,7 @@ impl Message {
     /// # Returns
     /// * `Message` - A new text message instance.
     pub fn new(role: Type, content: String) -> Self {
-        Self {
-            id: Uuid::new_v4().to_string(),
-            role,
-            content,
-            content_type: ContentType::Text,
-            image_url: None,
-            base64_data: None,
-        }
+        Self::with_parts(Uuid::new_v4().to_string(), role, text_part(content))
     }
 
     ///
@@ -57,14 +165,67 @@ impl Message {
     /// # Returns
     /// * `Message` - A new image message instance.
     pub fn new_image(role: Type, url: String, base64: Option<String>) -> Self {
-        Self {
-            id: Uuid::new_v4().to_string(),
-            role,
-            content: url.clone(),
-            content_type: ContentType::Image,
-            image_url: Some(url),
-            base64_data: base64,
+        let image = Part::Image(GeneratedImage::new(url, base64));
+        Self::with_parts(Uuid::new_v4().to_string(), role, vec![image])
+    }
+
+    ///
+    /// Creates an image message from a PNG, JPEG, WebP or GIF file, e.g. for a vision model.
+    /// The format is detected from the file's contents, not its extension.
+    ///
+    /// # Arguments
+    /// * `role`: `Type` - The role of the message sender.
+    /// * `path`: `&Path` - The image file.
+    ///
+    /// # Returns
+    /// * `Result<Message, T3Error>` - The message with the image inline as a `data:` URL,
+    ///   `T3Error::Io` if the file cannot be read, or `T3Error::UnsupportedAttachment` naming
+    ///   the detected type if it is not a supported image.
+    pub fn new_image_from_file(role: Type, path: &Path) -> Result<Self, T3Error> {
+        let bytes = std::fs::read(path)?;
+        Self::new_image_from_bytes(role, &bytes, None).map_err(|err| match err {
+            T3Error::UnsupportedAttachment(reason) => {
+                T3Error::UnsupportedAttachment(format!("{}: {}", path.display(), reason))
+            }
+            other => other,
+        })
+    }
+
+    ///
+    /// Creates an image message from PNG, JPEG, WebP or GIF data in memory.
+    ///
+    /// # Arguments
+    /// * `role`: `Type` - The role of the message sender.
+    /// * `bytes`: `&[u8]` - The image data.
+    /// * `mime_type`: `Option<&str>` - The type the data is expected to be, or `None` to take
+    ///   whatever the magic bytes say.
+    ///
+    /// # Returns
+    /// * `Result<Message, T3Error>` - The message with the image's base64 data and a `data:`
+    ///   URL, or `T3Error::UnsupportedAttachment` naming the detected type if the data is not a
+    ///   supported image or does not match `mime_type`.
+    pub fn new_image_from_bytes(
+        role: Type,
+        bytes: &[u8],
+        mime_type: Option<&str>,
+    ) -> Result<Self, T3Error> {
+        let detected = sniff_image_type(bytes).ok_or_else(|| {
+            T3Error::UnsupportedAttachment(format!(
+                "not a PNG, JPEG, WebP or GIF image (detected {})",
+                detect_file_type(bytes)
+            ))
+        })?;
+        if let Some(expected) = mime_type
+            && !expected.eq_ignore_ascii_case(detected)
+        {
+            return Err(T3Error::UnsupportedAttachment(format!(
+                "expected {} but the data is {}",
+                expected, detected
+            )));
         }
+        let base64 = general_purpose::STANDARD.encode(bytes);
+        let url = format!("data:{};base64,{}", detected, base64);
+        Ok(Self::new_image(role, url, Some(base64)))
     }
 
     ///
@@ -78,13 +239,652 @@ impl Message {
     /// # Returns
     /// * `Message` - A new message instance with the provided ID.
     pub fn with_id(id: String, role: Type, content: String) -> Self {
+        Self::with_parts(id, role, text_part(content))
+    }
+
+    ///
+    /// Creates a message from its ID, role and parts in their order, with the other fields
+    /// empty.
+    ///
+    /// # Arguments
+    /// * `id`: `String` - The message ID.
+    /// * `role`: `Type` - The role of the message sender.
+    /// * `parts`: `Vec<Part>` - The parts, text first, then images, then files.
+    ///
+    /// # Returns
+    /// * `Message` - The message.
+    fn with_parts(id: String, role: Type, parts: Vec<Part>) -> Self {
         Self {
             id,
             role,
-            content,
-            content_type: ContentType::Text,
-            image_url: None,
-            base64_data: None,
+            parts,
+            local_only: false,
+            truncated: false,
+            reasoning: None,
+            citations: Vec::new(),
+            created_at: Utc::now(),
+            completed_at: None,
+            model: None,
+            usage: None,
         }
     }
+
+    ///
+    /// Creates a new text `Message` with a file attached, e.g. an image for a vision model.
+    /// The file's data is sent with this message only; later requests just name the file.
+    ///
+    /// # Arguments
+    /// * `role`: `Type` - The role of the message sender.
+    /// * `content`: `String` - The text content of the message.
+    /// * `attachment`: `Attachment` - The file to send with the message.
+    ///
+    /// # Returns
+    /// * `Message` - A new text message with the attachment.
+    pub fn new_with_attachment(role: Type, content: String, attachment: Attachment) -> Self {
+        let mut message = Self::new(role, content);
+        message.parts.push(Part::File(attachment));
+        message
+    }
+
+    ///
+    /// Creates a message from its parts. Text parts are joined with blank lines.
+    ///
+    /// # Arguments
+    /// * `role`: `Type` - The role of the message sender.
+    /// * `parts`: `Vec<Part>` - The parts, such as a question and an image.
+    ///
+    /// # Returns
+    /// * `Message` - A new message; an image message if any part is an image.
+    pub fn from_parts(role: Type, parts: Vec<Part>) -> Self {
+        parts
+            .into_iter()
+            .fold(Self::builder(role), MessageBuilder::part)
+            .build()
+    }
+
+    ///
+    /// Gets the text of the message, without the image URL `content` gives for an image-only
+    /// message.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The message.
+    ///
+    /// # Returns
+    /// * `&str` - The text, empty if there is none.
+    pub fn text(&self) -> &str {
+        match self.parts.first() {
+            Some(Part::Text(text)) => text,
+            _ => "",
+        }
+    }
+
+    ///
+    /// Replaces the text of the message, keeping its images and files.
+    ///
+    /// # Arguments
+    /// * `self`: `&mut Self` - The message.
+    /// * `text`: `impl Into<String>` - The new text, or an empty string to remove it.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        let has_text = matches!(self.parts.first(), Some(Part::Text(_)));
+        match (has_text, text.is_empty()) {
+            (true, true) => {
+                self.parts.remove(0);
+            }
+            (true, false) => self.parts[0] = Part::Text(text),
+            (false, false) => self.parts.insert(0, Part::Text(text)),
+            (false, true) => {}
+        }
+    }
+
+    ///
+    /// Gets the content of the message as earlier versions stored it: the text, or the first
+    /// image URL when an image message has no text.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The message.
+    ///
+    /// # Returns
+    /// * `&str` - The content, empty if there is none.
+    pub fn content(&self) -> &str {
+        match (self.text(), self.image_url()) {
+            ("", Some(url)) => url,
+            (text, _) => text,
+        }
+    }
+
+    ///
+    /// Gets the URL of the message's first image.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The message.
+    ///
+    /// # Returns
+    /// * `Option<&str>` - The URL, or `None` if the message has no image.
+    pub fn image_url(&self) -> Option<&str> {
+        self.images().next().map(|image| image.url.as_str())
+    }
+
+    ///
+    /// Gets the base64 data of the message's first image, once it is known.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The message.
+    ///
+    /// # Returns
+    /// * `Option<&str>` - The data, or `None` for a message without an image or with an image
+    ///   not downloaded.
+    pub fn base64(&self) -> Option<&str> {
+        self.images().next().and_then(|image| image.base64.as_deref())
+    }
+
+    ///
+    /// Gets the content type earlier versions stored for the message.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The message.
+    ///
+    /// # Returns
+    /// * `ContentType` - `Image` if the message has an image, otherwise `Text`.
+    #[deprecated(note = "match on `parts`, or use `image_url` or `images`")]
+    pub fn content_type(&self) -> ContentType {
+        match self.image_url() {
+            Some(_) => ContentType::Image,
+            None => ContentType::Text,
+        }
+    }
+
+    ///
+    /// Gets the base64 data of the message's first image.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The message.
+    ///
+    /// # Returns
+    /// * `Option<&str>` - The data, or `None` if there is none.
+    #[deprecated(note = "use `base64`")]
+    pub fn base64_data(&self) -> Option<&str> {
+        self.base64()
+    }
+
+    ///
+    /// Gets the parts of the message: its text, then its images, then its attachments.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The message.
+    ///
+    /// # Returns
+    /// * `&[Part]` - The parts.
+    pub fn parts(&self) -> &[Part] {
+        &self.parts
+    }
+
+    ///
+    /// Gets every image of the message, e.g. all the images of an image generation reply.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The message.
+    ///
+    /// # Returns
+    /// * `impl Iterator<Item = &GeneratedImage>` - The images, in order.
+    pub fn images(&self) -> impl Iterator<Item = &GeneratedImage> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Image(image) => Some(image),
+            _ => None,
+        })
+    }
+
+    ///
+    /// Gets every image of the message for changing, e.g. to drop their inline data.
+    ///
+    /// # Arguments
+    /// * `self`: `&mut Self` - The message.
+    ///
+    /// # Returns
+    /// * `impl Iterator<Item = &mut GeneratedImage>` - The images, in order.
+    pub fn images_mut(&mut self) -> impl Iterator<Item = &mut GeneratedImage> {
+        self.parts.iter_mut().filter_map(|part| match part {
+            Part::Image(image) => Some(image),
+            _ => None,
+        })
+    }
+
+    ///
+    /// Gets the files attached to the message.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The message.
+    ///
+    /// # Returns
+    /// * `impl Iterator<Item = &Attachment>` - The attachments, in order.
+    pub fn attachments(&self) -> impl Iterator<Item = &Attachment> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::File(attachment) => Some(attachment),
+            _ => None,
+        })
+    }
+
+    ///
+    /// Adds a part in its place: text before the images, images before the files. Text is
+    /// joined to any text already there with a blank line.
+    ///
+    /// # Arguments
+    /// * `self`: `&mut Self` - The message.
+    /// * `part`: `Part` - The part.
+    fn push_part(&mut self, part: Part) {
+        match part {
+            Part::Text(text) if self.text().is_empty() => self.set_text(text),
+            Part::Text(text) => {
+                let joined = format!("{}\n\n{}", self.text(), text);
+                self.set_text(joined);
+            }
+            Part::Image(image) => {
+                let index = self
+                    .parts
+                    .iter()
+                    .position(|part| matches!(part, Part::File(_)))
+                    .unwrap_or(self.parts.len());
+                self.parts.insert(index, Part::Image(image));
+            }
+            Part::File(attachment) => self.parts.push(Part::File(attachment)),
+        }
+    }
+
+    ///
+    /// Estimates the tokens in the message content with `tokens::estimate_tokens`. The
+    /// per-message request overhead is not included; see `Client::conversation_tokens`.
+    /// Local-only messages are never sent and count as zero, as in `tokens::message_tokens`.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The message.
+    ///
+    /// # Returns
+    /// * `usize` - The estimated number of tokens.
+    pub fn estimated_tokens(&self) -> usize {
+        if self.local_only {
+            0
+        } else {
+            estimate_tokens(self.content())
+        }
+    }
+
+    ///
+    /// Creates a local-only note that is kept in the conversation but never sent.
+    ///
+    /// # Arguments
+    /// * `content`: `String` - The text of the note.
+    ///
+    /// # Returns
+    /// * `Message` - A new user-role message with `local_only` set.
+    pub fn new_note(content: String) -> Self {
+        Self {
+            local_only: true,
+            ..Self::new(Type::User, content)
+        }
+    }
+}
+
+///
+/// Wraps text as the parts of a text message.
+///
+/// # Arguments
+/// * `text`: `String` - The text.
+///
+/// # Returns
+/// * `Vec<Part>` - One text part, or none for empty text.
+fn text_part(text: String) -> Vec<Part> {
+    if text.is_empty() {
+        Vec::new()
+    } else {
+        vec![Part::Text(text)]
+    }
+}
+
+impl PartialEq for Message {
+    fn eq(&self, other: &Self) -> bool {
+        self.role == other.role
+            && self.parts == other.parts
+            && self.local_only == other.local_only
+            && self.truncated == other.truncated
+            && self.reasoning == other.reasoning
+            && self.citations == other.citations
+    }
+}
+
+/// The saved form of a `Message`, with the fields of earlier versions. `image_url` and
+/// `base64_data` repeat the first of `images` so older readers still find the image; when
+/// reading, `images` wins, and the image fields of a `text` message are ignored.
+#[derive(Serialize, Deserialize)]
+struct StoredMessage<'a> {
+    id: Cow<'a, str>,
+    role: Cow<'a, Type>,
+    content: Cow<'a, str>,
+    content_type: ContentType,
+    image_url: Option<Cow<'a, str>>,
+    base64_data: Option<Cow<'a, str>>,
+    #[serde(default)]
+    local_only: bool,
+    #[serde(default)]
+    attachments: Vec<Cow<'a, Attachment>>,
+    #[serde(default)]
+    truncated: bool,
+    #[serde(default)]
+    reasoning: Option<Cow<'a, str>>,
+    #[serde(default)]
+    citations: Cow<'a, [Citation]>,
+    #[serde(default)]
+    images: Vec<Cow<'a, GeneratedImage>>,
+    #[serde(default = "Utc::now")]
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    completed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    model: Option<Cow<'a, str>>,
+    #[serde(default)]
+    usage: Option<Cow<'a, Usage>>,
+}
+
+impl<'a> From<&'a Message> for StoredMessage<'a> {
+    fn from(message: &'a Message) -> Self {
+        let content_type = match message.image_url() {
+            Some(_) => ContentType::Image,
+            None => ContentType::Text,
+        };
+        StoredMessage {
+            id: Cow::Borrowed(&message.id),
+            role: Cow::Borrowed(&message.role),
+            content: Cow::Borrowed(message.content()),
+            content_type,
+            image_url: message.image_url().map(Cow::Borrowed),
+            base64_data: message.base64().map(Cow::Borrowed),
+            local_only: message.local_only,
+            attachments: message.attachments().map(Cow::Borrowed).collect(),
+            truncated: message.truncated,
+            reasoning: message.reasoning.as_deref().map(Cow::Borrowed),
+            citations: Cow::Borrowed(&message.citations),
+            images: message.images().map(Cow::Borrowed).collect(),
+            created_at: message.created_at,
+            completed_at: message.completed_at,
+            model: message.model.as_deref().map(Cow::Borrowed),
+            usage: message.usage.as_ref().map(Cow::Borrowed),
+        }
+    }
+}
+
+impl From<StoredMessage<'_>> for Message {
+    fn from(stored: StoredMessage<'_>) -> Self {
+        let images: Vec<GeneratedImage> = match stored.content_type {
+            ContentType::Text => Vec::new(),
+            ContentType::Image if stored.images.is_empty() => stored
+                .image_url
+                .as_deref()
+                .map(|url| {
+                    GeneratedImage::new(url, stored.base64_data.as_deref().map(str::to_string))
+                })
+                .into_iter()
+                .collect(),
+            ContentType::Image => stored.images.into_iter().map(Cow::into_owned).collect(),
+        };
+        // An image message without text stored its first image URL as the content.
+        let text = match images.first().map(|image| image.url.as_str()) {
+            Some(url) if url == stored.content => String::new(),
+            _ => stored.content.into_owned(),
+        };
+        let mut parts = text_part(text);
+        parts.extend(images.into_iter().map(Part::Image));
+        parts.extend(
+            stored
+                .attachments
+                .into_iter()
+                .map(|attachment| Part::File(attachment.into_owned())),
+        );
+        Message {
+            id: stored.id.into_owned(),
+            role: stored.role.into_owned(),
+            parts,
+            local_only: stored.local_only,
+            truncated: stored.truncated,
+            reasoning: stored.reasoning.map(Cow::into_owned),
+            citations: stored.citations.into_owned(),
+            created_at: stored.created_at,
+            completed_at: stored.completed_at,
+            model: stored.model.map(Cow::into_owned),
+            usage: stored.usage.map(Cow::into_owned),
+        }
+    }
+}
+
+impl Serialize for Message {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        StoredMessage::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Message {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        StoredMessage::deserialize(deserializer).map(Message::from)
+    }
+}
+
+/// Renders the message as a transcript line such as `User: hi`, or `User [local]: hi` for a
+/// local-only note. Images and attachments are summarized as `[image: <url>]` and
+/// `[attachment: <file name>]`, and inline `data:` URLs are cut before their payload.
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.role)?;
+        if self.local_only {
+            write!(f, " [local]")?;
+        }
+        write!(f, ":")?;
+        if !self.text().is_empty() {
+            write!(f, " {}", self.text())?;
+        }
+        for image in self.images() {
+            write!(f, " [image: {}]", summarize_url(&image.url))?;
+        }
+        for attachment in self.attachments() {
+            write!(f, " [attachment: {}]", attachment.file_name)?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// Shortens a `data:` URL to its media type; other URLs are returned as they are.
+///
+/// # Arguments
+/// * `url`: `&str` - The image URL.
+///
+/// # Returns
+/// * `String` - The URL for display.
+fn summarize_url(url: &str) -> String {
+    match url.strip_prefix("data:") {
+        Some(rest) => {
+            let media_type = rest.split([';', ',']).next().unwrap_or_default();
+            format!("data:{},… ({} bytes)", media_type, url.len())
+        }
+        None => url.to_string(),
+    }
+}
+
+///
+/// Gets the media type of an image from its `data:` URL or file extension.
+///
+/// # Arguments
+/// * `url`: `&str` - The image URL.
+///
+/// # Returns
+/// * `String` - The media type, `image/png` if it cannot be told.
+pub(crate) fn image_media_type(url: &str) -> String {
+    if let Some(rest) = url.strip_prefix("data:") {
+        return rest
+            .split([';', ','])
+            .next()
+            .unwrap_or_default()
+            .to_string();
+    }
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let extension = path
+        .rsplit('.')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        "gif" => "image/gif",
+        _ => "image/png",
+    }
+    .to_string()
+}
+
+/// Builds a `Message` field by field, e.g.
+/// `Message::builder(Type::User).id("msg-1").text("hi").image(url, None).build()`.
+/// Created with `Message::builder`.
+#[derive(Clone, Debug)]
+pub struct MessageBuilder {
+    message: Message,
+}
+
+impl MessageBuilder {
+    ///
+    /// Sets the message ID instead of a random one.
+    ///
+    /// # Arguments
+    /// * `id`: `impl Into<String>` - The ID.
+    ///
+    /// # Returns
+    /// * `MessageBuilder` - The updated builder.
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.message.id = id.into();
+        self
+    }
+
+    ///
+    /// Sets the text of the message.
+    ///
+    /// # Arguments
+    /// * `text`: `impl Into<String>` - The text.
+    ///
+    /// # Returns
+    /// * `MessageBuilder` - The updated builder.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.message.set_text(text);
+        self
+    }
+
+    ///
+    /// Adds an image, which makes the message an image message, like one created with
+    /// `Message::new_image`.
+    ///
+    /// # Arguments
+    /// * `url`: `impl Into<String>` - The image URL.
+    /// * `base64`: `Option<String>` - Optional base64-encoded image data.
+    ///
+    /// # Returns
+    /// * `MessageBuilder` - The updated builder.
+    pub fn image(mut self, url: impl Into<String>, base64: Option<String>) -> Self {
+        self.message.push_part(Part::Image(GeneratedImage::new(url, base64)));
+        self
+    }
+
+    ///
+    /// Adds a part. Text parts are appended to the text, separated by a blank line.
+    ///
+    /// # Arguments
+    /// * `part`: `Part` - The part.
+    ///
+    /// # Returns
+    /// * `MessageBuilder` - The updated builder.
+    pub fn part(mut self, part: Part) -> Self {
+        self.message.push_part(part);
+        self
+    }
+
+    ///
+    /// Attaches a file, e.g. an image for a vision model.
+    ///
+    /// # Arguments
+    /// * `attachment`: `Attachment` - The file to send with the message.
+    ///
+    /// # Returns
+    /// * `MessageBuilder` - The updated builder.
+    pub fn attachment(mut self, attachment: Attachment) -> Self {
+        self.message.push_part(Part::File(attachment));
+        self
+    }
+
+    ///
+    /// Sets the reasoning that preceded the answer.
+    ///
+    /// # Arguments
+    /// * `reasoning`: `impl Into<String>` - The reasoning text.
+    ///
+    /// # Returns
+    /// * `MessageBuilder` - The updated builder.
+    pub fn reasoning(mut self, reasoning: impl Into<String>) -> Self {
+        self.message.reasoning = Some(reasoning.into());
+        self
+    }
+
+    ///
+    /// Sets the model the message was requested from.
+    ///
+    /// # Arguments
+    /// * `model`: `impl Into<String>` - The model ID.
+    ///
+    /// # Returns
+    /// * `MessageBuilder` - The updated builder.
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.message.model = Some(model.into());
+        self
+    }
+
+    ///
+    /// Sets the token counts reported for the message.
+    ///
+    /// # Arguments
+    /// * `usage`: `Usage` - The token counts.
+    ///
+    /// # Returns
+    /// * `MessageBuilder` - The updated builder.
+    pub fn usage(mut self, usage: Usage) -> Self {
+        self.message.usage = Some(usage);
+        self
+    }
+
+    ///
+    /// Keeps the message in the conversation without sending it. See `Message::new_note`.
+    ///
+    /// # Arguments
+    /// * `local_only`: `bool` - Whether the message is local-only.
+    ///
+    /// # Returns
+    /// * `MessageBuilder` - The updated builder.
+    pub fn local_only(mut self, local_only: bool) -> Self {
+        self.message.local_only = local_only;
+        self
+    }
+
+    ///
+    /// Sets when the message was created instead of now.
+    ///
+    /// # Arguments
+    /// * `created_at`: `DateTime<Utc>` - The creation time.
+    ///
+    /// # Returns
+    /// * `MessageBuilder` - The updated builder.
+    pub fn created_at(mut self, created_at: DateTime<Utc>) -> Self {
+        self.message.created_at = created_at;
+        self
+    }
+
+    ///
+    /// Builds the message.
+    ///
+    /// # Returns
+    /// * `Message` - The message.
+    pub fn build(self) -> Message {
+        self.message
+    }
 }
@@ -1,7 +1,33 @@
 #![allow(dead_code)]
+pub mod attachment;
+pub mod backend;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod client;
+pub mod clock;
 pub mod config;
+pub mod conversation;
+pub mod cookies;
+pub mod cost;
+pub mod dump;
+pub mod error;
+pub mod events;
+pub mod export;
 pub mod history;
+mod http;
+mod js_literal;
+pub mod manager;
 pub mod message;
 pub mod models;
+pub mod openai_compat;
+pub mod settings;
+#[cfg(feature = "sqlite")]
+pub mod store;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod thread;
+pub mod tokens;
+mod trace;
 pub mod usage;
+#[cfg(feature = "vcr")]
+pub mod vcr;
@@ -1,14 +1,78 @@
-use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use futures_util::{StreamExt, stream};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use wreq_util::Emulation;
+
+use super::client::Client;
+use super::config::{BaseUrls, Config};
+use super::error::T3Error;
+use super::http::{HttpOptions, build_http_client};
+use super::js_literal::{find_objects, parse_literal};
+use super::message::{Message, Type};
+use super::trace;
 
 #[derive(Debug, Clone)]
 pub struct ModelStatus {
     pub name: String,
     pub indicator: String,
     pub description: String,
+    pub checked_at: DateTime<Utc>,
+    pub latency: Option<Duration>,
+}
+
+impl ModelStatus {
+    /// Create a status checked now, without a latency measurement.
+    ///
+    /// # Arguments
+    /// * `name` - &str: The model or component name.
+    /// * `indicator` - &str: `operational`, `degraded`, `down` or `unknown`.
+    /// * `description` - &str: A human-readable description.
+    ///
+    /// # Returns
+    /// * Self - The status.
+    pub fn new(name: &str, indicator: &str, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            indicator: indicator.to_string(),
+            description: description.to_string(),
+            checked_at: Utc::now(),
+            latency: None,
+        }
+    }
+}
+
+/// Limits for `ModelsClient::probe_models`, which spends a real message per probed model.
+#[derive(Debug, Clone)]
+pub struct ProbeOptions {
+    /// How many models are probed at the same time.
+    pub max_concurrency: usize,
+    /// How long a probe may take before the model is reported as `degraded`.
+    pub timeout: Duration,
+    /// How long a probe result is reused before the same model is probed again.
+    pub cooldown: Duration,
+}
+
+impl Default for ProbeOptions {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 2,
+            timeout: Duration::from_secs(20),
+            cooldown: Duration::from_secs(5 * 60),
+        }
+    }
 }
 
+const PROBE_PROMPT: &str = "Reply with the single word: ok";
+
 #[derive(Debug, Clone, Default)]
 pub struct ModelBenchmark {
     pub model_id: String,
@@ -17,7 +81,8 @@ pub struct ModelBenchmark {
     pub description: String,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ModelCost {
     pub input: Option<f64>,
     pub output: Option<f64>,
@@ -26,7 +91,19 @@ pub struct ModelCost {
     pub fixed: Option<f64>,
 }
 
-#[derive(Debug, Clone, Default)]
+/// Projected spend for one request, from `estimate_cost`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CostEstimate {
+    /// Provider cost in US dollars, or `None` if the model has no token pricing.
+    pub usd: Option<f64>,
+    /// Credits t3.chat deducts per message, if the model lists a credit amount.
+    pub credits: Option<i64>,
+    /// Whether the credits come from the premium allowance rather than the standard one.
+    pub premium: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ModelLimits {
     pub app_max_input_tokens: Option<i64>,
     pub app_max_output_tokens: Option<i64>,
@@ -34,7 +111,11 @@ pub struct ModelLimits {
     pub provider_max_output_tokens: Option<i64>,
 }
 
-#[derive(Debug, Clone, Default)]
+/// A model from the t3.chat catalog. `context_length` is the model's listed context length, or
+/// else its provider (then app) input limit; `max_output_tokens` is the provider (then app)
+/// output limit. Both are `None` when the catalog lists nothing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ModelInfo {
     pub id: String,
     pub name: String,
@@ -50,6 +131,8 @@ pub struct ModelInfo {
     pub cost: ModelCost,
     pub credit_amount: Option<i64>,
     pub limits: ModelLimits,
+    pub context_length: Option<u32>,
+    pub max_output_tokens: Option<u32>,
     pub features: Vec<String>,
     pub search_tags: Vec<String>,
     pub api_key_support: String,
@@ -57,36 +140,398 @@ pub struct ModelInfo {
     pub knowledge_cutoff_date: Option<String>,
     pub retired_on: Option<String>,
     pub succeded_by: Option<String>,
+    pub supports_vision: bool,
+    pub supports_image_generation: bool,
+    pub supports_reasoning: bool,
+    pub supports_search: bool,
+}
+
+/// A t3.chat subscription level, for `ModelInfo::accessible_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Plan {
+    Free,
+    Pro,
+}
+
+impl ModelInfo {
+    ///
+    /// Checks whether an account on the given plan can use this model. Free accounts cannot
+    /// use models that require Pro or spend premium credits; disabled models are usable on no
+    /// plan.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The model.
+    /// * `plan`: `Plan` - The account's plan.
+    ///
+    /// # Returns
+    /// * `bool` - `true` if the plan allows the model.
+    pub fn accessible_with(&self, plan: Plan) -> bool {
+        !self.disabled
+            && match plan {
+                Plan::Free => !self.requires_pro && !self.premium,
+                Plan::Pro => true,
+            }
+    }
+}
+
+/// Model IDs from the t3.chat catalog. Any other ID can still be passed as a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum KnownModel {
+    Claude4Opus,
+    Claude4Sonnet,
+    Claude37,
+    Claude35,
+    ClaudeFable5,
+    Gpt4o,
+    Gpt4oMini,
+    GptO3Mini,
+    GptO4Mini,
+    O3Full,
+    O3Pro,
+    Gemini25Pro,
+    Gemini25Flash,
+    Gemini25FlashLite,
+    Gemini20Flash,
+    GrokV3,
+    GrokV4,
+    DeepseekV3,
+    DeepseekR1,
+    DeepseekR1Groq,
+    Llama3370b,
+    Qwen332b,
+    Qwen3235b,
+    GptImage1,
+    GptImage15,
+    GeminiImagen4,
+    Gemini25FlashImage,
+}
+
+impl KnownModel {
+    /// Every known model, in catalog order.
+    pub const ALL: &'static [KnownModel] = &[
+        KnownModel::Claude4Opus,
+        KnownModel::Claude4Sonnet,
+        KnownModel::Claude37,
+        KnownModel::Claude35,
+        KnownModel::ClaudeFable5,
+        KnownModel::Gpt4o,
+        KnownModel::Gpt4oMini,
+        KnownModel::GptO3Mini,
+        KnownModel::GptO4Mini,
+        KnownModel::O3Full,
+        KnownModel::O3Pro,
+        KnownModel::Gemini25Pro,
+        KnownModel::Gemini25Flash,
+        KnownModel::Gemini25FlashLite,
+        KnownModel::Gemini20Flash,
+        KnownModel::GrokV3,
+        KnownModel::GrokV4,
+        KnownModel::DeepseekV3,
+        KnownModel::DeepseekR1,
+        KnownModel::DeepseekR1Groq,
+        KnownModel::Llama3370b,
+        KnownModel::Qwen332b,
+        KnownModel::Qwen3235b,
+        KnownModel::GptImage1,
+        KnownModel::GptImage15,
+        KnownModel::GeminiImagen4,
+        KnownModel::Gemini25FlashImage,
+    ];
+
+    ///
+    /// Returns the ID t3.chat uses for the model.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The model.
+    ///
+    /// # Returns
+    /// * `&'static str` - The model ID.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KnownModel::Claude4Opus => "claude-4-opus",
+            KnownModel::Claude4Sonnet => "claude-4-sonnet",
+            KnownModel::Claude37 => "claude-3.7",
+            KnownModel::Claude35 => "claude-3.5",
+            KnownModel::ClaudeFable5 => "claude-fable-5",
+            KnownModel::Gpt4o => "gpt-4o",
+            KnownModel::Gpt4oMini => "gpt-4o-mini",
+            KnownModel::GptO3Mini => "gpt-o3-mini",
+            KnownModel::GptO4Mini => "gpt-o4-mini",
+            KnownModel::O3Full => "o3-full",
+            KnownModel::O3Pro => "o3-pro",
+            KnownModel::Gemini25Pro => "gemini-2.5-pro",
+            KnownModel::Gemini25Flash => "gemini-2.5-flash",
+            KnownModel::Gemini25FlashLite => "gemini-2.5-flash-lite",
+            KnownModel::Gemini20Flash => "gemini-2.0-flash",
+            KnownModel::GrokV3 => "grok-v3",
+            KnownModel::GrokV4 => "grok-v4",
+            KnownModel::DeepseekV3 => "deepseek-v3",
+            KnownModel::DeepseekR1 => "deepseek-r1",
+            KnownModel::DeepseekR1Groq => "deepseek-r1-groq",
+            KnownModel::Llama3370b => "llama-3.3-70b",
+            KnownModel::Qwen332b => "qwen3-32b",
+            KnownModel::Qwen3235b => "qwen3-235b",
+            KnownModel::GptImage1 => "gpt-image-1",
+            KnownModel::GptImage15 => "gpt-image-1.5",
+            KnownModel::GeminiImagen4 => "gemini-imagen-4",
+            KnownModel::Gemini25FlashImage => "gemini-2.5-flash-image",
+        }
+    }
+}
+
+impl fmt::Display for KnownModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error returned when a string is not the ID of a `KnownModel`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownModelError {
+    pub input: String,
+}
+
+impl fmt::Display for UnknownModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown model id {:?}", self.input)
+    }
+}
+
+impl std::error::Error for UnknownModelError {}
+
+impl FromStr for KnownModel {
+    type Err = UnknownModelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KnownModel::ALL
+            .iter()
+            .copied()
+            .find(|model| model.as_str() == s.trim())
+            .ok_or_else(|| UnknownModelError {
+                input: s.to_string(),
+            })
+    }
+}
+
+/// A model ID accepted by `Client::send`: a `KnownModel` or any ID string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ModelId(String);
+
+impl ModelId {
+    ///
+    /// Returns the model ID as a string.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The model ID.
+    ///
+    /// # Returns
+    /// * `&str` - The model ID.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    ///
+    /// Returns the matching `KnownModel`, if the ID is one.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The model ID.
+    ///
+    /// # Returns
+    /// * `Option<KnownModel>` - The known model.
+    pub fn known(&self) -> Option<KnownModel> {
+        self.0.parse().ok()
+    }
+}
+
+impl fmt::Display for ModelId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<KnownModel> for ModelId {
+    fn from(model: KnownModel) -> Self {
+        Self(model.as_str().to_string())
+    }
+}
+
+impl From<&str> for ModelId {
+    fn from(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+impl From<String> for ModelId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
+
+impl From<&String> for ModelId {
+    fn from(id: &String) -> Self {
+        Self(id.clone())
+    }
+}
+
+impl From<&ModelId> for ModelId {
+    fn from(id: &ModelId) -> Self {
+        id.clone()
+    }
+}
+
+/// Find a model in a list by ID or name.
+///
+/// Matches are tried in order: exact ID, case-insensitive ID or name, the same comparison
+/// ignoring punctuation and spaces, and finally a case-insensitive substring of the ID or name.
+/// When several models match at the same step, the one with the shortest ID wins, then the
+/// alphabetically first.
+///
+/// # Arguments
+/// * `models`: `&[ModelInfo]` - The models to search.
+/// * `query`: `&str` - The model ID or name to look for.
+///
+/// # Returns
+/// * `Option<&ModelInfo>` - The best match, or `None` if nothing matches.
+pub fn find_model_in<'a>(models: &'a [ModelInfo], query: &str) -> Option<&'a ModelInfo> {
+    let query = query.trim();
+    if query.is_empty() {
+        return None;
+    }
+    if let Some(model) = models.iter().find(|m| m.id == query) {
+        return Some(model);
+    }
+    let normalize = |s: &str| -> String {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let lower = query.to_lowercase();
+    let normalized = normalize(query);
+    let rules: [&dyn Fn(&ModelInfo) -> bool; 3] = [
+        &|m| m.id.to_lowercase() == lower || m.name.to_lowercase() == lower,
+        &|m| {
+            !normalized.is_empty()
+                && (normalize(&m.id) == normalized || normalize(&m.name) == normalized)
+        },
+        &|m| m.id.to_lowercase().contains(&lower) || m.name.to_lowercase().contains(&lower),
+    ];
+    rules.iter().find_map(|rule| {
+        models
+            .iter()
+            .filter(|m| rule(m))
+            .min_by(|a, b| a.id.len().cmp(&b.id.len()).then_with(|| a.id.cmp(&b.id)))
+    })
+}
+
+/// Estimate what a request to a model costs, in provider dollars and in t3.chat credits.
+///
+/// The dollar figure applies the model's per-token `cost` to the token counts and adds any
+/// fixed per-request cost. Credits are the model's flat `credit_amount` per message.
+///
+/// # Arguments
+/// * `model`: `&ModelInfo` - The model, as returned by `ModelsClient::get_models`.
+/// * `prompt_tokens`: `u64` - Tokens sent, e.g. from `Client::conversation_tokens`.
+/// * `completion_tokens`: `u64` - Tokens expected in the reply.
+///
+/// # Returns
+/// * `CostEstimate` - The estimate; fields the catalog has no data for are `None`.
+pub fn estimate_cost(model: &ModelInfo, prompt_tokens: u64, completion_tokens: u64) -> CostEstimate {
+    let cost = &model.cost;
+    let usd = (cost.input.is_some() || cost.output.is_some() || cost.fixed.is_some()).then(|| {
+        cost.input.unwrap_or(0.0) * prompt_tokens as f64
+            + cost.output.unwrap_or(0.0) * completion_tokens as f64
+            + cost.fixed.unwrap_or(0.0)
+    });
+    CostEstimate {
+        usd,
+        credits: model.credit_amount,
+        premium: model.premium,
+    }
+}
+
+/// Suggest the model IDs closest to a mistyped one, ranked by edit distance.
+///
+/// IDs are compared case-insensitively. Only IDs within a third of the query's length
+/// (and at least two edits) are suggested. Ties are broken alphabetically.
+///
+/// # Arguments
+/// * `ids`: `&[String]` - The known model IDs.
+/// * `given`: `&str` - The ID that was not found.
+/// * `limit`: `usize` - The maximum number of suggestions.
+///
+/// # Returns
+/// * `Vec<String>` - The closest IDs, best first.
+pub fn suggest_models(ids: &[String], given: &str, limit: usize) -> Vec<String> {
+    let given = given.trim().to_lowercase();
+    let max_distance = (given.chars().count() / 3).max(2);
+    let mut ranked: Vec<(usize, &String)> = ids
+        .iter()
+        .map(|id| (edit_distance(&id.to_lowercase(), &given), id))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    ranked.sort();
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|(_, id)| id.clone())
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
 }
 
 fn parse_bool_after_field(js: &str, field: &str) -> bool {
     let pattern = format!(r#"{}:(true|false)"#, field);
-    if let Ok(re) = Regex::new(&pattern) {
-        if let Some(cap) = re.captures(js) {
-            return cap.get(1).map(|m| m.as_str() == "true").unwrap_or(false);
-        }
+    if let Ok(re) = Regex::new(&pattern)
+        && let Some(cap) = re.captures(js)
+    {
+        return cap.get(1).map(|m| m.as_str() == "true").unwrap_or(false);
     }
     false
 }
 
+fn parse_js_bool(js: &str, field: &str) -> Option<bool> {
+    let pattern = format!(r#"\b{}:(true|false|!0|!1)"#, field);
+    let cap = Regex::new(&pattern).ok()?.captures(js)?;
+    Some(is_js_true(cap.get(1)?.as_str()))
+}
+
+/// Whether a boolean literal is true, including the minified `!0` form.
+fn is_js_true(literal: &str) -> bool {
+    matches!(literal, "true" | "!0")
+}
+
 fn parse_optional_backtick(js: &str, field: &str) -> Option<String> {
     let pattern = format!(r#"{}:`([^`]*)`"#, field);
-    if let Ok(re) = Regex::new(&pattern) {
-        if let Some(cap) = re.captures(js) {
-            return cap.get(1).map(|m| m.as_str().to_string());
-        }
+    if let Ok(re) = Regex::new(&pattern)
+        && let Some(cap) = re.captures(js)
+    {
+        return cap.get(1).map(|m| m.as_str().to_string());
     }
     None
 }
 
 fn parse_optional_number(js: &str, field: &str) -> Option<f64> {
     let pattern = format!(r#"{}:([0-9eE./+-]+)"#, field);
-    if let Ok(re) = Regex::new(&pattern) {
-        if let Some(cap) = re.captures(js) {
-            if let Some(val_str) = cap.get(1).map(|m| m.as_str()) {
-                return eval_js_number(val_str);
-            }
-        }
+    if let Ok(re) = Regex::new(&pattern)
+        && let Some(cap) = re.captures(js)
+        && let Some(val_str) = cap.get(1).map(|m| m.as_str())
+    {
+        return eval_js_number(val_str);
     }
     None
 }
@@ -105,40 +550,307 @@ fn eval_js_number(s: &str) -> Option<f64> {
 
 fn parse_set_strings(js: &str, field: &str) -> Vec<String> {
     let pattern = format!(r#"{}:new Set\(\[([^\]]*)\]\)"#, field);
-    if let Ok(re) = Regex::new(&pattern) {
-        if let Some(cap) = re.captures(js) {
-            if let Some(inner) = cap.get(1) {
-                let item_re = Regex::new(r#"`([^`]+)`"#).unwrap();
-                return item_re
-                    .captures_iter(inner.as_str())
-                    .map(|c| c.get(1).unwrap().as_str().to_string())
-                    .collect();
-            }
-        }
+    if let Ok(re) = Regex::new(&pattern)
+        && let Some(cap) = re.captures(js)
+        && let Some(inner) = cap.get(1)
+    {
+        let item_re = Regex::new(r#"`([^`]+)`"#).unwrap();
+        return item_re
+            .captures_iter(inner.as_str())
+            .map(|c| group(&c, 1).to_string())
+            .collect();
     }
     Vec::new()
 }
 
+fn has_feature(features: &[String], names: &[&str]) -> bool {
+    features
+        .iter()
+        .any(|feature| names.iter().any(|name| feature.eq_ignore_ascii_case(name)))
+}
+
 fn parse_array_strings(js: &str, field: &str) -> Vec<String> {
     let pattern = format!(r#"{}:\[([^\]]*)\]"#, field);
-    if let Ok(re) = Regex::new(&pattern) {
-        if let Some(cap) = re.captures(js) {
-            if let Some(inner) = cap.get(1) {
-                let item_re = Regex::new(r#"`([^`]+)`"#).unwrap();
-                return item_re
-                    .captures_iter(inner.as_str())
-                    .map(|c| c.get(1).unwrap().as_str().to_string())
-                    .collect();
+    if let Ok(re) = Regex::new(&pattern)
+        && let Some(cap) = re.captures(js)
+        && let Some(inner) = cap.get(1)
+    {
+        let item_re = Regex::new(r#"`([^`]+)`"#).unwrap();
+        return item_re
+            .captures_iter(inner.as_str())
+            .map(|c| group(&c, 1).to_string())
+            .collect();
+    }
+    Vec::new()
+}
+
+///
+/// Parses the model entries of a t3.chat JavaScript chunk. Each model's object literal is
+/// found in one pass over the chunk and read field by field, so field order and fields the
+/// crate does not know about do not matter. If that finds nothing, the older
+/// `parse_model_chunk_regex` scan is tried. Entries whose ID looks like a template rather than
+/// a model are skipped. Malformed input yields fewer models, never a panic.
+///
+/// # Arguments
+/// * `js`: `&str` - The chunk source.
+///
+/// # Returns
+/// * `Result<Vec<ModelInfo>, T3Error>` - The models found, in the order they appear.
+pub fn parse_model_chunk(js: &str) -> Result<Vec<ModelInfo>, T3Error> {
+    let models: Vec<ModelInfo> = find_objects(js, "{id:`")
+        .into_iter()
+        .filter_map(|object| model_from_literal(&parse_literal(object)))
+        .collect();
+    if !models.is_empty() {
+        return Ok(models);
+    }
+    trace::debug_event!("no model objects found, falling back to the regex scan");
+    parse_model_chunk_regex(js)
+}
+
+///
+/// Reads a model from a parsed object literal of the catalog.
+///
+/// # Arguments
+/// * `value`: `&Value` - The object.
+///
+/// # Returns
+/// * `Option<ModelInfo>` - The model, or `None` if the object lacks an ID, name or provider
+///   or its ID is a template.
+fn model_from_literal(value: &Value) -> Option<ModelInfo> {
+    let text = |key: &str| value.get(key).and_then(Value::as_str).map(str::to_string);
+    let flag = |key: &str| value.get(key).and_then(Value::as_bool).unwrap_or(false);
+    let strings = |key: &str| -> Vec<String> {
+        value
+            .get(key)
+            .and_then(Value::as_array)
+            .map(|items| items.iter().filter_map(Value::as_str).map(str::to_string).collect())
+            .unwrap_or_default()
+    };
+    let id = text("id")?;
+    if id.contains(['/', '$', ' ']) {
+        return None;
+    }
+    let number = |object: &Value, key: &str| object.get(key).and_then(Value::as_f64);
+    let cost_fields = value.get("cost").unwrap_or(&Value::Null);
+    let cost = ModelCost {
+        input: number(cost_fields, "input"),
+        output: number(cost_fields, "output"),
+        cache_read: number(cost_fields, "cacheRead").or_else(|| number(value, "cacheRead")),
+        cache_write: number(cost_fields, "cacheWrite").or_else(|| number(value, "cacheWrite")),
+        fixed: number(cost_fields, "fixed"),
+    };
+    let limit = |scope: &str, key: &str| {
+        value
+            .pointer(&format!("/limits/{}/{}", scope, key))
+            .and_then(Value::as_i64)
+    };
+    let limits = ModelLimits {
+        app_max_input_tokens: limit("app", "maxInputTokens"),
+        app_max_output_tokens: limit("app", "maxOutputTokens"),
+        provider_max_input_tokens: limit("provider", "maxInputTokens"),
+        provider_max_output_tokens: limit("provider", "maxOutputTokens"),
+    };
+    let context_length = ["contextLength", "contextWindow"]
+        .iter()
+        .find_map(|field| number(value, field))
+        .map(|tokens| tokens as i64)
+        .or(limits.provider_max_input_tokens)
+        .or(limits.app_max_input_tokens)
+        .and_then(|tokens| u32::try_from(tokens).ok());
+    let max_output_tokens = limits
+        .provider_max_output_tokens
+        .or(limits.app_max_output_tokens)
+        .and_then(|tokens| u32::try_from(tokens).ok());
+    let mut model = ModelInfo {
+        id,
+        name: text("name")?,
+        provider: text("provider")?,
+        developer: text("developer").unwrap_or_default(),
+        short_description: text("shortDescription").unwrap_or_default(),
+        full_description: text("fullDescription").unwrap_or_default(),
+        requires_pro: flag("requiresPro"),
+        premium: flag("premium"),
+        disabled: flag("disabled"),
+        legacy: flag("legacy"),
+        aa_identifier: text("aaIdentifier"),
+        cost,
+        credit_amount: value.get("creditAmount").and_then(Value::as_i64),
+        limits,
+        context_length,
+        max_output_tokens,
+        features: strings("features"),
+        search_tags: strings("searchTags"),
+        api_key_support: text("apiKeySupport").unwrap_or_default(),
+        added_on: text("addedOn"),
+        knowledge_cutoff_date: text("knowledgeCutoffDate"),
+        retired_on: text("retiredOn"),
+        succeded_by: text("succededBy"),
+        ..ModelInfo::default()
+    };
+    set_capabilities(&mut model);
+    Some(model)
+}
+
+///
+/// Sets the `supports_*` flags of a model from its feature names.
+///
+/// # Arguments
+/// * `model`: `&mut ModelInfo` - The model, with `features` filled in.
+fn set_capabilities(model: &mut ModelInfo) {
+    let features = &model.features;
+    model.supports_vision = has_feature(features, &["vision", "images", "image-input"]);
+    model.supports_image_generation =
+        has_feature(features, &["imageGeneration", "image-generation", "image-gen"]);
+    model.supports_reasoning =
+        has_feature(features, &["reasoning", "effort-control", "reasoningEffort"]);
+    model.supports_search = has_feature(features, &["search", "web-search"]);
+}
+
+///
+/// Parses the model entries of a chunk with the regex scan `parse_model_chunk` used before
+/// it read object literals. It is slower, needs the fields in catalog order and is kept as a
+/// fallback and for comparison.
+///
+/// # Arguments
+/// * `js`: `&str` - The chunk source.
+///
+/// # Returns
+/// * `Result<Vec<ModelInfo>, T3Error>` - The models found, in the order they appear.
+pub fn parse_model_chunk_regex(js: &str) -> Result<Vec<ModelInfo>, T3Error> {
+    let model_entry_regex = Regex::new(r#"(?s)\{id:`([^`]+)`,.*?name:`([^`]*)`.*?provider:`([^`]*)`.*?developer:`([^`]*)`.*?shortDescription:`([^`]*)`.*?fullDescription:`([^`]*)`.*?(?:requiresPro:(true|false|!0|!1)).*?(?:disabled:(true|false|!0|!1)).*?(?:legacy:(true|false|!0|!1))"#)?;
+    let cost_regex = Regex::new(r#"cost:\{input:([^,}]+),output:([^,}]+)(?:,fixed:([^}]+))?\}"#)?;
+    let cache_regex = Regex::new(r#"cacheRead:([^,}]+),cacheWrite:([^,}]+)"#)?;
+    let credit_regex = Regex::new(r#"creditAmount:(\d+)"#)?;
+    let app_limits_regex = Regex::new(r#"app:\{maxInputTokens:(\d+),maxOutputTokens:(\d+)"#)?;
+    let provider_limits_regex = Regex::new(
+        r#"provider:\{maxInputTokens:(\d+),maxOutputTokens:(\d+)"#,
+    )?;
+    let api_key_re = Regex::new(r#"apiKeySupport:(\w+\.\w+)"#)?;
+    let mut models = Vec::new();
+    let captures: Vec<_> = model_entry_regex.captures_iter(js).collect();
+    for (index, capture) in captures.iter().enumerate() {
+        let id = group(capture, 1).to_string();
+        if id.contains('/') || id.contains('$') || id.contains(' ') {
+            continue;
+        }
+        let full_match = capture.get_match();
+        let model_js = full_match.as_str();
+        let segment_end = captures
+            .get(index + 1)
+            .map(|next| next.get_match().start())
+            .unwrap_or(js.len());
+        let model_segment = &js[full_match.start()..segment_end];
+        let mut cost = ModelCost::default();
+        if let Some(c) = cost_regex.captures(model_segment) {
+            cost.input = eval_js_number(group(&c, 1));
+            cost.output = eval_js_number(group(&c, 2));
+            if let Some(fixed) = c.get(3) {
+                cost.fixed = eval_js_number(fixed.as_str());
             }
         }
+        if let Some(c) = cache_regex.captures(model_segment) {
+            cost.cache_read = eval_js_number(group(&c, 1));
+            cost.cache_write = eval_js_number(group(&c, 2));
+        }
+        let mut limits = ModelLimits::default();
+        if let Some(c) = app_limits_regex.captures(model_segment) {
+            limits.app_max_input_tokens = group(&c, 1).parse().ok();
+            limits.app_max_output_tokens = group(&c, 2).parse().ok();
+        }
+        if let Some(c) = provider_limits_regex.captures(model_segment) {
+            limits.provider_max_input_tokens = group(&c, 1).parse().ok();
+            limits.provider_max_output_tokens = group(&c, 2).parse().ok();
+        }
+        let context_length = ["contextLength", "contextWindow"]
+            .iter()
+            .find_map(|field| parse_optional_number(model_segment, field))
+            .map(|tokens| tokens as i64)
+            .or(limits.provider_max_input_tokens)
+            .or(limits.app_max_input_tokens)
+            .and_then(|tokens| u32::try_from(tokens).ok());
+        let max_output_tokens = limits
+            .provider_max_output_tokens
+            .or(limits.app_max_output_tokens)
+            .and_then(|tokens| u32::try_from(tokens).ok());
+        let mut model = ModelInfo {
+            id: id.clone(),
+            name: group(capture, 2).to_string(),
+            provider: group(capture, 3).to_string(),
+            developer: group(capture, 4).to_string(),
+            short_description: group(capture, 5).to_string(),
+            full_description: group(capture, 6).to_string(),
+            requires_pro: is_js_true(group(capture, 7)),
+            premium: parse_js_bool(model_segment, "premium").unwrap_or(false),
+            disabled: is_js_true(group(capture, 8)),
+            legacy: is_js_true(group(capture, 9)),
+            aa_identifier: parse_optional_backtick(model_js, "aaIdentifier"),
+            cost,
+            credit_amount: credit_regex
+                .captures(model_segment)
+                .and_then(|c| group(&c, 1).parse().ok()),
+            limits,
+            context_length,
+            max_output_tokens,
+            features: parse_set_strings(model_js, "features"),
+            search_tags: parse_array_strings(model_js, "searchTags"),
+            api_key_support: api_key_re
+                .captures(model_js)
+                .map(|c| group(&c, 1).to_string())
+                .unwrap_or_default(),
+            added_on: parse_optional_backtick(model_js, "addedOn"),
+            knowledge_cutoff_date: parse_optional_backtick(model_js, "knowledgeCutoffDate"),
+            retired_on: parse_optional_backtick(model_js, "retiredOn"),
+            succeded_by: parse_optional_backtick(model_js, "succededBy"),
+            ..ModelInfo::default()
+        };
+        set_capabilities(&mut model);
+        models.push(model);
+    }
+    Ok(models)
+}
+
+///
+/// Gets the text of a capture group, or an empty string if the group did not match.
+///
+/// # Arguments
+/// * `capture`: `&Captures` - The regex captures.
+/// * `index`: `usize` - The group number.
+///
+/// # Returns
+/// * `&str` - The matched text.
+fn group<'h>(capture: &Captures<'h>, index: usize) -> &'h str {
+    capture.get(index).map_or("", |m| m.as_str())
+}
+
+/// How long a fetched model list is reused unless configured otherwise.
+pub const DEFAULT_MODEL_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Serialize, Deserialize)]
+struct CachedModels {
+    fetched_at: u64,
+    models: Vec<ModelInfo>,
+}
+
+impl CachedModels {
+    fn is_fresh(&self, ttl: Duration) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        now.saturating_sub(self.fetched_at) < ttl.as_secs()
     }
-    Vec::new()
 }
 
 pub struct ModelsClient {
     client: wreq::Client,
     cookies: String,
-    _convex_session_id: String,
+    convex_session_id: String,
+    base_urls: BaseUrls,
+    cache_ttl: Duration,
+    cache_path: Option<PathBuf>,
+    cache: Mutex<Option<CachedModels>>,
+    probe_cache: Mutex<HashMap<String, ModelStatus>>,
 }
 
 impl ModelsClient {
@@ -151,17 +863,76 @@ impl ModelsClient {
     /// # Returns
     /// * Self - A new ModelsClient instance.
     pub fn new(cookies: String, convex_session_id: String) -> Self {
+        Self::with_http_client(
+            build_http_client(HttpOptions::default()).unwrap(),
+            cookies,
+            convex_session_id,
+        )
+    }
+
+    /// Create a ModelsClient that reuses an existing HTTP client.
+    ///
+    /// # Arguments
+    /// * `client` - wreq::Client: The HTTP client to share.
+    /// * `cookies` - String: Cookie header for requests.
+    /// * `convex_session_id` - String: Session ID for authentication.
+    ///
+    /// # Returns
+    /// * Self - A new ModelsClient instance.
+    pub(crate) fn with_http_client(
+        client: wreq::Client,
+        cookies: String,
+        convex_session_id: String,
+    ) -> Self {
         Self {
-            client: wreq::Client::builder()
-                .emulation(Emulation::Chrome136)
-                .cookie_store(true)
-                .build()
-                .unwrap(),
+            client,
             cookies,
-            _convex_session_id: convex_session_id,
+            convex_session_id,
+            base_urls: BaseUrls::default(),
+            cache_ttl: DEFAULT_MODEL_CACHE_TTL,
+            cache_path: None,
+            cache: Mutex::new(None),
+            probe_cache: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Send requests to other hosts than production, such as a local test server.
+    ///
+    /// # Arguments
+    /// * `base_urls` - BaseUrls: The hosts to use.
+    ///
+    /// # Returns
+    /// * Self - The updated ModelsClient.
+    pub fn with_base_urls(mut self, base_urls: BaseUrls) -> Self {
+        self.base_urls = base_urls;
+        self
+    }
+
+    /// Set how long a fetched model list is reused before fetching again.
+    ///
+    /// # Arguments
+    /// * `ttl` - Duration: The cache lifetime. `Duration::ZERO` disables caching.
+    ///
+    /// # Returns
+    /// * Self - The updated ModelsClient.
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Persist the model list to a JSON file so later runs can skip the network while it is fresh.
+    /// A missing or corrupt file is ignored.
+    ///
+    /// # Arguments
+    /// * `path` - PathBuf: The cache file.
+    ///
+    /// # Returns
+    /// * Self - The updated ModelsClient.
+    pub fn cache_path(mut self, path: PathBuf) -> Self {
+        self.cache_path = Some(path);
+        self
+    }
+
     ///
     /// Fetch all chunk URLs from the t3.chat homepage.
     ///
@@ -169,13 +940,13 @@ impl ModelsClient {
     /// * `self`: `&Self` - The models client instance.
     ///
     /// # Returns
-    /// * `Result<Vec<String>, Box<dyn std::error::Error>>` - Chunk URLs or an error.
+    /// * `Result<Vec<String>, T3Error>` - Chunk URLs or an error.
     async fn get_chunk_urls_from_homepage(
         &self,
-    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    ) -> Result<Vec<String>, T3Error> {
         let response = self
             .client
-            .get("https://t3.chat/")
+            .get(self.base_urls.app_url("/"))
             .header("Cookie", &self.cookies)
             .send()
             .await?;
@@ -183,19 +954,21 @@ impl ModelsClient {
         let mut chunk_urls = Vec::new();
         let link_regex = Regex::new(r#"<link[^>]*href="(/assets/[^"]+\.js[^"]*)""#)?;
         for capture in link_regex.captures_iter(&html) {
-            let chunk_path = capture.get(1).unwrap().as_str();
-            chunk_urls.push(format!("https://t3.chat{}", chunk_path));
+            let chunk_path = group(&capture, 1);
+            chunk_urls.push(self.base_urls.app_url(chunk_path));
         }
         let script_regex = Regex::new(r#"<script[^>]+src="(/assets/[^"]+\.js[^"]*)"#)?;
         for capture in script_regex.captures_iter(&html) {
-            let chunk_path = capture.get(1).unwrap().as_str();
-            let url = format!("https://t3.chat{}", chunk_path);
+            let chunk_path = group(&capture, 1);
+            let url = self.base_urls.app_url(chunk_path);
             if !chunk_urls.contains(&url) {
                 chunk_urls.push(url);
             }
         }
         if chunk_urls.is_empty() {
-            return Err("Could not find any chunk URLs in homepage".into());
+            return Err(T3Error::ModelDiscovery(
+                "could not find any chunk URLs in homepage".to_string(),
+            ));
         }
         Ok(chunk_urls)
     }
@@ -207,115 +980,151 @@ impl ModelsClient {
     /// * `chunk_url`: `&str` - The chunk URL to parse.
     ///
     /// # Returns
-    /// * `Result<Vec<ModelInfo>, Box<dyn std::error::Error>>` - List of models or an error.
+    /// * `Result<Vec<ModelInfo>, T3Error>` - List of models or an error.
     async fn parse_models_from_chunk(
         &self,
         chunk_url: &str,
-    ) -> Result<Vec<ModelInfo>, Box<dyn std::error::Error>> {
+    ) -> Result<Vec<ModelInfo>, T3Error> {
         let response = self
             .client
             .get(chunk_url)
             .header("Cookie", &self.cookies)
-            .header("Referer", "https://t3.chat/")
+            .header("Referer", self.base_urls.app_url("/"))
             .send()
             .await?;
         let js_content = response.text().await?;
-        let model_entry_regex = Regex::new(r#"(?s)\{id:`([^`]+)`,.*?name:`([^`]*)`.*?provider:`([^`]*)`.*?developer:`([^`]*)`.*?shortDescription:`([^`]*)`.*?fullDescription:`([^`]*)`.*?(?:requiresPro:(true|false)).*?(?:premium:(true|false)).*?(?:disabled:(true|false)).*?(?:legacy:(true|false))"#)?;
-        let cost_regex = Regex::new(r#"cost:\{input:([^,}]+),output:([^,}]+)(?:,fixed:([^}]+))?\}"#)?;
-        let cache_regex = Regex::new(r#"cacheRead:([^,}]+),cacheWrite:([^,}]+)"#)?;
-        let credit_regex = Regex::new(r#"creditAmount:(\d+)"#)?;
-        let app_limits_regex = Regex::new(r#"app:\{maxInputTokens:(\d+),maxOutputTokens:(\d+)"#)?;
-        let provider_limits_regex = Regex::new(
-            r#"provider:\{maxInputTokens:(\d+),maxOutputTokens:(\d+)"#,
-        )?;
-        let api_key_re = Regex::new(r#"apiKeySupport:(\w+\.\w+)"#)?;
-        let mut models = Vec::new();
-        for capture in model_entry_regex.captures_iter(&js_content) {
-            let id = capture.get(1).unwrap().as_str().to_string();
-            if id.contains('/') || id.contains('$') || id.contains(' ') {
-                continue;
-            }
-            let full_match = capture.get(0).unwrap();
-            let model_js = full_match.as_str();
-            let mut cost = ModelCost::default();
-            if let Some(c) = cost_regex.captures(model_js) {
-                cost.input = eval_js_number(c.get(1).unwrap().as_str());
-                cost.output = eval_js_number(c.get(2).unwrap().as_str());
-                if let Some(fixed) = c.get(3) {
-                    cost.fixed = eval_js_number(fixed.as_str());
-                }
-            }
-            if let Some(c) = cache_regex.captures(model_js) {
-                cost.cache_read = eval_js_number(c.get(1).unwrap().as_str());
-                cost.cache_write = eval_js_number(c.get(2).unwrap().as_str());
-            }
-            let mut limits = ModelLimits::default();
-            if let Some(c) = app_limits_regex.captures(model_js) {
-                limits.app_max_input_tokens = c.get(1).unwrap().as_str().parse().ok();
-                limits.app_max_output_tokens = c.get(2).unwrap().as_str().parse().ok();
-            }
-            if let Some(c) = provider_limits_regex.captures(model_js) {
-                limits.provider_max_input_tokens = c.get(1).unwrap().as_str().parse().ok();
-                limits.provider_max_output_tokens = c.get(2).unwrap().as_str().parse().ok();
-            }
-            let model = ModelInfo {
-                id: id.clone(),
-                name: capture.get(2).unwrap().as_str().to_string(),
-                provider: capture.get(3).unwrap().as_str().to_string(),
-                developer: capture.get(4).unwrap().as_str().to_string(),
-                short_description: capture.get(5).unwrap().as_str().to_string(),
-                full_description: capture.get(6).unwrap().as_str().to_string(),
-                requires_pro: capture.get(7).map(|m| m.as_str() == "true").unwrap_or(false),
-                premium: capture.get(8).map(|m| m.as_str() == "true").unwrap_or(false),
-                disabled: capture.get(9).map(|m| m.as_str() == "true").unwrap_or(false),
-                legacy: capture.get(10).map(|m| m.as_str() == "true").unwrap_or(false),
-                aa_identifier: parse_optional_backtick(model_js, "aaIdentifier"),
-                cost,
-                credit_amount: credit_regex
-                    .captures(model_js)
-                    .and_then(|c| c.get(1).unwrap().as_str().parse().ok()),
-                limits,
-                features: parse_set_strings(model_js, "features"),
-                search_tags: parse_array_strings(model_js, "searchTags"),
-                api_key_support: api_key_re
-                    .captures(model_js)
-                    .map(|c| c.get(1).unwrap().as_str().to_string())
-                    .unwrap_or_default(),
-                added_on: parse_optional_backtick(model_js, "addedOn"),
-                knowledge_cutoff_date: parse_optional_backtick(model_js, "knowledgeCutoffDate"),
-                retired_on: parse_optional_backtick(model_js, "retiredOn"),
-                succeded_by: parse_optional_backtick(model_js, "succededBy"),
-            };
-            models.push(model);
+        parse_model_chunk(&js_content)
+    }
+
+    /// Fetch all models with full metadata from the t3.chat site.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The models client instance.
+    ///
+    /// # Returns
+    /// * `Result<Vec<ModelInfo>, T3Error>` - List of ModelInfo or error.
+    pub async fn get_models(&self) -> Result<Vec<ModelInfo>, T3Error> {
+        if let Some(models) = self.cached_models() {
+            return Ok(models);
         }
+        self.refresh_models().await
+    }
+
+    /// Fetch the model list from the t3.chat site, bypassing and then updating the cache.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The models client instance.
+    ///
+    /// # Returns
+    /// * `Result<Vec<ModelInfo>, T3Error>` - List of ModelInfo or error.
+    pub async fn refresh_models(&self) -> Result<Vec<ModelInfo>, T3Error> {
+        let models = self.fetch_models_dynamically().await?;
+        let cached = CachedModels {
+            fetched_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            models: models.clone(),
+        };
+        if let Some(path) = &self.cache_path
+            && let Ok(json) = serde_json::to_string(&cached)
+        {
+            let _ = fs::write(path, json);
+        }
+        *self.cache.lock().unwrap() = Some(cached);
         Ok(models)
     }
 
-    /// Fetch all models with full metadata from the t3.chat site.
+    /// Return the cached model list if it is still fresh, loading the cache file on first use.
+    ///
+    /// # Returns
+    /// * Option<Vec<ModelInfo>> - The cached models, or None if they must be fetched.
+    fn cached_models(&self) -> Option<Vec<ModelInfo>> {
+        let mut cache = self.cache.lock().unwrap();
+        if cache.is_none() {
+            *cache = self
+                .cache_path
+                .as_ref()
+                .and_then(|path| fs::read_to_string(path).ok())
+                .and_then(|json| serde_json::from_str(&json).ok());
+        }
+        cache
+            .as_ref()
+            .filter(|cached| cached.is_fresh(self.cache_ttl))
+            .map(|cached| cached.models.clone())
+    }
+
+    /// Find a model by ID or name. See `find_model_in` for the matching rules.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The models client instance.
+    /// * `query`: `&str` - The model ID or name to look for.
+    ///
+    /// # Returns
+    /// * `Result<Option<ModelInfo>, T3Error>` - The best match, or `None` if nothing matches.
+    pub async fn find_model(&self, query: &str) -> Result<Option<ModelInfo>, T3Error> {
+        let models = self.get_models().await?;
+        Ok(find_model_in(&models, query).cloned())
+    }
+
+    /// List the models served by a provider, compared case-insensitively.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The models client instance.
+    /// * `provider`: `&str` - The provider name, e.g. `openai`.
+    ///
+    /// # Returns
+    /// * `Result<Vec<ModelInfo>, T3Error>` - The matching models.
+    pub async fn models_by_provider(&self, provider: &str) -> Result<Vec<ModelInfo>, T3Error> {
+        let models = self.get_models().await?;
+        Ok(models
+            .into_iter()
+            .filter(|m| m.provider.eq_ignore_ascii_case(provider))
+            .collect())
+    }
+
+    /// List the enabled models usable without a Pro subscription or premium credits.
     ///
     /// # Arguments
     /// * `self`: `&Self` - The models client instance.
     ///
     /// # Returns
-    /// * `Result<Vec<ModelInfo>, Box<dyn std::error::Error>>` - List of ModelInfo or error.
-    pub async fn get_models(&self) -> Result<Vec<ModelInfo>, Box<dyn std::error::Error>> {
-        self.fetch_models_dynamically().await
+    /// * `Result<Vec<ModelInfo>, T3Error>` - The free models.
+    pub async fn free_models(&self) -> Result<Vec<ModelInfo>, T3Error> {
+        let models = self.get_models().await?;
+        Ok(models
+            .into_iter()
+            .filter(|m| m.accessible_with(Plan::Free))
+            .collect())
+    }
+
+    /// List the models that consume premium credits.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The models client instance.
+    ///
+    /// # Returns
+    /// * `Result<Vec<ModelInfo>, T3Error>` - The premium models.
+    pub async fn premium_models(&self) -> Result<Vec<ModelInfo>, T3Error> {
+        let models = self.get_models().await?;
+        Ok(models.into_iter().filter(|m| m.premium).collect())
     }
 
     /// Get the status of all models.
     ///
     /// # Returns
-    /// * Result<Vec<ModelStatus>, Box<dyn std::error::Error>> - List of ModelStatus or error.
-    pub async fn get_model_statuses(&self) -> Result<Vec<ModelStatus>, Box<dyn std::error::Error>> {
-        match self.fetch_models_dynamically().await {
+    /// * Result<Vec<ModelStatus>, T3Error> - List of ModelStatus or error.
+    pub async fn get_model_statuses(&self) -> Result<Vec<ModelStatus>, T3Error> {
+        if let Ok(statuses) = self.fetch_trpc_statuses().await
+            && !statuses.is_empty()
+        {
+            return Ok(statuses);
+        }
+        match self.get_models().await {
             Ok(models) => {
                 let statuses = models
-                    .into_iter()
-                    .map(|m| ModelStatus {
-                        name: m.id,
-                        indicator: "operational".to_string(),
-                        description: m.short_description,
-                    })
+                    .iter()
+                    .map(|m| ModelStatus::new(&m.id, "unknown", &m.short_description))
                     .collect();
                 Ok(statuses)
             }
@@ -323,11 +1132,104 @@ impl ModelsClient {
         }
     }
 
+    /// Probe models by sending each a tiny ephemeral prompt. A reply marks the model
+    /// `operational`, a timeout `degraded` and an error `down`. Each probe uses one message
+    /// from your quota, so probes run at most `max_concurrency` at a time and a model probed
+    /// within `cooldown` reuses its previous result.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The models client instance.
+    /// * `model_ids`: `&[&str]` - The models to probe.
+    /// * `options`: `&ProbeOptions` - Concurrency, timeout and cooldown limits.
+    ///
+    /// # Returns
+    /// * `Vec<ModelStatus>` - One status per requested model, in the requested order.
+    pub async fn probe_models(&self, model_ids: &[&str], options: &ProbeOptions) -> Vec<ModelStatus> {
+        let now = Utc::now();
+        let mut to_probe = Vec::new();
+        let mut statuses = Vec::new();
+        {
+            let cache = self.probe_cache.lock().unwrap();
+            for id in model_ids {
+                let fresh = cache.get(*id).filter(|status| {
+                    (now - status.checked_at)
+                        .to_std()
+                        .is_ok_and(|age| age < options.cooldown)
+                });
+                match fresh {
+                    Some(status) => statuses.push(status.clone()),
+                    None => to_probe.push(id.to_string()),
+                }
+            }
+        }
+        let probed: Vec<ModelStatus> = stream::iter(to_probe)
+            .map(|id| self.probe_model(id, options.timeout))
+            .buffer_unordered(options.max_concurrency.max(1))
+            .collect()
+            .await;
+        {
+            let mut cache = self.probe_cache.lock().unwrap();
+            for status in &probed {
+                cache.insert(status.name.clone(), status.clone());
+            }
+        }
+        statuses.extend(probed);
+        statuses.sort_by_key(|status| model_ids.iter().position(|id| *id == status.name));
+        statuses
+    }
+
+    /// Send a single probe prompt to a model.
+    ///
+    /// # Returns
+    /// * ModelStatus - The probe result.
+    async fn probe_model(&self, model_id: String, timeout: Duration) -> ModelStatus {
+        let mut client = match Client::builder(self.cookies.clone(), self.convex_session_id.clone())
+            .base_urls(self.base_urls.clone())
+            .build()
+        {
+            Ok(client) => client,
+            Err(err) => {
+                return ModelStatus::new(&model_id, "down", &format!("probe failed: {}", err));
+            }
+        };
+        let started = Instant::now();
+        let outcome = tokio::time::timeout(
+            timeout,
+            client.send_with_callback(
+                model_id.as_str(),
+                Some(Message::new(Type::User, PROBE_PROMPT.to_string())),
+                Some(Config::default().ephemeral(true)),
+                |_| {},
+            ),
+        )
+        .await;
+        let (indicator, description, latency) = match outcome {
+            Ok(Ok(_)) => {
+                let latency = started.elapsed();
+                (
+                    "operational",
+                    format!("probe answered in {} ms", latency.as_millis()),
+                    Some(latency),
+                )
+            }
+            Ok(Err(err)) => ("down", format!("probe failed: {}", err), None),
+            Err(_) => (
+                "degraded",
+                format!("probe timed out after {} s", timeout.as_secs()),
+                None,
+            ),
+        };
+        ModelStatus {
+            latency,
+            ..ModelStatus::new(&model_id, indicator, &description)
+        }
+    }
+
     /// Fetch models dynamically from the t3.chat site.
     ///
     /// # Returns
-    /// * Result<Vec<ModelInfo>, Box<dyn std::error::Error>> - List of ModelInfo or error.
-    async fn fetch_models_dynamically(&self) -> Result<Vec<ModelInfo>, Box<dyn std::error::Error>> {
+    /// * Result<Vec<ModelInfo>, T3Error> - List of ModelInfo or error.
+    async fn fetch_models_dynamically(&self) -> Result<Vec<ModelInfo>, T3Error> {
         let chunk_urls = self.get_chunk_urls_from_homepage().await?;
         let mut prioritized: Vec<String> = chunk_urls
             .iter()
@@ -341,10 +1243,10 @@ impl ModelsClient {
         }
         let mut all_models = Vec::new();
         for chunk_url in &prioritized {
-            if let Ok(models) = self.parse_models_from_chunk(chunk_url).await {
-                if !models.is_empty() {
-                    all_models.extend(models);
-                }
+            if let Ok(models) = self.parse_models_from_chunk(chunk_url).await
+                && !models.is_empty()
+            {
+                all_models.extend(models);
             }
             if all_models.len() > 5 {
                 all_models.sort_by(|a, b| a.id.cmp(&b.id));
@@ -357,45 +1259,47 @@ impl ModelsClient {
             all_models.dedup_by(|a, b| a.id == b.id);
             return Ok(all_models);
         }
-        Err("Could not find model definitions in any chunk".into())
+        Err(T3Error::ModelDiscovery(
+            "could not find model definitions in any chunk".to_string(),
+        ))
     }
 
     /// Get fallback model statuses if dynamic fetching fails.
     ///
     /// # Returns
-    /// * Result<Vec<ModelStatus>, Box<dyn std::error::Error>> - List of ModelStatus or error.
-    fn get_fallback_models(&self) -> Result<Vec<ModelStatus>, Box<dyn std::error::Error>> {
+    /// * Result<Vec<ModelStatus>, T3Error> - List of ModelStatus or error.
+    fn get_fallback_models(&self) -> Result<Vec<ModelStatus>, T3Error> {
         let model_statuses = vec![
-            ModelStatus {
-                name: "gemini-2.5-flash".to_string(),
-                indicator: "operational".to_string(),
-                description: "Google's state of the art fast model".to_string(),
-            },
-            ModelStatus {
-                name: "gemini-2.5-flash-lite".to_string(),
-                indicator: "operational".to_string(),
-                description: "Google's most cost-efficient model".to_string(),
-            },
-            ModelStatus {
-                name: "claude-3.7".to_string(),
-                indicator: "operational".to_string(),
-                description: "Anthropic's Claude 3.7 Sonnet".to_string(),
-            },
-            ModelStatus {
-                name: "claude-4-sonnet".to_string(),
-                indicator: "operational".to_string(),
-                description: "Anthropic's Claude 4 Sonnet".to_string(),
-            },
-            ModelStatus {
-                name: "gpt-o4-mini".to_string(),
-                indicator: "operational".to_string(),
-                description: "OpenAI's latest small reasoning model".to_string(),
-            },
-            ModelStatus {
-                name: "deepseek-r1-groq".to_string(),
-                indicator: "operational".to_string(),
-                description: "DeepSeek R1 distilled on Llama".to_string(),
-            },
+            ModelStatus::new(
+                "gemini-2.5-flash",
+                "unknown",
+                "Google's state of the art fast model",
+            ),
+            ModelStatus::new(
+                "gemini-2.5-flash-lite",
+                "unknown",
+                "Google's most cost-efficient model",
+            ),
+            ModelStatus::new(
+                "claude-3.7",
+                "unknown",
+                "Anthropic's Claude 3.7 Sonnet",
+            ),
+            ModelStatus::new(
+                "claude-4-sonnet",
+                "unknown",
+                "Anthropic's Claude 4 Sonnet",
+            ),
+            ModelStatus::new(
+                "gpt-o4-mini",
+                "unknown",
+                "OpenAI's latest small reasoning model",
+            ),
+            ModelStatus::new(
+                "deepseek-r1-groq",
+                "unknown",
+                "DeepSeek R1 distilled on Llama",
+            ),
         ];
         Ok(model_statuses)
     }
@@ -406,25 +1310,33 @@ impl ModelsClient {
     /// * `self`: `&Self` - The models client instance.
     ///
     /// # Returns
-    /// * `Result<Vec<ModelStatus>, Box<dyn std::error::Error>>` - Model statuses or error.
+    /// * `Result<Vec<ModelStatus>, T3Error>` - Model statuses or error.
     pub async fn get_model_statuses_trpc(
         &self,
-    ) -> Result<Vec<ModelStatus>, Box<dyn std::error::Error>> {
-        let url = "https://t3.chat/api/trpc/getModelStatuses?batch=1&input=%7B%220%22%3A%7B%22json%22%3Anull%2C%22meta%22%3A%7B%22values%22%3A%5B%22undefined%22%5D%7D%7D%7D";
+    ) -> Result<Vec<ModelStatus>, T3Error> {
+        let statuses = self.fetch_trpc_statuses().await?;
+        if statuses.is_empty() {
+            return self.get_model_statuses().await;
+        }
+        Ok(statuses)
+    }
+
+    /// Fetch the raw model statuses reported by the tRPC API.
+    ///
+    /// # Returns
+    /// * Result<Vec<ModelStatus>, T3Error> - The reported statuses, possibly empty.
+    async fn fetch_trpc_statuses(&self) -> Result<Vec<ModelStatus>, T3Error> {
+        let url = self.base_urls.app_url("/api/trpc/getModelStatuses?batch=1&input=%7B%220%22%3A%7B%22json%22%3Anull%2C%22meta%22%3A%7B%22values%22%3A%5B%22undefined%22%5D%7D%7D%7D");
         let response = self
             .client
             .get(url)
             .header("Cookie", &self.cookies)
             .header("x-trpc-source", "web-client")
-            .header("Referer", "https://t3.chat/")
+            .header("Referer", self.base_urls.app_url("/"))
             .send()
             .await?;
         let body = response.text().await?;
-        let statuses = parse_trpc_model_statuses(&body);
-        if statuses.is_empty() {
-            return self.get_model_statuses().await;
-        }
-        Ok(statuses)
+        Ok(parse_trpc_model_statuses(&body))
     }
 
     /// Fetch all model benchmarks from the t3.chat tRPC API.
@@ -433,17 +1345,17 @@ impl ModelsClient {
     /// * `self`: `&Self` - The models client instance.
     ///
     /// # Returns
-    /// * `Result<Vec<ModelBenchmark>, Box<dyn std::error::Error>>` - Model benchmarks or error.
+    /// * `Result<Vec<ModelBenchmark>, T3Error>` - Model benchmarks or error.
     pub async fn get_model_benchmarks(
         &self,
-    ) -> Result<Vec<ModelBenchmark>, Box<dyn std::error::Error>> {
-        let url = "https://t3.chat/api/trpc/getAllModelBenchmarks?batch=1&input=%7B%220%22%3A%7B%22json%22%3Anull%2C%22meta%22%3A%7B%22values%22%3A%5B%22undefined%22%5D%7D%7D%7D";
+    ) -> Result<Vec<ModelBenchmark>, T3Error> {
+        let url = self.base_urls.app_url("/api/trpc/getAllModelBenchmarks?batch=1&input=%7B%220%22%3A%7B%22json%22%3Anull%2C%22meta%22%3A%7B%22values%22%3A%5B%22undefined%22%5D%7D%7D%7D");
         let response = self
             .client
             .get(url)
             .header("Cookie", &self.cookies)
             .header("x-trpc-source", "web-client")
-            .header("Referer", "https://t3.chat/")
+            .header("Referer", self.base_urls.app_url("/"))
             .send()
             .await?;
         let body = response.text().await?;
@@ -470,24 +1382,20 @@ fn parse_trpc_model_statuses(body: &str) -> Vec<ModelStatus> {
 }
 
 fn collect_model_statuses(v: &Value, result: &mut Vec<ModelStatus>) {
-    if let Some(data) = find_data_json(v) {
-        if let Some(arr) = data.as_array() {
-            for item in arr {
-                if let Some(name) = item.get("name").and_then(|v| v.as_str()) {
-                    result.push(ModelStatus {
-                        name: name.to_string(),
-                        indicator: item
-                            .get("indicator")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("operational")
-                            .to_string(),
-                        description: item
-                            .get("description")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string(),
-                    });
-                }
+    if let Some(data) = find_data_json(v)
+        && let Some(arr) = data.as_array()
+    {
+        for item in arr {
+            if let Some(name) = item.get("name").and_then(|v| v.as_str()) {
+                result.push(ModelStatus::new(
+                    name,
+                    item.get("indicator")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("unknown"),
+                    item.get("description")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or(""),
+                ));
             }
         }
     }
@@ -511,60 +1419,59 @@ fn parse_trpc_benchmarks(body: &str) -> Vec<ModelBenchmark> {
 }
 
 fn collect_benchmarks(v: &Value, result: &mut Vec<ModelBenchmark>) {
-    if let Some(data) = find_data_json(v) {
-        if let Some(arr) = data.as_array() {
-            for item in arr {
-                result.push(ModelBenchmark {
-                    model_id: item
-                        .get("modelId")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("")
-                        .to_string(),
-                    benchmark_id: item
-                        .get("benchmarkId")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("")
-                        .to_string(),
-                    score: item.get("score").and_then(|v| v.as_f64()).unwrap_or(0.0),
-                    description: item
-                        .get("description")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("")
-                        .to_string(),
-                });
-            }
+    if let Some(data) = find_data_json(v)
+        && let Some(arr) = data.as_array()
+    {
+        for item in arr {
+            result.push(ModelBenchmark {
+                model_id: item
+                    .get("modelId")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                benchmark_id: item
+                    .get("benchmarkId")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                score: item.get("score").and_then(|v| v.as_f64()).unwrap_or(0.0),
+                description: item
+                    .get("description")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+            });
         }
     }
 }
 
 fn find_data_json(v: &Value) -> Option<Value> {
-    if let Some(result) = v.get("result") {
-        if let Some(data) = result.get("data") {
-            if let Some(json) = data.get("json") {
-                return Some(json.clone());
-            }
-            return Some(data.clone());
+    if let Some(result) = v.get("result")
+        && let Some(data) = result.get("data")
+    {
+        if let Some(json) = data.get("json") {
+            return Some(json.clone());
         }
+        return Some(data.clone());
     }
     if let Some(json_val) = v.get("json") {
-        if let Some(json_arr) = json_val.as_array() {
-            if json_arr.len() >= 3 {
-                if let Some(data_part) = json_arr.get(2) {
-                    if let Some(data_arr) = data_part.as_array() {
-                        if let Some(inner_arr) = data_arr.first().and_then(|x| x.as_array()) {
-                            if let Some(data) = inner_arr.first() {
-                                return Some(data.clone());
-                            }
-                        }
-                        if let Some(data) = data_arr.first() {
-                            return Some(data.clone());
-                        }
-                    }
-                    if data_part.is_object() || data_part.is_array() {
-                        return Some(data_part.clone());
-                    }
+        if let Some(json_arr) = json_val.as_array()
+            && json_arr.len() >= 3
+            && let Some(data_part) = json_arr.get(2)
+        {
+            if let Some(data_arr) = data_part.as_array() {
+                if let Some(inner_arr) = data_arr.first().and_then(|x| x.as_array())
+                    && let Some(data) = inner_arr.first()
+                {
+                    return Some(data.clone());
+                }
+                if let Some(data) = data_arr.first() {
+                    return Some(data.clone());
                 }
             }
+            if data_part.is_object() || data_part.is_array() {
+                return Some(data_part.clone());
+            }
         }
         if json_val.is_array() {
             return Some(json_val.clone());
@@ -572,13 +1479,13 @@ fn find_data_json(v: &Value) -> Option<Value> {
     }
     if let Some(arr) = v.as_array() {
         for item in arr {
-            if let Some(result) = item.get("result") {
-                if let Some(data) = result.get("data") {
-                    if let Some(json) = data.get("json") {
-                        return Some(json.clone());
-                    }
-                    return Some(data.clone());
+            if let Some(result) = item.get("result")
+                && let Some(data) = result.get("data")
+            {
+                if let Some(json) = data.get("json") {
+                    return Some(json.clone());
                 }
+                return Some(data.clone());
             }
         }
     }
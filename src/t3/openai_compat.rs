@@ -0,0 +1,576 @@
+use async_stream::try_stream;
+use chrono::Utc;
+use futures_util::{Stream, StreamExt, pin_mut};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use super::client::{Client, Response, StreamDelta};
+use super::config::{Config, ReasoningEffort};
+use super::error::T3Error;
+use super::events::Usage;
+use super::message::{GeneratedImage, Message, MessageBuilder, Part, Type};
+
+/// The role of a message in the OpenAI chat-completions format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    System,
+    /// Treated like `System`; newer OpenAI models use it for instructions.
+    Developer,
+    User,
+    Assistant,
+    /// Parsed so payloads with tool results deserialize, but rejected on send.
+    Tool,
+}
+
+/// One part of a multi-part message content. Only `text` parts can be sent to t3.chat.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ContentPart {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Message content, either a plain string or a list of typed parts.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChatContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+/// A message in the OpenAI chat-completions format.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    #[serde(default)]
+    pub content: Option<ChatContent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The model's reasoning, in the field name used by OpenAI-compatible reasoning APIs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<String>,
+}
+
+/// A `POST /v1/chat/completions` request body. Sampling parameters such as `temperature`
+/// and `max_tokens` are accepted for compatibility but t3.chat does not apply them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChatCompletionRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    #[serde(default)]
+    pub stream: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// Enables t3.chat web search when present.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web_search_options: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// Any other fields of the request, kept so the request round-trips unchanged.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// What `Client::import_openai_messages` did with a transcript.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OpenAiImport {
+    /// How many messages were appended to the conversation.
+    pub imported: usize,
+    /// The indices of the entries that were skipped: `tool` and `function` results, and
+    /// assistant tool calls without text.
+    pub skipped: Vec<usize>,
+}
+
+/// Token counts in the OpenAI format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompletionUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+/// One completion of a `ChatCompletionResponse`. t3.chat always returns a single choice.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Choice {
+    pub index: u32,
+    pub message: ChatMessage,
+    pub finish_reason: Option<String>,
+}
+
+/// A `chat.completion` response body.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChatCompletionResponse {
+    pub id: String,
+    pub object: String,
+    pub created: i64,
+    pub model: String,
+    pub choices: Vec<Choice>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<CompletionUsage>,
+}
+
+/// The incremental message of a `ChunkChoice`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChunkDelta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<ChatRole>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<String>,
+}
+
+/// One choice of a `ChatCompletionChunk`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChunkChoice {
+    pub index: u32,
+    pub delta: ChunkDelta,
+    pub finish_reason: Option<String>,
+}
+
+/// A `chat.completion.chunk` object, sent as one SSE `data:` line of a streamed completion.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChatCompletionChunk {
+    pub id: String,
+    pub object: String,
+    pub created: i64,
+    pub model: String,
+    pub choices: Vec<ChunkChoice>,
+}
+
+impl ChatContent {
+    ///
+    /// Joins the content into plain text.
+    ///
+    /// # Returns
+    /// * `Result<String, T3Error>` - The text, or `T3Error::InvalidRequest` if a part is not text.
+    pub fn to_text(&self) -> Result<String, T3Error> {
+        match self {
+            ChatContent::Text(text) => Ok(text.clone()),
+            ChatContent::Parts(parts) => {
+                let mut texts = Vec::with_capacity(parts.len());
+                for part in parts {
+                    match (&*part.kind, &part.text) {
+                        ("text", Some(text)) => texts.push(text.as_str()),
+                        (kind, _) => {
+                            return Err(T3Error::InvalidRequest(format!(
+                                "unsupported content part type {kind:?}"
+                            )));
+                        }
+                    }
+                }
+                Ok(texts.join("\n"))
+            }
+        }
+    }
+}
+
+impl ChatMessage {
+    ///
+    /// Converts the message to a t3router `Message`. `developer` messages become system
+    /// messages and missing content becomes empty text.
+    ///
+    /// # Returns
+    /// * `Result<Message, T3Error>` - The message, or `T3Error::InvalidRequest` for `tool`
+    ///   messages and non-text content.
+    pub fn to_message(&self) -> Result<Message, T3Error> {
+        let role = match self.role {
+            ChatRole::System | ChatRole::Developer => Type::System,
+            ChatRole::User => Type::User,
+            ChatRole::Assistant => Type::Assistant,
+            ChatRole::Tool => {
+                return Err(T3Error::InvalidRequest(
+                    "tool messages are not supported".to_string(),
+                ));
+            }
+        };
+        let content = match &self.content {
+            Some(content) => content.to_text()?,
+            None => String::new(),
+        };
+        Ok(Message::new(role, content))
+    }
+}
+
+impl From<&Message> for ChatMessage {
+    fn from(message: &Message) -> Self {
+        ChatMessage {
+            role: match message.role {
+                Type::System => ChatRole::System,
+                Type::User => ChatRole::User,
+                Type::Assistant => ChatRole::Assistant,
+            },
+            content: Some(ChatContent::Text(message.content().to_string())),
+            name: None,
+            reasoning_content: message.reasoning.clone(),
+        }
+    }
+}
+
+impl From<&Usage> for CompletionUsage {
+    fn from(usage: &Usage) -> Self {
+        let prompt_tokens = usage.input_tokens.unwrap_or(0);
+        let completion_tokens = usage.output_tokens.unwrap_or(0);
+        CompletionUsage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: usage
+                .total_tokens
+                .unwrap_or(prompt_tokens + completion_tokens),
+        }
+    }
+}
+
+impl ChatCompletionRequest {
+    ///
+    /// Builds the request config from the client's default config: sets the reasoning
+    /// effort and enables search when `web_search_options` is present.
+    ///
+    /// # Arguments
+    /// * `base`: `&Config` - The config to start from.
+    ///
+    /// # Returns
+    /// * `Config` - The config for this request.
+    pub fn to_config(&self, base: &Config) -> Config {
+        let mut config = base.clone();
+        if let Some(effort) = self.reasoning_effort {
+            config.reasoning_effort = effort;
+        }
+        if self.web_search_options.is_some() {
+            config = config.with_search(true);
+        }
+        config
+    }
+}
+
+impl ChatCompletionResponse {
+    ///
+    /// Converts a `Client::send_detailed` response into a `chat.completion` object.
+    ///
+    /// # Arguments
+    /// * `response`: `&Response` - The response to convert.
+    /// * `model`: `&str` - The requested model, used when the server did not echo one.
+    ///
+    /// # Returns
+    /// * `ChatCompletionResponse` - The completion with a single choice.
+    pub fn from_response(response: &Response, model: &str) -> Self {
+        ChatCompletionResponse {
+            id: format!("chatcmpl-{}", response.message.id),
+            object: "chat.completion".to_string(),
+            created: Utc::now().timestamp(),
+            model: response.model.clone().unwrap_or_else(|| model.to_string()),
+            choices: vec![Choice {
+                index: 0,
+                message: ChatMessage::from(&response.message),
+                finish_reason: Some(openai_finish_reason(response.finish_reason.as_deref())),
+            }],
+            usage: response.usage.as_ref().map(CompletionUsage::from),
+        }
+    }
+}
+
+impl ChatCompletionChunk {
+    ///
+    /// Creates a chunk with a single choice.
+    ///
+    /// # Arguments
+    /// * `id`: `&str` - The completion ID, shared by all chunks of a stream.
+    /// * `created`: `i64` - The Unix timestamp of the completion.
+    /// * `model`: `&str` - The model name.
+    /// * `delta`: `ChunkDelta` - The incremental message.
+    /// * `finish_reason`: `Option<String>` - Set on the last chunk only.
+    ///
+    /// # Returns
+    /// * `ChatCompletionChunk` - The chunk.
+    pub fn new(
+        id: &str,
+        created: i64,
+        model: &str,
+        delta: ChunkDelta,
+        finish_reason: Option<String>,
+    ) -> Self {
+        ChatCompletionChunk {
+            id: id.to_string(),
+            object: "chat.completion.chunk".to_string(),
+            created,
+            model: model.to_string(),
+            choices: vec![ChunkChoice {
+                index: 0,
+                delta,
+                finish_reason,
+            }],
+        }
+    }
+}
+
+///
+/// Maps an AI SDK finish reason (`stop`, `length`, `content-filter`, `tool-calls`, ...) onto
+/// the OpenAI names. Unknown or missing reasons map to `stop`.
+///
+/// # Arguments
+/// * `reason`: `Option<&str>` - The finish reason reported by the stream.
+///
+/// # Returns
+/// * `String` - The OpenAI finish reason.
+pub fn openai_finish_reason(reason: Option<&str>) -> String {
+    match reason {
+        Some("length") => "length",
+        Some("content-filter") | Some("content_filter") => "content_filter",
+        Some("tool-calls") | Some("tool_calls") => "tool_calls",
+        _ => "stop",
+    }
+    .to_string()
+}
+
+impl Message {
+    ///
+    /// Converts one message of an OpenAI-format transcript. `system` and `developer` messages
+    /// become system messages. `content` may be a string, `null` or an array of `text` and
+    /// `image_url` parts; `reasoning_content` is kept as the reasoning.
+    ///
+    /// # Arguments
+    /// * `value`: `&Value` - The message object, e.g. `{"role": "user", "content": "hi"}`.
+    ///
+    /// # Returns
+    /// * `Result<Option<Message>, T3Error>` - The message, `None` for entries that have no
+    ///   t3.chat equivalent (`tool` and `function` results, assistant tool calls without
+    ///   text), or `T3Error::InvalidRequest` if the entry is malformed.
+    pub fn from_openai_value(value: &Value) -> Result<Option<Message>, T3Error> {
+        parse_openai_value(value).map_err(T3Error::InvalidRequest)
+    }
+}
+
+fn parse_openai_value(value: &Value) -> Result<Option<Message>, String> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| "expected a message object".to_string())?;
+    let role = match object.get("role").and_then(Value::as_str) {
+        Some("system" | "developer") => Type::System,
+        Some("user") => Type::User,
+        Some("assistant") => Type::Assistant,
+        Some("tool" | "function") => return Ok(None),
+        Some(role) => return Err(format!("unknown role {role:?}")),
+        None => return Err("missing role".to_string()),
+    };
+    let parts = match object.get("content").unwrap_or(&Value::Null) {
+        Value::Null => Vec::new(),
+        Value::String(text) => vec![Part::Text(text.clone())],
+        Value::Array(parts) => parts
+            .iter()
+            .map(openai_part)
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => return Err("content must be a string, null or an array of parts".to_string()),
+    };
+    let calls_tools = object.contains_key("tool_calls") || object.contains_key("function_call");
+    if parts.is_empty() && calls_tools {
+        return Ok(None);
+    }
+    let mut builder = parts
+        .into_iter()
+        .fold(Message::builder(role), MessageBuilder::part);
+    if let Some(reasoning) = object.get("reasoning_content").and_then(Value::as_str) {
+        builder = builder.reasoning(reasoning);
+    }
+    Ok(Some(builder.build()))
+}
+
+fn openai_part(part: &Value) -> Result<Part, String> {
+    let kind = part.get("type").and_then(Value::as_str).unwrap_or_default();
+    match kind {
+        "text" | "input_text" | "output_text" => part
+            .get("text")
+            .and_then(Value::as_str)
+            .map(|text| Part::Text(text.to_string()))
+            .ok_or_else(|| format!("{kind} part has no text")),
+        "image_url" => {
+            let image_url = part.get("image_url");
+            image_url
+                .and_then(|image| image.get("url"))
+                .or(image_url)
+                .and_then(Value::as_str)
+                .map(|url| Part::Image(GeneratedImage::new(url, None)))
+                .ok_or_else(|| "image_url part has no url".to_string())
+        }
+        _ => Err(format!("unsupported content part type {kind:?}")),
+    }
+}
+
+impl Client {
+    /**
+    Appends the messages of an OpenAI-format transcript to the current conversation, so it
+    can be continued with `send`. `json` is either an array of messages or an object with a
+    `messages` array, such as a saved chat-completions request. Entries without a t3.chat
+    equivalent are skipped; see `Message::from_openai_value`. A system message replaces any
+    earlier one, as with `append_message`. Nothing is appended if any entry is malformed.
+
+    # Arguments
+    * `self` - &mut Self: The client instance.
+    * `json` - &str: The transcript.
+
+    # Returns
+    * `Result<OpenAiImport, T3Error>` - How many messages were imported and which entries
+      were skipped, `T3Error::Json` if `json` is not JSON, or `T3Error::InvalidTranscript`
+      naming the first malformed entry.
+    */
+    pub fn import_openai_messages(&mut self, json: &str) -> Result<OpenAiImport, T3Error> {
+        let value: Value = serde_json::from_str(json)?;
+        let entries = match &value {
+            Value::Array(entries) => entries,
+            Value::Object(object) => match object.get("messages") {
+                Some(Value::Array(entries)) => entries,
+                _ => {
+                    return Err(T3Error::InvalidRequest(
+                        "transcript object has no messages array".to_string(),
+                    ));
+                }
+            },
+            _ => {
+                return Err(T3Error::InvalidRequest(
+                    "transcript must be an array of messages".to_string(),
+                ));
+            }
+        };
+        let mut messages = Vec::with_capacity(entries.len());
+        let mut skipped = Vec::new();
+        for (index, entry) in entries.iter().enumerate() {
+            match parse_openai_value(entry) {
+                Ok(Some(message)) => messages.push(message),
+                Ok(None) => skipped.push(index),
+                Err(reason) => return Err(T3Error::InvalidTranscript { index, reason }),
+            }
+        }
+        let imported = messages.len();
+        for message in messages {
+            self.append_message(message);
+        }
+        Ok(OpenAiImport { imported, skipped })
+    }
+
+    /**
+    Sends an OpenAI chat-completions request. OpenAI requests carry the whole history, so
+    the current conversation is replaced by the request's messages and a new thread is
+    started. Reasoning effort and web search are mapped onto the default config.
+
+    # Arguments
+    * `self` - &mut Self: The client instance.
+    * `request` - ChatCompletionRequest: The request to send.
+
+    # Returns
+    * `Result<ChatCompletionResponse, T3Error>` - The completion or an error.
+    */
+    pub async fn send_openai(
+        &mut self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionResponse, T3Error> {
+        let config = self.load_openai_request(&request)?;
+        let response = self
+            .send_detailed(request.model.as_str(), None, Some(config))
+            .await?;
+        Ok(ChatCompletionResponse::from_response(
+            &response,
+            &request.model,
+        ))
+    }
+
+    /**
+    Sends an OpenAI chat-completions request like `send_openai` and streams the reply as
+    `chat.completion.chunk` objects. The first chunk carries the assistant role and the last
+    one an empty delta with the finish reason.
+
+    # Arguments
+    * `self` - &mut Self: The client instance.
+    * `request` - ChatCompletionRequest: The request to send.
+
+    # Returns
+    * `impl Stream<Item = Result<ChatCompletionChunk, T3Error>>` - The chunks in arrival order.
+    */
+    pub fn send_openai_stream(
+        &mut self,
+        request: ChatCompletionRequest,
+    ) -> impl Stream<Item = Result<ChatCompletionChunk, T3Error>> + '_ {
+        try_stream! {
+            let config = self.load_openai_request(&request)?;
+            let model = request.model;
+            let id = format!("chatcmpl-{}", Uuid::new_v4());
+            let created = Utc::now().timestamp();
+            let role = ChunkDelta {
+                role: Some(ChatRole::Assistant),
+                ..ChunkDelta::default()
+            };
+            yield ChatCompletionChunk::new(&id, created, &model, role, None);
+            let deltas = self.send_stream(model.as_str(), None, Some(config));
+            pin_mut!(deltas);
+            while let Some(delta) = deltas.next().await {
+                let delta = match delta? {
+                    StreamDelta::Text(text) => ChunkDelta {
+                        content: Some(text),
+                        ..ChunkDelta::default()
+                    },
+                    StreamDelta::Reasoning(text) => ChunkDelta {
+                        reasoning_content: Some(text),
+                        ..ChunkDelta::default()
+                    },
+                    StreamDelta::Done(_) => {
+                        let finish_reason = Some(openai_finish_reason(None));
+                        yield ChatCompletionChunk::new(
+                            &id,
+                            created,
+                            &model,
+                            ChunkDelta::default(),
+                            finish_reason,
+                        );
+                        continue;
+                    }
+                    _ => continue,
+                };
+                yield ChatCompletionChunk::new(&id, created, &model, delta, None);
+            }
+        }
+    }
+
+    /**
+    Replaces the conversation with the messages of an OpenAI request and builds its config.
+    System and developer messages are joined into one system prompt.
+
+    # Arguments
+    * `self` - &mut Self: The client instance.
+    * `request` - &ChatCompletionRequest: The request.
+
+    # Returns
+    * `Result<Config, T3Error>` - The config for the request, `T3Error::EmptyConversation`
+      if it has no messages, or `T3Error::InvalidRequest` if a message cannot be converted.
+    */
+    fn load_openai_request(&mut self, request: &ChatCompletionRequest) -> Result<Config, T3Error> {
+        if request.messages.is_empty() {
+            return Err(T3Error::EmptyConversation);
+        }
+        let messages = request
+            .messages
+            .iter()
+            .map(ChatMessage::to_message)
+            .collect::<Result<Vec<_>, _>>()?;
+        let (system, messages): (Vec<Message>, Vec<Message>) = messages
+            .into_iter()
+            .partition(|message| matches!(message.role, Type::System));
+        self.new_conversation();
+        if !system.is_empty() {
+            let prompt: Vec<&str> = system.iter().map(Message::content).collect();
+            self.set_system_prompt(prompt.join("\n\n"));
+        }
+        for message in messages {
+            self.append_message(message);
+        }
+        Ok(request.to_config(self.get_default_config()))
+    }
+}
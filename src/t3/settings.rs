@@ -0,0 +1,196 @@
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+
+use super::config::Preferences;
+use super::error::T3Error;
+use super::history::HistoryClient;
+
+const SETTINGS_QUERY: &str = "userSettings:get";
+const SETTINGS_MUTATION: &str = "userSettings:update";
+
+/// Stored keys read into `Settings` fields rather than kept in `Settings::other`.
+const KNOWN_KEYS: [&str; 5] = [
+    "name",
+    "occupation",
+    "selectedTraits",
+    "additionalInfo",
+    "disableExternalLinkWarning",
+];
+
+/// The account's settings from the t3.chat Settings → Customization screen.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Settings {
+    /// Name, occupation, traits and additional info, as sent with every request.
+    pub preferences: Preferences,
+    /// Whether links in replies open without the "you are leaving t3.chat" prompt.
+    pub disable_external_link_warning: bool,
+    /// Any other stored settings, as returned by the server.
+    pub other: Map<String, Value>,
+}
+
+impl Settings {
+    ///
+    /// Reads settings from the stored document. Missing fields take their defaults and the
+    /// document's system fields, such as `_id`, are dropped.
+    ///
+    /// # Arguments
+    /// * `value`: `&Value` - The document, or `null` for an account that never saved any.
+    ///
+    /// # Returns
+    /// * `Settings` - The settings.
+    pub fn from_value(value: &Value) -> Settings {
+        let Some(fields) = value.as_object() else {
+            return Settings::default();
+        };
+        Settings {
+            preferences: serde_json::from_value(value.clone()).unwrap_or_default(),
+            disable_external_link_warning: fields
+                .get("disableExternalLinkWarning")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            other: fields
+                .iter()
+                .filter(|(key, _)| !key.starts_with('_') && !KNOWN_KEYS.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// A change to the account's settings for `Client::update_settings`. Only the fields that are
+/// `Some` are sent, so the rest keep their stored values.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsPatch {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub occupation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_traits: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub additional_info: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable_external_link_warning: Option<bool>,
+}
+
+impl SettingsPatch {
+    ///
+    /// Creates a patch that sets every customization field to the given preferences, e.g.
+    /// to give a fresh account the same customization as another.
+    ///
+    /// # Arguments
+    /// * `preferences`: `&Preferences` - The name, occupation, traits and additional info.
+    ///
+    /// # Returns
+    /// * `SettingsPatch` - The patch.
+    pub fn from_preferences(preferences: &Preferences) -> SettingsPatch {
+        SettingsPatch {
+            name: Some(preferences.name.clone()),
+            occupation: Some(preferences.occupation.clone()),
+            selected_traits: Some(preferences.selected_traits.clone()),
+            additional_info: Some(preferences.additional_info.clone()),
+            disable_external_link_warning: None,
+        }
+    }
+
+    ///
+    /// Checks whether the patch changes nothing.
+    ///
+    /// # Returns
+    /// * `bool` - `true` if no field is set.
+    pub fn is_empty(&self) -> bool {
+        *self == SettingsPatch::default()
+    }
+}
+
+///
+/// Fetches the account's settings.
+///
+/// # Arguments
+/// * `convex`: `&HistoryClient` - The Convex client with the account's session.
+///
+/// # Returns
+/// * `Result<Settings, T3Error>` - The settings, `T3Error::Auth` if the session is rejected.
+pub(crate) async fn fetch(convex: &HistoryClient) -> Result<Settings, T3Error> {
+    let value = convex
+        .convex_call(
+            "query",
+            SETTINGS_QUERY,
+            json!({ "sessionId": convex.convex_session_id() }),
+        )
+        .await?;
+    Ok(Settings::from_value(&value))
+}
+
+///
+/// Saves the fields of a patch. A rejected value is reported as `T3Error::InvalidSetting`.
+///
+/// # Arguments
+/// * `convex`: `&HistoryClient` - The Convex client with the account's session.
+/// * `patch`: `&SettingsPatch` - The fields to change.
+///
+/// # Returns
+/// * `Result<(), T3Error>` - `T3Error::InvalidSetting` with the field the server rejected.
+pub(crate) async fn update(convex: &HistoryClient, patch: &SettingsPatch) -> Result<(), T3Error> {
+    let Value::Object(mut args) = serde_json::to_value(patch)? else {
+        unreachable!("a SettingsPatch serializes to a JSON object");
+    };
+    let fields: Vec<String> = args.keys().cloned().collect();
+    args.insert(
+        "sessionId".to_string(),
+        Value::from(convex.convex_session_id()),
+    );
+    match convex
+        .convex_call("mutation", SETTINGS_MUTATION, Value::Object(args))
+        .await
+    {
+        Err(T3Error::Convex(message)) => Err(match rejected_field(&message, &fields) {
+            Some(field) => T3Error::InvalidSetting { field, message },
+            None => T3Error::Convex(message),
+        }),
+        other => other.map(|_| ()),
+    }
+}
+
+///
+/// Finds the field a Convex error is about, from the validator's `Path: .field` line, an
+/// "extra field" complaint, or else the first patched field the message names.
+///
+/// # Arguments
+/// * `message`: `&str` - The Convex error message.
+/// * `fields`: `&[String]` - The patched fields, in their stored camelCase form.
+///
+/// # Returns
+/// * `Option<String>` - The field in snake_case, as named on `SettingsPatch`, or `None`.
+fn rejected_field(message: &str, fields: &[String]) -> Option<String> {
+    let after = |marker: &str| {
+        let rest = &message[message.find(marker)? + marker.len()..];
+        let end = rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(rest.len());
+        (end > 0).then(|| rest[..end].to_string())
+    };
+    let field = after("Path: .")
+        .or_else(|| after("extra field `"))
+        .or_else(|| {
+            fields
+                .iter()
+                .find(|field| message.contains(field.as_str()))
+                .cloned()
+        })?;
+    Some(snake_case(&field))
+}
+
+fn snake_case(field: &str) -> String {
+    let mut out = String::with_capacity(field.len() + 4);
+    for c in field.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('_');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
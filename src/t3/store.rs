@@ -0,0 +1,314 @@
+use std::path::Path;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde_json::{Map, Value};
+
+use super::client::Client;
+use super::conversation::Conversation;
+use super::error::T3Error;
+use super::message::Message;
+use super::thread::ThreadId;
+
+/// Schema migrations, applied in order. `PRAGMA user_version` records how many have run, so
+/// append new ones and never edit a released one.
+const MIGRATIONS: &[&str] = &["
+    CREATE TABLE threads (
+        thread_id TEXT PRIMARY KEY NOT NULL,
+        title TEXT,
+        system_prompt TEXT,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL
+    );
+    CREATE TABLE messages (
+        thread_id TEXT NOT NULL REFERENCES threads (thread_id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        id TEXT NOT NULL,
+        role TEXT NOT NULL,
+        content TEXT NOT NULL,
+        content_type TEXT NOT NULL,
+        image_url TEXT,
+        base64_data TEXT,
+        created_at TEXT NOT NULL,
+        completed_at TEXT,
+        details TEXT NOT NULL,
+        PRIMARY KEY (thread_id, position)
+    );
+"];
+
+/// Message fields stored in columns of their own. The rest of the message is kept as JSON in
+/// the `details` column.
+const MESSAGE_COLUMNS: [&str; 8] = [
+    "id",
+    "role",
+    "content",
+    "content_type",
+    "image_url",
+    "base64_data",
+    "created_at",
+    "completed_at",
+];
+
+/// A thread saved in a `ConversationStore`, as listed by `ConversationStore::list`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredThread {
+    pub thread_id: ThreadId,
+    /// The title from the conversation's config, if it set one.
+    pub title: Option<String>,
+    pub system_prompt: Option<String>,
+    pub message_count: usize,
+    /// When the thread was first saved.
+    pub created_at: DateTime<Utc>,
+    /// When the thread was last saved.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Durable conversation storage in a SQLite database. Each save replaces the stored messages
+/// of the thread, so saving after every send keeps the database in step with the client.
+/// Message role, content, content type, image URL, inline image data and timestamps are plain
+/// columns that can be queried directly; the schema is created and migrated on open.
+#[derive(Debug)]
+pub struct ConversationStore {
+    conn: Connection,
+}
+
+impl ConversationStore {
+    ///
+    /// Opens the database at `path`, creating it if missing, and applies pending migrations.
+    ///
+    /// # Arguments
+    /// * `path`: `&Path` - The database file.
+    ///
+    /// # Returns
+    /// * `Result<ConversationStore, T3Error>` - The store, or `T3Error::Sqlite`.
+    pub fn open(path: &Path) -> Result<Self, T3Error> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    ///
+    /// Opens a store that lives in memory and is lost when dropped, for tests.
+    ///
+    /// # Returns
+    /// * `Result<ConversationStore, T3Error>` - The store, or `T3Error::Sqlite`.
+    pub fn open_in_memory() -> Result<Self, T3Error> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(mut conn: Connection) -> Result<Self, T3Error> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        let applied: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(applied as usize) {
+            let tx = conn.transaction()?;
+            tx.execute_batch(migration)?;
+            tx.pragma_update(None, "user_version", index as i64 + 1)?;
+            tx.commit()?;
+        }
+        Ok(ConversationStore { conn })
+    }
+
+    ///
+    /// Saves the client's current conversation under its thread ID.
+    ///
+    /// # Arguments
+    /// * `client`: `&Client` - The client.
+    ///
+    /// # Returns
+    /// * `Result<ThreadId, T3Error>` - The thread saved, or `T3Error::InvalidRequest` if the
+    ///   conversation has no thread yet because nothing was sent.
+    pub fn save(&self, client: &Client) -> Result<ThreadId, T3Error> {
+        self.save_conversation(client.conversation())
+    }
+
+    ///
+    /// Saves a conversation under its thread ID, replacing what was stored for the thread.
+    /// Archived messages are not saved.
+    ///
+    /// # Arguments
+    /// * `conversation`: `&Conversation` - The conversation, e.g. one driven with `send_in`.
+    ///
+    /// # Returns
+    /// * `Result<ThreadId, T3Error>` - The thread saved, or `T3Error::InvalidRequest` if the
+    ///   conversation has no thread yet.
+    pub fn save_conversation(&self, conversation: &Conversation) -> Result<ThreadId, T3Error> {
+        let thread_id = *conversation.thread_id().ok_or_else(|| {
+            T3Error::InvalidRequest("conversation has no thread to save yet".to_string())
+        })?;
+        let title = conversation
+            .config()
+            .and_then(|config| config.thread_title.as_deref());
+        let now = Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true);
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO threads (thread_id, title, system_prompt, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)
+             ON CONFLICT (thread_id) DO UPDATE SET
+                 title = excluded.title,
+                 system_prompt = excluded.system_prompt,
+                 updated_at = excluded.updated_at",
+            params![
+                thread_id.to_string(),
+                title,
+                conversation.system_prompt(),
+                now
+            ],
+        )?;
+        tx.execute(
+            "DELETE FROM messages WHERE thread_id = ?1",
+            params![thread_id.to_string()],
+        )?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO messages (thread_id, position, id, role, content, content_type,
+                     image_url, base64_data, created_at, completed_at, details)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )?;
+            for (position, message) in conversation.messages().iter().enumerate() {
+                let Value::Object(mut fields) = serde_json::to_value(message)? else {
+                    unreachable!("a Message serializes to a JSON object");
+                };
+                let values = MESSAGE_COLUMNS.map(|name| match fields.remove(name) {
+                    Some(Value::String(text)) => Some(text),
+                    _ => None,
+                });
+                insert.execute(params![
+                    thread_id.to_string(),
+                    position as i64,
+                    values[0],
+                    values[1],
+                    values[2],
+                    values[3],
+                    values[4],
+                    values[5],
+                    values[6],
+                    values[7],
+                    Value::Object(fields).to_string(),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(thread_id)
+    }
+
+    ///
+    /// Loads the messages of a saved thread, in conversation order.
+    ///
+    /// # Arguments
+    /// * `thread_id`: `&ThreadId` - The thread.
+    ///
+    /// # Returns
+    /// * `Result<Vec<Message>, T3Error>` - The messages, or `T3Error::NotFound` if the thread
+    ///   was never saved.
+    pub fn load(&self, thread_id: &ThreadId) -> Result<Vec<Message>, T3Error> {
+        let exists = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM threads WHERE thread_id = ?1",
+                params![thread_id.to_string()],
+                |_| Ok(()),
+            )
+            .optional()?;
+        if exists.is_none() {
+            return Err(T3Error::NotFound(format!("stored thread {}", thread_id)));
+        }
+        let mut select = self.conn.prepare(
+            "SELECT id, role, content, content_type, image_url, base64_data, created_at,
+                 completed_at, details
+             FROM messages WHERE thread_id = ?1 ORDER BY position",
+        )?;
+        let rows = select.query_map(params![thread_id.to_string()], |row| {
+            let mut values = Vec::with_capacity(MESSAGE_COLUMNS.len());
+            for index in 0..MESSAGE_COLUMNS.len() {
+                values.push(row.get::<_, Option<String>>(index)?);
+            }
+            let details: String = row.get(MESSAGE_COLUMNS.len())?;
+            Ok((values, details))
+        })?;
+        let mut messages = Vec::new();
+        for row in rows {
+            let (values, details) = row?;
+            let mut fields: Map<String, Value> = serde_json::from_str(&details)?;
+            for (name, value) in MESSAGE_COLUMNS.iter().zip(values) {
+                fields.insert(name.to_string(), value.map_or(Value::Null, Value::String));
+            }
+            messages.push(serde_json::from_value(Value::Object(fields))?);
+        }
+        Ok(messages)
+    }
+
+    ///
+    /// Loads a saved thread as a conversation, ready for `Client::send_in`.
+    ///
+    /// # Arguments
+    /// * `thread_id`: `&ThreadId` - The thread.
+    ///
+    /// # Returns
+    /// * `Result<Conversation, T3Error>` - The conversation, or `T3Error::NotFound`.
+    pub fn load_conversation(&self, thread_id: &ThreadId) -> Result<Conversation, T3Error> {
+        let mut conversation = Conversation::new();
+        conversation.set_thread_id(*thread_id);
+        for message in self.load(thread_id)? {
+            conversation.append_message(message);
+        }
+        Ok(conversation)
+    }
+
+    ///
+    /// Lists the saved threads, most recently saved first.
+    ///
+    /// # Returns
+    /// * `Result<Vec<StoredThread>, T3Error>` - The threads.
+    pub fn list(&self) -> Result<Vec<StoredThread>, T3Error> {
+        let mut select = self.conn.prepare(
+            "SELECT t.thread_id, t.title, t.system_prompt, t.created_at, t.updated_at,
+                 (SELECT COUNT(*) FROM messages m WHERE m.thread_id = t.thread_id)
+             FROM threads t ORDER BY t.updated_at DESC, t.thread_id",
+        )?;
+        let rows = select.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, i64>(5)?,
+            ))
+        })?;
+        let mut threads = Vec::new();
+        for row in rows {
+            let (thread_id, title, system_prompt, created_at, updated_at, count) = row?;
+            threads.push(StoredThread {
+                thread_id: thread_id.parse()?,
+                title,
+                system_prompt,
+                message_count: count as usize,
+                created_at: parse_timestamp(&created_at)?,
+                updated_at: parse_timestamp(&updated_at)?,
+            });
+        }
+        Ok(threads)
+    }
+
+    ///
+    /// Deletes a saved thread and its messages.
+    ///
+    /// # Arguments
+    /// * `thread_id`: `&ThreadId` - The thread.
+    ///
+    /// # Returns
+    /// * `Result<bool, T3Error>` - Whether the thread was stored.
+    pub fn delete(&self, thread_id: &ThreadId) -> Result<bool, T3Error> {
+        let deleted = self.conn.execute(
+            "DELETE FROM threads WHERE thread_id = ?1",
+            params![thread_id.to_string()],
+        )?;
+        Ok(deleted > 0)
+    }
+}
+
+fn parse_timestamp(text: &str) -> Result<DateTime<Utc>, T3Error> {
+    DateTime::parse_from_rfc3339(text)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| T3Error::Parse {
+            raw: text.to_string(),
+        })
+}
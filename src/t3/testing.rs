@@ -0,0 +1,243 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use futures_util::stream::{self, BoxStream, StreamExt};
+
+use super::backend::ChatBackend;
+use super::client::StreamDelta;
+use super::clock::Clock;
+use super::config::Config;
+use super::error::T3Error;
+use super::message::{Message, Type};
+use super::models::ModelId;
+use super::thread::ThreadId;
+
+/// A request received by `MockBackend`.
+#[derive(Debug, Clone)]
+pub struct SentRequest {
+    pub model: ModelId,
+    pub new_message: Option<Message>,
+    pub config: Option<Config>,
+    /// The conversation as it would have been sent, including `new_message`.
+    pub messages: Vec<Message>,
+}
+
+/// A `ChatBackend` that answers with scripted replies and records every request, for
+/// testing code that talks to t3.chat without network access or cookies. Replies are used
+/// in order; a send with none left fails with `T3Error::EmptyResponse`.
+#[derive(Default)]
+pub struct MockBackend {
+    replies: VecDeque<Result<Message, T3Error>>,
+    sent: Vec<SentRequest>,
+    messages: Vec<Message>,
+    thread_id: Option<ThreadId>,
+}
+
+impl MockBackend {
+    ///
+    /// Creates a mock with no scripted replies.
+    ///
+    /// # Returns
+    /// * `MockBackend` - The mock.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Queues an assistant reply with the given text.
+    ///
+    /// # Arguments
+    /// * `text`: `&str` - The reply text.
+    ///
+    /// # Returns
+    /// * `MockBackend` - The updated mock.
+    pub fn with_reply(mut self, text: &str) -> Self {
+        self.push_reply(Message::new(Type::Assistant, text.to_string()));
+        self
+    }
+
+    ///
+    /// Queues a reply message.
+    ///
+    /// # Arguments
+    /// * `message`: `Message` - The reply.
+    pub fn push_reply(&mut self, message: Message) {
+        self.replies.push_back(Ok(message));
+    }
+
+    ///
+    /// Queues an error, returned by the next send instead of a reply.
+    ///
+    /// # Arguments
+    /// * `error`: `T3Error` - The error.
+    pub fn push_error(&mut self, error: T3Error) {
+        self.replies.push_back(Err(error));
+    }
+
+    ///
+    /// Gets the requests received so far, oldest first.
+    ///
+    /// # Returns
+    /// * `&[SentRequest]` - The requests.
+    pub fn sent(&self) -> &[SentRequest] {
+        &self.sent
+    }
+
+    ///
+    /// Records a request and takes the next scripted reply. Like `Client::send`, a reply
+    /// appends the new message and itself to the conversation, and an error leaves the
+    /// conversation as it was.
+    ///
+    /// # Returns
+    /// * `Result<Message, T3Error>` - The scripted reply or error.
+    fn respond(
+        &mut self,
+        model: ModelId,
+        new_message: Option<Message>,
+        config: Option<Config>,
+    ) -> Result<Message, T3Error> {
+        let mut messages = self.messages.clone();
+        messages.extend(new_message.iter().cloned());
+        self.sent.push(SentRequest {
+            model,
+            new_message,
+            config,
+            messages: messages.clone(),
+        });
+        let reply = self
+            .replies
+            .pop_front()
+            .unwrap_or(Err(T3Error::EmptyResponse))?;
+        self.thread_id.get_or_insert_with(ThreadId::new);
+        messages.push(reply.clone());
+        self.messages = messages;
+        Ok(reply)
+    }
+}
+
+impl ChatBackend for MockBackend {
+    fn send(
+        &mut self,
+        model: ModelId,
+        new_message: Option<Message>,
+        config: Option<Config>,
+    ) -> BoxFuture<'_, Result<Message, T3Error>> {
+        let reply = self.respond(model, new_message, config);
+        Box::pin(async move { reply })
+    }
+
+    fn send_stream(
+        &mut self,
+        model: ModelId,
+        new_message: Option<Message>,
+        config: Option<Config>,
+    ) -> BoxStream<'_, Result<StreamDelta, T3Error>> {
+        let deltas = match self.respond(model, new_message, config) {
+            Ok(reply) => vec![
+                Ok(StreamDelta::Text(reply.content().to_string())),
+                Ok(StreamDelta::Done(reply)),
+            ],
+            Err(err) => vec![Err(err)],
+        };
+        stream::iter(deltas).boxed()
+    }
+
+    fn new_conversation(&mut self) {
+        self.messages.clear();
+        self.thread_id = None;
+    }
+
+    fn thread_id(&self) -> Option<ThreadId> {
+        self.thread_id
+    }
+
+    fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+}
+
+/// A `Clock` whose time only moves when something sleeps on it: a sleep returns at once with
+/// the clock moved forward by its duration. Set on a `Client`, a session of any simulated length
+/// then runs instantly and the same way every time, with pacing, retry backoff and session
+/// expiry all following the simulated time. Clones share the same time.
+#[derive(Clone)]
+pub struct MockClock {
+    start: Instant,
+    start_utc: DateTime<Utc>,
+    time: Arc<Mutex<MockTime>>,
+}
+
+/// The simulated time a `MockClock` and its clones share.
+#[derive(Default)]
+struct MockTime {
+    elapsed: Duration,
+    sleeps: Vec<Duration>,
+}
+
+impl MockClock {
+    ///
+    /// Creates a clock starting at the current time.
+    ///
+    /// # Returns
+    /// * `MockClock` - The clock.
+    pub fn new() -> Self {
+        MockClock {
+            start: Instant::now(),
+            start_utc: Utc::now(),
+            time: Arc::default(),
+        }
+    }
+
+    ///
+    /// Gets how far the clock has moved since it was created.
+    ///
+    /// # Returns
+    /// * `Duration` - The simulated time passed.
+    pub fn elapsed(&self) -> Duration {
+        self.time.lock().unwrap().elapsed
+    }
+
+    ///
+    /// Gets every sleep so far, oldest first.
+    ///
+    /// # Returns
+    /// * `Vec<Duration>` - The sleeps.
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.time.lock().unwrap().sleeps.clone()
+    }
+
+    ///
+    /// Moves the clock forward without a sleep, as time spent elsewhere would.
+    ///
+    /// # Arguments
+    /// * `duration`: `Duration` - How far to move.
+    pub fn advance(&self, duration: Duration) {
+        self.time.lock().unwrap().elapsed += duration;
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        let mut time = self.time.lock().unwrap();
+        time.elapsed += duration;
+        time.sleeps.push(duration);
+        Box::pin(async {})
+    }
+
+    fn utc_now(&self) -> DateTime<Utc> {
+        self.start_utc + chrono::Duration::from_std(self.elapsed()).unwrap_or_default()
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Identifier of a t3.chat thread. Always holds a valid UUID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ThreadId(Uuid);
+
+/// Alias kept for callers that think in terms of conversations rather than threads.
+pub type ConversationId = ThreadId;
+
+/// Error returned when a string cannot be parsed into a `ThreadId`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseThreadIdError {
+    pub input: String,
+}
+
+impl fmt::Display for ParseThreadIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid thread id {:?}: expected a UUID", self.input)
+    }
+}
+
+impl std::error::Error for ParseThreadIdError {}
+
+impl ThreadId {
+    ///
+    /// Generates a new random thread ID.
+    ///
+    /// # Returns
+    /// * `ThreadId` - A fresh v4 UUID thread ID.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    ///
+    /// Wraps an existing UUID.
+    ///
+    /// # Arguments
+    /// * `uuid`: `Uuid` - The UUID of the thread.
+    ///
+    /// # Returns
+    /// * `ThreadId` - The thread ID.
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    ///
+    /// Returns the underlying UUID.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The thread ID.
+    ///
+    /// # Returns
+    /// * `&Uuid` - The wrapped UUID.
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+
+    ///
+    /// Extracts the thread ID from a t3.chat URL such as `https://t3.chat/chat/<id>`.
+    ///
+    /// # Arguments
+    /// * `url`: `&str` - The URL copied from the browser.
+    ///
+    /// # Returns
+    /// * `Result<ThreadId, ParseThreadIdError>` - The thread ID or an error if the URL has no valid ID.
+    pub fn from_url(url: &str) -> Result<Self, ParseThreadIdError> {
+        let segment = url
+            .split(['?', '#'])
+            .next()
+            .unwrap_or("")
+            .split_once("/chat/")
+            .map(|(_, rest)| rest.trim_end_matches('/'))
+            .ok_or_else(|| ParseThreadIdError {
+                input: url.to_string(),
+            })?;
+        segment.parse().map_err(|_| ParseThreadIdError {
+            input: url.to_string(),
+        })
+    }
+}
+
+impl Default for ThreadId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for ThreadId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.hyphenated().fmt(f)
+    }
+}
+
+impl FromStr for ThreadId {
+    type Err = ParseThreadIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Uuid::parse_str(s.trim())
+            .map(Self)
+            .map_err(|_| ParseThreadIdError {
+                input: s.to_string(),
+            })
+    }
+}
+
+impl TryFrom<String> for ThreadId {
+    type Error = ParseThreadIdError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ThreadId> for String {
+    fn from(id: ThreadId) -> Self {
+        id.to_string()
+    }
+}
+
+impl From<Uuid> for ThreadId {
+    fn from(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+}
@@ -0,0 +1,112 @@
+use super::message::Message;
+
+/// Tokens added for each message on top of its text: the role, ID and JSON framing of the
+/// request body.
+pub const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Counts the tokens a piece of text uses, for budgeting what is sent to a model.
+/// Implement it to plug in an exact tokenizer; `Client` uses `HeuristicCounter` by default.
+pub trait TokenCounter: Send + Sync {
+    ///
+    /// Counts the tokens in a piece of text.
+    ///
+    /// # Arguments
+    /// * `text`: `&str` - The text to count.
+    ///
+    /// # Returns
+    /// * `usize` - The number of tokens.
+    fn count(&self, text: &str) -> usize;
+}
+
+/// Estimates one token per four characters, rounded up. Close enough for English text with
+/// most tokenizers, and needs no vocabulary.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HeuristicCounter;
+
+impl TokenCounter for HeuristicCounter {
+    fn count(&self, text: &str) -> usize {
+        estimate_tokens(text)
+    }
+}
+
+/// Counts tokens exactly with the BPE vocabulary of an OpenAI model.
+#[cfg(feature = "tiktoken")]
+pub struct TiktokenCounter {
+    bpe: tiktoken_rs::CoreBPE,
+}
+
+#[cfg(feature = "tiktoken")]
+impl TiktokenCounter {
+    ///
+    /// Loads the vocabulary used by a model, such as `gpt-4o` or `gpt-4`.
+    ///
+    /// # Arguments
+    /// * `model`: `&str` - The OpenAI model name.
+    ///
+    /// # Returns
+    /// * `Option<TiktokenCounter>` - The counter, or `None` if the model is not known to tiktoken.
+    pub fn for_model(model: &str) -> Option<Self> {
+        tiktoken_rs::get_bpe_from_model(model)
+            .ok()
+            .map(|bpe| TiktokenCounter { bpe })
+    }
+
+    ///
+    /// Loads the `o200k_base` vocabulary of the GPT-4o family.
+    ///
+    /// # Returns
+    /// * `Option<TiktokenCounter>` - The counter, or `None` if the vocabulary fails to load.
+    pub fn o200k() -> Option<Self> {
+        tiktoken_rs::o200k_base()
+            .ok()
+            .map(|bpe| TiktokenCounter { bpe })
+    }
+
+    ///
+    /// Loads the `cl100k_base` vocabulary of GPT-4 and GPT-3.5.
+    ///
+    /// # Returns
+    /// * `Option<TiktokenCounter>` - The counter, or `None` if the vocabulary fails to load.
+    pub fn cl100k() -> Option<Self> {
+        tiktoken_rs::cl100k_base()
+            .ok()
+            .map(|bpe| TiktokenCounter { bpe })
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+impl TokenCounter for TiktokenCounter {
+    fn count(&self, text: &str) -> usize {
+        self.bpe.encode_with_special_tokens(text).len()
+    }
+}
+
+///
+/// Estimates the tokens in a piece of text at four characters per token, rounded up.
+///
+/// # Arguments
+/// * `text`: `&str` - The text to estimate.
+///
+/// # Returns
+/// * `usize` - The estimated number of tokens.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+///
+/// Counts the tokens a message adds to a request: its text plus `MESSAGE_OVERHEAD_TOKENS`.
+/// Local-only messages are never sent and count as zero.
+///
+/// # Arguments
+/// * `counter`: `&dyn TokenCounter` - The counter to use.
+/// * `message`: `&Message` - The message.
+///
+/// # Returns
+/// * `usize` - The number of tokens.
+pub fn message_tokens(counter: &dyn TokenCounter, message: &Message) -> usize {
+    if message.local_only {
+        0
+    } else {
+        counter.count(message.content()) + MESSAGE_OVERHEAD_TOKENS
+    }
+}
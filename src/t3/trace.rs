@@ -0,0 +1,88 @@
+use std::future::Future;
+
+use futures_util::Stream;
+
+use super::thread::ThreadId;
+
+/// Emits a `tracing::debug!` event, or nothing without the `tracing` feature, so call sites
+/// need no `cfg` of their own. Arguments are only evaluated when the feature is on.
+macro_rules! debug_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+/// Emits a `tracing::warn!` event, like `debug_event!`.
+macro_rules! warn_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::warn!($($arg)*);
+    };
+}
+
+pub(crate) use {debug_event, warn_event};
+
+///
+/// Runs a send inside a `send` span. Every send goes through `in_send_span` or
+/// `stream_in_send_span`, which `record_chat` fills in once the request is built.
+///
+/// # Arguments
+/// * `send`: `F` - The send.
+///
+/// # Returns
+/// * `impl Future<Output = F::Output>` - The send, in the span when the `tracing` feature is on.
+pub(crate) fn in_send_span<F: Future>(send: F) -> impl Future<Output = F::Output> {
+    #[cfg(feature = "tracing")]
+    let send = tracing::Instrument::instrument(send, send_span());
+    send
+}
+
+///
+/// Polls a streamed send inside a `send` span, like `in_send_span`.
+///
+/// # Arguments
+/// * `deltas`: `S` - The reply stream.
+///
+/// # Returns
+/// * `impl Stream<Item = S::Item>` - The stream, in the span when the `tracing` feature is on.
+pub(crate) fn stream_in_send_span<S: Stream>(deltas: S) -> impl Stream<Item = S::Item> {
+    #[cfg(feature = "tracing")]
+    let deltas = {
+        let span = send_span();
+        let mut deltas = Box::pin(deltas);
+        futures_util::stream::poll_fn(move |cx| {
+            let _entered = span.enter();
+            deltas.as_mut().poll_next(cx)
+        })
+    };
+    deltas
+}
+
+#[cfg(feature = "tracing")]
+fn send_span() -> tracing::Span {
+    tracing::info_span!(
+        "send",
+        model = tracing::field::Empty,
+        thread_id = tracing::field::Empty
+    )
+}
+
+///
+/// Records the model and thread on the current `send` span.
+///
+/// # Arguments
+/// * `model`: `&str` - The model ID.
+/// * `thread_id`: `Option<&ThreadId>` - The thread the request is sent to, if any.
+pub(crate) fn record_chat(model: &str, thread_id: Option<&ThreadId>) {
+    #[cfg(feature = "tracing")]
+    {
+        let span = tracing::Span::current();
+        span.record("model", model);
+        if let Some(thread_id) = thread_id {
+            span.record("thread_id", tracing::field::display(thread_id));
+        }
+    }
+    #[cfg(not(feature = "tracing"))]
+    let _ = (model, thread_id);
+}
@@ -1,5 +1,14 @@
+use chrono::{DateTime, Utc};
 use serde_json::Value;
-use wreq_util::Emulation;
+
+use super::config::BaseUrls;
+use super::error::T3Error;
+use super::http::{HttpOptions, build_http_client};
+use super::models::{ModelInfo, Plan};
+
+const CUSTOMER_DATA_PATH: &str = "/api/trpc/getCustomerData?batch=1&input=%7B%220%22%3A%7B%22json%22%3A%7B%22sessionId%22%3Anull%7D%2C%22meta%22%3A%7B%22values%22%3A%7B%22sessionId%22%3A%5B%22undefined%22%5D%7D%7D%7D%7D";
+pub(crate) const ACTIVE_SESSIONS_PATH: &str = "/api/trpc/auth.getActiveSessions?batch=1&input=%7B%220%22%3A%7B%22json%22%3A%7B%22includeLocation%22%3Afalse%7D%7D%7D";
+const SUBSCRIPTION_DATA_PATH: &str = "/api/trpc/getSubscriptionData?batch=1&input=%7B%220%22%3A%7B%22json%22%3Anull%2C%22meta%22%3A%7B%22values%22%3A%5B%22undefined%22%5D%7D%7D%7D";
 
 fn parse_iso_timestamp(s: &str) -> Option<i64> {
     use chrono::DateTime;
@@ -8,7 +17,7 @@ fn parse_iso_timestamp(s: &str) -> Option<i64> {
         .map(|dt| dt.timestamp_millis())
 }
 
-fn extract_trpc_result(body: &str) -> Option<Value> {
+fn trpc_candidates(body: &str) -> Vec<Value> {
     let mut candidates: Vec<Value> = Vec::new();
     if let Ok(v) = serde_json::from_str::<Value>(body) {
         collect_candidates_from_value(&v, &mut candidates);
@@ -22,16 +31,20 @@ fn extract_trpc_result(body: &str) -> Option<Value> {
             collect_candidates_from_value(&v, &mut candidates);
         }
     }
+    candidates
+}
+
+fn extract_trpc_result(body: &str) -> Option<Value> {
+    let candidates = trpc_candidates(body);
     for c in &candidates {
-        if c.is_object() {
-            if c.get("subTier").is_some()
+        if c.is_object()
+            && (c.get("subTier").is_some()
                 || c.get("isPaid").is_some()
                 || c.get("sessionId").is_some()
                 || c.get("balance").is_some()
-                || c.get("id").is_some()
-            {
-                return Some(c.clone());
-            }
+                || c.get("id").is_some())
+        {
+            return Some(c.clone());
         }
     }
     for c in &candidates {
@@ -43,46 +56,41 @@ fn extract_trpc_result(body: &str) -> Option<Value> {
 }
 
 fn collect_candidates_from_value(v: &Value, candidates: &mut Vec<Value>) {
-    if let Some(result) = v.get("result") {
-        if let Some(data) = result.get("data") {
-            if let Some(json) = data.get("json") {
-                if json.is_object() || json.is_array() {
-                    candidates.push(json.clone());
-                }
-            }
-        }
+    if let Some(result) = v.get("result")
+        && let Some(data) = result.get("data")
+        && let Some(json) = data.get("json")
+        && (json.is_object() || json.is_array())
+    {
+        candidates.push(json.clone());
     }
     if let Some(json_val) = v.get("json") {
-        if let Some(json_arr) = json_val.as_array() {
-            if json_arr.len() >= 3 {
-                if let Some(data_part) = json_arr.get(2) {
-                    if let Some(data_arr) = data_part.as_array() {
-                        if let Some(inner_arr) = data_arr.first().and_then(|x| x.as_array()) {
-                            if let Some(data) = inner_arr.first() {
-                                if data.is_object() || data.is_array() {
-                                    candidates.push(data.clone());
-                                }
-                            }
-                        }
-                        if let Some(data) = data_arr.first() {
-                            if data.is_object() || data.is_array() {
-                                candidates.push(data.clone());
-                            }
-                        }
-                    }
-                    if data_part.is_object() || data_part.is_array() {
-                        candidates.push(data_part.clone());
-                    }
+        if let Some(json_arr) = json_val.as_array()
+            && json_arr.len() >= 3
+            && let Some(data_part) = json_arr.get(2)
+        {
+            if let Some(data_arr) = data_part.as_array() {
+                if let Some(inner_arr) = data_arr.first().and_then(|x| x.as_array())
+                    && let Some(data) = inner_arr.first()
+                    && (data.is_object() || data.is_array())
+                {
+                    candidates.push(data.clone());
+                }
+                if let Some(data) = data_arr.first()
+                    && (data.is_object() || data.is_array())
+                {
+                    candidates.push(data.clone());
                 }
             }
+            if data_part.is_object() || data_part.is_array() {
+                candidates.push(data_part.clone());
+            }
         }
-        if json_val.is_object() {
-            if json_val.get("subTier").is_some()
+        if json_val.is_object()
+            && (json_val.get("subTier").is_some()
                 || json_val.get("isPaid").is_some()
-                || json_val.get("sessionId").is_some()
-            {
-                candidates.push(json_val.clone());
-            }
+                || json_val.get("sessionId").is_some())
+        {
+            candidates.push(json_val.clone());
         }
         if json_val.is_array() {
             candidates.push(json_val.clone());
@@ -90,19 +98,185 @@ fn collect_candidates_from_value(v: &Value, candidates: &mut Vec<Value>) {
     }
     if let Some(arr) = v.as_array() {
         for item in arr {
-            if let Some(result) = item.get("result") {
-                if let Some(data) = result.get("data") {
-                    if let Some(json) = data.get("json") {
-                        if json.is_object() || json.is_array() {
-                            candidates.push(json.clone());
-                        }
-                    }
-                }
+            if let Some(result) = item.get("result")
+                && let Some(data) = result.get("data")
+                && let Some(json) = data.get("json")
+                && (json.is_object() || json.is_array())
+            {
+                candidates.push(json.clone());
             }
         }
     }
 }
 
+/// Find the result of `auth.getActiveSessions` in a tRPC response body. Unlike
+/// `extract_trpc_result`, which stops at the first object with an `id`, this keeps the whole
+/// list: an object with a `sessions` list, or else the longest list of session objects.
+///
+/// # Arguments
+/// * `body` - &str: The response body, plain JSON or jsonl batch framing.
+///
+/// # Returns
+/// * `Option<Value>` - The result, or `None` if the body holds no sessions.
+fn extract_active_sessions(body: &str) -> Option<Value> {
+    let candidates = trpc_candidates(body);
+    if let Some(found) = candidates
+        .iter()
+        .find(|c| c.get("sessions").is_some_and(Value::is_array))
+    {
+        return Some(found.clone());
+    }
+    candidates
+        .into_iter()
+        .filter(|c| {
+            c.as_array()
+                .is_some_and(|items| items.iter().all(|item| item.get("id").is_some()))
+        })
+        .max_by_key(|c| c.as_array().map_or(0, Vec::len))
+}
+
+/// Parse the result of `auth.getActiveSessions`, either a bare list of sessions or an object
+/// with a `sessions` list and a `currentSessionId`.
+///
+/// # Arguments
+/// * `data` - &Value: The tRPC result.
+///
+/// # Returns
+/// * `Vec<SessionInfo>` - The parsed sessions.
+fn parse_active_sessions(data: &Value) -> Vec<SessionInfo> {
+    let current_id = data.get("currentSessionId").and_then(|v| v.as_str());
+    let items = match data.get("sessions").unwrap_or(data) {
+        Value::Array(items) => items.clone(),
+        item => vec![item.clone()],
+    };
+    let timestamp = |item: &Value, keys: &[&str]| {
+        keys.iter().find_map(|key| match item.get(*key)? {
+            Value::String(s) => parse_iso_timestamp(s),
+            other => other.as_i64(),
+        })
+    };
+    let text = |item: &Value, key: &str| item.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    items
+        .iter()
+        .filter(|item| item.is_object())
+        .map(|item| {
+            let session_id = text(item, "id");
+            let user_agent = text(item, "userAgent");
+            let (device, browser) = describe_user_agent(&user_agent);
+            SessionInfo {
+                is_current: item
+                    .get("isCurrent")
+                    .or_else(|| item.get("current"))
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or_else(|| current_id == Some(session_id.as_str())),
+                created_at: timestamp(item, &["createdAt"]),
+                expires_at: timestamp(item, &["expiresAt"]),
+                last_active_at: timestamp(item, &["lastActiveAt", "lastActive", "updatedAt"]),
+                ip_address: text(item, "ipAddress"),
+                device: item
+                    .get("device")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+                    .unwrap_or(device),
+                browser: item
+                    .get("browser")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+                    .unwrap_or(browser),
+                session_id,
+                user_agent,
+            }
+        })
+        .collect()
+}
+
+/// Parse which providers have the account's own API keys from customer data. The keys are
+/// read from a `byokProviders`, `apiKeys` or `providerKeys` entry, given either as an object
+/// keyed by provider or as a list of provider names or objects.
+///
+/// # Arguments
+/// * `data` - &Value: The customer data, or its `user` object.
+///
+/// # Returns
+/// * `ByokStatus` - The providers, sorted by name.
+fn parse_byok_status(data: &Value) -> ByokStatus {
+    let keys = ["byokProviders", "apiKeys", "providerKeys", "byokKeys"]
+        .iter()
+        .find_map(|key| data.get(*key).or_else(|| data.get("user")?.get(*key)));
+    let configured = |value: &Value| match value {
+        Value::Bool(configured) => *configured,
+        Value::Null => false,
+        Value::String(key) => !key.is_empty(),
+        Value::Object(_) => ["configured", "hasKey", "enabled", "isConfigured"]
+            .iter()
+            .find_map(|key| value.get(*key)?.as_bool())
+            .unwrap_or(true),
+        _ => true,
+    };
+    let mut providers: Vec<ByokProvider> = match keys {
+        Some(Value::Object(entries)) => entries
+            .iter()
+            .map(|(provider, value)| ByokProvider {
+                provider: provider.to_ascii_lowercase(),
+                configured: configured(value),
+            })
+            .collect(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::String(provider) => Some(ByokProvider {
+                    provider: provider.to_ascii_lowercase(),
+                    configured: true,
+                }),
+                _ => Some(ByokProvider {
+                    provider: ["provider", "name", "id"]
+                        .iter()
+                        .find_map(|key| item.get(*key)?.as_str())?
+                        .to_ascii_lowercase(),
+                    configured: configured(item),
+                }),
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    providers.sort_by(|a, b| a.provider.cmp(&b.provider));
+    providers.dedup_by(|a, b| a.provider == b.provider);
+    ByokStatus { providers }
+}
+
+/// Derive a device and browser name from a user agent string.
+///
+/// # Arguments
+/// * `user_agent` - &str: The user agent.
+///
+/// # Returns
+/// * `(String, String)` - The device and browser, `"Unknown"` when not recognised.
+fn describe_user_agent(user_agent: &str) -> (String, String) {
+    let device = [
+        ("iPhone", "iPhone"),
+        ("iPad", "iPad"),
+        ("Android", "Android"),
+        ("Windows", "Windows"),
+        ("Mac OS X", "macOS"),
+        ("CrOS", "ChromeOS"),
+        ("Linux", "Linux"),
+    ]
+    .iter()
+    .find(|(needle, _)| user_agent.contains(needle))
+    .map_or("Unknown", |(_, name)| name);
+    let browser = [
+        ("Edg/", "Edge"),
+        ("OPR/", "Opera"),
+        ("Firefox/", "Firefox"),
+        ("Chrome/", "Chrome"),
+        ("Safari/", "Safari"),
+    ]
+    .iter()
+    .find(|(needle, _)| user_agent.contains(needle))
+    .map_or("Unknown", |(_, name)| name);
+    (device.to_string(), browser.to_string())
+}
+
 fn extract_trpc_result_list(body: &str) -> Vec<Value> {
     if let Some(result) = extract_trpc_result(body) {
         if let Some(arr) = result.as_array() {
@@ -131,6 +305,17 @@ pub struct CustomerData {
     pub subscription: Option<Subscription>,
 }
 
+/// Remaining message allowance as reported for the logged-in account.
+/// Fields the endpoint does not report for the account's plan are `None`.
+#[derive(Debug, Clone, Default)]
+pub struct UsageInfo {
+    pub plan: String,
+    pub standard_remaining: Option<u64>,
+    pub premium_remaining: Option<u64>,
+    pub reset_at: Option<DateTime<Utc>>,
+    pub balance: Option<f64>,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Subscription {
     pub product_id: String,
@@ -157,18 +342,96 @@ pub struct SubscriptionData {
     pub sub_tier: String,
 }
 
+/// Plan and profile of the logged-in account. Profile fields the endpoints do not report are `None`.
+#[derive(Debug, Clone, Default)]
+pub struct AccountInfo {
+    pub plan: String,
+    pub is_pro: bool,
+    pub email: Option<String>,
+    pub name: Option<String>,
+    pub has_byok_keys: Option<bool>,
+}
+
+impl AccountInfo {
+    /// The account's plan, as `ModelInfo::accessible_with` takes it.
+    ///
+    /// # Returns
+    /// * `Plan` - `Plan::Pro` for a paid account, otherwise `Plan::Free`.
+    pub fn tier(&self) -> Plan {
+        if self.is_pro { Plan::Pro } else { Plan::Free }
+    }
+
+    /// Whether this account can use a model. See `ModelInfo::accessible_with`.
+    ///
+    /// # Arguments
+    /// * `model` - &ModelInfo: The model to check.
+    ///
+    /// # Returns
+    /// * `bool` - `true` if the model is enabled and the plan allows it.
+    pub fn can_use(&self, model: &ModelInfo) -> bool {
+        model.accessible_with(self.tier())
+    }
+}
+
+/// The account's own provider API keys ("bring your own key"), as set on the t3.chat settings
+/// screen. Only whether a key exists is reported, never the key.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ByokStatus {
+    pub providers: Vec<ByokProvider>,
+}
+
+/// One provider on the BYOK settings screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByokProvider {
+    /// The provider in lowercase, such as `openai`, `anthropic` or `openrouter`.
+    pub provider: String,
+    pub configured: bool,
+}
+
+impl ByokStatus {
+    /// Whether the account has a key for a provider.
+    ///
+    /// # Arguments
+    /// * `provider` - &str: The provider, in any case.
+    ///
+    /// # Returns
+    /// * `bool` - `true` if a key is configured.
+    pub fn has_key(&self, provider: &str) -> bool {
+        self.providers
+            .iter()
+            .any(|entry| entry.configured && entry.provider.eq_ignore_ascii_case(provider))
+    }
+
+    /// The providers with a key configured.
+    ///
+    /// # Returns
+    /// * `Vec<&str>` - The provider names.
+    pub fn configured(&self) -> Vec<&str> {
+        self.providers
+            .iter()
+            .filter(|entry| entry.configured)
+            .map(|entry| entry.provider.as_str())
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct SessionInfo {
     pub session_id: String,
     pub created_at: Option<i64>,
     pub expires_at: Option<i64>,
+    pub last_active_at: Option<i64>,
     pub ip_address: String,
     pub user_agent: String,
+    pub device: String,
+    pub browser: String,
+    pub is_current: bool,
 }
 
 pub struct UsageClient {
     client: wreq::Client,
     cookies: String,
+    base_urls: BaseUrls,
 }
 
 impl UsageClient {
@@ -180,16 +443,37 @@ impl UsageClient {
     /// # Returns
     /// * Self - A new UsageClient instance.
     pub fn new(cookies: String) -> Self {
+        Self::with_http_client(build_http_client(HttpOptions::default()).unwrap(), cookies)
+    }
+
+    /// Create a UsageClient that reuses an existing HTTP client.
+    ///
+    /// # Arguments
+    /// * `client` - wreq::Client: The HTTP client to share.
+    /// * `cookies` - String: Cookie header for requests.
+    ///
+    /// # Returns
+    /// * Self - A new UsageClient instance.
+    pub(crate) fn with_http_client(client: wreq::Client, cookies: String) -> Self {
         Self {
-            client: wreq::Client::builder()
-                .emulation(Emulation::Chrome136)
-                .cookie_store(true)
-                .build()
-                .unwrap(),
+            client,
             cookies,
+            base_urls: BaseUrls::default(),
         }
     }
 
+    /// Send requests to other hosts than production, such as a local test server.
+    ///
+    /// # Arguments
+    /// * `base_urls` - BaseUrls: The hosts to use.
+    ///
+    /// # Returns
+    /// * Self - The updated UsageClient.
+    pub fn with_base_urls(mut self, base_urls: BaseUrls) -> Self {
+        self.base_urls = base_urls;
+        self
+    }
+
     /// Fetch customer data (balance, usage, subscription) from t3.chat tRPC API.
     ///
     /// # Arguments
@@ -200,22 +484,175 @@ impl UsageClient {
     pub async fn get_customer_data(
         &self,
     ) -> Result<CustomerData, Box<dyn std::error::Error>> {
-        let url = "https://t3.chat/api/trpc/getCustomerData?batch=1&input=%7B%220%22%3A%7B%22json%22%3A%7B%22sessionId%22%3Anull%7D%2C%22meta%22%3A%7B%22values%22%3A%7B%22sessionId%22%3A%5B%22undefined%22%5D%7D%7D%7D%7D";
+        let data = self.fetch_customer_json().await?;
+        Ok(Self::parse_customer_data(&data))
+    }
+
+    /// Fetch the remaining standard and premium message allowance.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The usage client instance.
+    ///
+    /// # Returns
+    /// * `Result<UsageInfo, T3Error>` - The usage info, `T3Error::Auth` if the cookies are rejected.
+    pub async fn get_usage(&self) -> Result<UsageInfo, T3Error> {
+        let data = self.fetch_customer_json().await?;
+        Ok(Self::parse_usage_info(&data))
+    }
+
+    /// Fetch the raw getCustomerData payload.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The usage client instance.
+    ///
+    /// # Returns
+    /// * `Result<Value, T3Error>` - The customer data JSON or error.
+    async fn fetch_customer_json(&self) -> Result<Value, T3Error> {
+        self.fetch_trpc_json(CUSTOMER_DATA_PATH).await
+    }
+
+    /// Fetch a batched tRPC query and extract its result.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The usage client instance.
+    /// * `path` - &str: The tRPC query path.
+    ///
+    /// # Returns
+    /// * `Result<Value, T3Error>` - The result JSON, `T3Error::Auth` if the cookies are rejected.
+    async fn fetch_trpc_json(&self, path: &str) -> Result<Value, T3Error> {
+        let body = self.fetch_trpc_body(path).await?;
+        extract_trpc_result(&body).ok_or(T3Error::Parse { raw: body })
+    }
+
+    /// Fetch a batched tRPC query and return its raw body.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The usage client instance.
+    /// * `path` - &str: The tRPC query path.
+    ///
+    /// # Returns
+    /// * `Result<String, T3Error>` - The response body, `T3Error::Auth` if the cookies are rejected.
+    async fn fetch_trpc_body(&self, path: &str) -> Result<String, T3Error> {
         let response = self
             .client
-            .get(url)
+            .get(self.base_urls.app_url(path))
             .header("Cookie", &self.cookies)
             .header("trpc-accept", "application/jsonl")
             .header("x-trpc-batch", "true")
             .header("x-trpc-source", "web-client")
-            .header("Referer", "https://t3.chat/")
+            .header("Referer", self.base_urls.app_url("/"))
             .send()
             .await?;
+        let status = response.status().as_u16();
+        if status == 401 || status == 403 {
+            return Err(T3Error::Auth(format!("tRPC query returned HTTP {}", status)));
+        }
         let body = response.text().await?;
-        if let Some(data) = extract_trpc_result(&body) {
-            return Ok(Self::parse_customer_data(&data));
+        if body.contains("\"UNAUTHORIZED\"") {
+            return Err(T3Error::Auth("not signed in".to_string()));
+        }
+        Ok(body)
+    }
+
+    /// Fetch the plan and profile of the logged-in account.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The usage client instance.
+    ///
+    /// # Returns
+    /// * `Result<AccountInfo, T3Error>` - The account info, `T3Error::Auth` if the cookies are rejected.
+    pub async fn get_account(&self) -> Result<AccountInfo, T3Error> {
+        let subscription = self.fetch_trpc_json(SUBSCRIPTION_DATA_PATH).await?;
+        let customer = self.fetch_customer_json().await.ok();
+        let profile = |keys: &[&str]| {
+            [Some(&subscription), customer.as_ref()]
+                .into_iter()
+                .flatten()
+                .flat_map(|data| [Some(data), data.get("user")])
+                .flatten()
+                .find_map(|data| keys.iter().find_map(|key| data.get(*key)))
+        };
+        let text = |keys: &[&str]| {
+            profile(keys)
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let plan = text(&["subTier"]).unwrap_or_else(|| "free".to_string());
+        let is_paid = subscription.get("isPaid").and_then(|v| v.as_bool()).unwrap_or(false);
+        Ok(AccountInfo {
+            is_pro: is_paid || !plan.eq_ignore_ascii_case("free"),
+            plan,
+            email: text(&["email", "userEmail"]),
+            name: text(&["name", "userName", "displayName"]),
+            has_byok_keys: profile(&["hasByokKeys", "hasApiKeys", "byokEnabled"])
+                .and_then(|v| v.as_bool()),
+        })
+    }
+
+    /// Fetch which providers have the account's own API keys configured.
+    ///
+    /// # Arguments
+    /// * `self`: `&Self` - The usage client instance.
+    ///
+    /// # Returns
+    /// * `Result<ByokStatus, T3Error>` - The providers, `T3Error::Auth` if the cookies are rejected.
+    pub async fn get_byok_status(&self) -> Result<ByokStatus, T3Error> {
+        let data = self.fetch_customer_json().await?;
+        Ok(parse_byok_status(&data))
+    }
+
+    /// Parse the remaining message allowance from customer data.
+    ///
+    /// # Arguments
+    /// * `data` - &Value: The JSON value containing customer data.
+    ///
+    /// # Returns
+    /// * `UsageInfo` - Parsed usage info.
+    fn parse_usage_info(data: &Value) -> UsageInfo {
+        let field = |keys: &[&str]| {
+            keys.iter().find_map(|key| {
+                data.get(*key)
+                    .or_else(|| data.get("credits")?.get(*key))
+                    .filter(|v| !v.is_null())
+            })
+        };
+        let count = |keys: &[&str]| field(keys).and_then(|v| v.as_f64()).map(|v| v.max(0.0) as u64);
+        let plan = data
+            .get("subscription")
+            .and_then(|s| s.get("productName"))
+            .or_else(|| data.get("subTier"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("free")
+            .to_string();
+        let reset_at = field(&[
+            "usageWindowNextResetAt",
+            "usagePeriodNextResetAt",
+            "billingNextResetAt",
+            "resetAt",
+        ])
+        .and_then(|v| match v {
+            Value::String(s) => parse_iso_timestamp(s),
+            other => other.as_i64(),
+        })
+        .and_then(DateTime::from_timestamp_millis);
+        UsageInfo {
+            plan,
+            standard_remaining: count(&[
+                "standardMessagesRemaining",
+                "remainingStandardCredits",
+                "standardCredits",
+                "standard",
+            ]),
+            premium_remaining: count(&[
+                "premiumMessagesRemaining",
+                "remainingPremiumCredits",
+                "premiumCredits",
+                "premium",
+            ]),
+            reset_at,
+            balance: field(&["balance"]).and_then(|v| v.as_f64()),
         }
-        Err("Could not parse customer data from tRPC response".into())
     }
 
     /// Parse customer data from a JSON value.
@@ -306,13 +743,13 @@ impl UsageClient {
     pub async fn get_pricing_products(
         &self,
     ) -> Result<Vec<PricingProduct>, Box<dyn std::error::Error>> {
-        let url = "https://t3.chat/api/trpc/getPricingProducts?batch=1&input=%7B%220%22%3A%7B%22json%22%3A%7B%22sessionId%22%3Anull%7D%2C%22meta%22%3A%7B%22values%22%3A%7B%22sessionId%22%3A%5B%22undefined%22%5D%7D%7D%7D%7D";
+        let url = self.base_urls.app_url("/api/trpc/getPricingProducts?batch=1&input=%7B%220%22%3A%7B%22json%22%3A%7B%22sessionId%22%3Anull%7D%2C%22meta%22%3A%7B%22values%22%3A%7B%22sessionId%22%3A%5B%22undefined%22%5D%7D%7D%7D%7D");
         let response = self
             .client
             .get(url)
             .header("Cookie", &self.cookies)
             .header("x-trpc-source", "web-client")
-            .header("Referer", "https://t3.chat/")
+            .header("Referer", self.base_urls.app_url("/"))
             .send()
             .await?;
         let body = response.text().await?;
@@ -344,15 +781,14 @@ impl UsageClient {
     pub async fn get_subscription_data(
         &self,
     ) -> Result<SubscriptionData, Box<dyn std::error::Error>> {
-        let url = "https://t3.chat/api/trpc/getSubscriptionData?batch=1&input=%7B%220%22%3A%7B%22json%22%3Anull%2C%22meta%22%3A%7B%22values%22%3A%5B%22undefined%22%5D%7D%7D%7D";
         let response = self
             .client
-            .get(url)
+            .get(self.base_urls.app_url(SUBSCRIPTION_DATA_PATH))
             .header("Cookie", &self.cookies)
             .header("trpc-accept", "application/jsonl")
             .header("x-trpc-batch", "true")
             .header("x-trpc-source", "web-client")
-            .header("Referer", "https://t3.chat/")
+            .header("Referer", self.base_urls.app_url("/"))
             .send()
             .await?;
         let body = response.text().await?;
@@ -371,38 +807,13 @@ impl UsageClient {
     /// * `self`: `&Self` - The usage client instance.
     ///
     /// # Returns
-    /// * `Result<Vec<SessionInfo>, Box<dyn std::error::Error>>` - Active sessions or error.
-    pub async fn get_active_sessions(
-        &self,
-    ) -> Result<Vec<SessionInfo>, Box<dyn std::error::Error>> {
-        let url = "https://t3.chat/api/trpc/auth.getActiveSessions?batch=1&input=%7B%220%22%3A%7B%22json%22%3A%7B%22includeLocation%22%3Afalse%7D%7D%7D";
-        let response = self
-            .client
-            .get(url)
-            .header("Cookie", &self.cookies)
-            .header("x-trpc-source", "web-client")
-            .header("Referer", "https://t3.chat/")
-            .send()
-            .await?;
-        let body = response.text().await?;
-        let items = extract_trpc_result_list(&body);
-        let sessions: Vec<SessionInfo> = items
-            .iter()
-            .map(|item| SessionInfo {
-                session_id: item.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                created_at: item
-                    .get("createdAt")
-                    .and_then(|v| v.as_str())
-                    .and_then(|s| parse_iso_timestamp(s)),
-                expires_at: item
-                    .get("expiresAt")
-                    .and_then(|v| v.as_str())
-                    .and_then(|s| parse_iso_timestamp(s)),
-                ip_address: item.get("ipAddress").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                user_agent: item.get("userAgent").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-            })
-            .collect();
-        Ok(sessions)
+    /// * `Result<Vec<SessionInfo>, T3Error>` - Active sessions, `T3Error::Auth` if the cookies are rejected.
+    pub async fn get_active_sessions(&self) -> Result<Vec<SessionInfo>, T3Error> {
+        let body = self.fetch_trpc_body(ACTIVE_SESSIONS_PATH).await?;
+        match extract_active_sessions(&body) {
+            Some(data) => Ok(parse_active_sessions(&data)),
+            None => Err(T3Error::Parse { raw: body }),
+        }
     }
 
     /// Fetch the current credit balance from customer data.
@@ -0,0 +1,390 @@
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use base64::{Engine as _, engine::general_purpose};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use wreq::Method;
+
+use super::dump::redact;
+use super::error::T3Error;
+
+/// Top-level fields of a request body that change on every send, left out of its hash.
+const VOLATILE_FIELDS: [&str; 4] = [
+    "responseMessageId",
+    "convexSessionId",
+    "threadMetadata",
+    "userInfo",
+];
+
+/// The recorded HTTP traffic of a `Client`, as written by `ClientBuilder::record_to` and
+/// answered from by `ClientBuilder::replay_from`. Cookies and session tokens are redacted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+/// One recorded request and the response it got.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// A recorded request. `body` is the JSON body without the fields that change on every send,
+/// such as message IDs, and `body_hash` is its hash; replays match on method, path and hash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub body_hash: Option<String>,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub body: Option<Value>,
+}
+
+/// A recorded response. `body` holds the body as text, or as base64 when `base64` is set
+/// because the body was not UTF-8, such as a downloaded image.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    #[serde(default)]
+    pub base64: bool,
+}
+
+impl Cassette {
+    ///
+    /// Reads a cassette file.
+    ///
+    /// # Arguments
+    /// * `path`: `&Path` - The cassette file.
+    ///
+    /// # Returns
+    /// * `Result<Cassette, T3Error>` - The cassette, or an error if it cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, T3Error> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    ///
+    /// Writes the cassette, creating parent directories as needed.
+    ///
+    /// # Arguments
+    /// * `path`: `&Path` - The cassette file.
+    ///
+    /// # Returns
+    /// * `Result<(), T3Error>` - An error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<(), T3Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+impl RecordedRequest {
+    ///
+    /// Describes the request for errors, e.g. `POST /api/chat (body 9f3c…)`.
+    ///
+    /// # Returns
+    /// * `String` - The description.
+    fn describe(&self) -> String {
+        match &self.body_hash {
+            Some(hash) => format!("{} {} (body {})", self.method, self.path, hash),
+            None => format!("{} {}", self.method, self.path),
+        }
+    }
+
+    ///
+    /// Rates how close a recorded request is to this one: method and path count most, then
+    /// the number of top-level body fields with equal values.
+    ///
+    /// # Arguments
+    /// * `other`: `&RecordedRequest` - The recorded request.
+    ///
+    /// # Returns
+    /// * `(bool, bool, usize)` - The score; higher is closer.
+    fn closeness(&self, other: &RecordedRequest) -> (bool, bool, usize) {
+        let shared = match (&self.body, &other.body) {
+            (Some(Value::Object(ours)), Some(Value::Object(theirs))) => ours
+                .iter()
+                .filter(|(key, value)| theirs.get(*key) == Some(*value))
+                .count(),
+            _ => 0,
+        };
+        (self.path == other.path, self.method == other.method, shared)
+    }
+}
+
+/// Where a `ClientBuilder` was told to record to or replay from.
+pub(crate) enum VcrMode {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+/// The cassette of a client that records or replays its traffic.
+pub(crate) enum Vcr {
+    Record {
+        path: PathBuf,
+        cassette: Mutex<Cassette>,
+    },
+    Replay {
+        cassette: Cassette,
+        used: Mutex<Vec<bool>>,
+    },
+}
+
+impl Vcr {
+    ///
+    /// Starts recording into a new cassette, or loads the cassette to replay.
+    ///
+    /// # Arguments
+    /// * `mode`: `VcrMode` - The cassette file and what to do with it.
+    ///
+    /// # Returns
+    /// * `Result<Vcr, T3Error>` - The recorder, or an error if a replayed cassette cannot be read.
+    pub(crate) fn open(mode: VcrMode) -> Result<Self, T3Error> {
+        match mode {
+            VcrMode::Record(path) => Ok(Vcr::Record {
+                path,
+                cassette: Mutex::new(Cassette::default()),
+            }),
+            VcrMode::Replay(path) => {
+                let cassette = Cassette::load(&path)?;
+                let used = Mutex::new(vec![false; cassette.interactions.len()]);
+                Ok(Vcr::Replay { cassette, used })
+            }
+        }
+    }
+
+    ///
+    /// Records the response `send` gets, or answers from the cassette without awaiting `send`.
+    /// Recorded responses are read in full before they are returned.
+    ///
+    /// # Arguments
+    /// * `method`: `&Method` - The request method.
+    /// * `url`: `&str` - The request URL.
+    /// * `headers`: `&[(&str, &str)]` - The headers set on the request.
+    /// * `body`: `Option<&Value>` - The JSON body, if any.
+    /// * `send`: `impl Future<Output = Result<wreq::Response, T3Error>>` - Sends the request.
+    ///
+    /// # Returns
+    /// * `Result<wreq::Response, T3Error>` - The response, or `T3Error::CassetteMiss` if a
+    ///   replayed cassette holds no matching request.
+    pub(crate) async fn exchange(
+        &self,
+        method: &Method,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: Option<&Value>,
+        send: impl Future<Output = Result<wreq::Response, T3Error>>,
+    ) -> Result<wreq::Response, T3Error> {
+        let request = recorded_request(method, url, headers, body);
+        match self {
+            Vcr::Replay { cassette, used } => replay(cassette, used, &request),
+            Vcr::Record { path, cassette } => {
+                let response = send.await?;
+                let status = response.status().as_u16();
+                let response_headers: Vec<(String, String)> = response
+                    .headers()
+                    .iter()
+                    .map(|(name, value)| {
+                        let value = String::from_utf8_lossy(value.as_bytes());
+                        (name.to_string(), redact(name.as_str(), &value).to_string())
+                    })
+                    .collect();
+                let bytes = response.bytes().await?.to_vec();
+                let recorded = match String::from_utf8(bytes.clone()) {
+                    Ok(text) => RecordedResponse {
+                        status,
+                        headers: response_headers,
+                        body: text,
+                        base64: false,
+                    },
+                    Err(_) => RecordedResponse {
+                        status,
+                        headers: response_headers,
+                        body: general_purpose::STANDARD.encode(&bytes),
+                        base64: true,
+                    },
+                };
+                let response = to_response(&recorded, bytes)?;
+                let mut cassette = cassette.lock().unwrap();
+                cassette.interactions.push(Interaction {
+                    request,
+                    response: recorded,
+                });
+                cassette.save(path)?;
+                Ok(response)
+            }
+        }
+    }
+}
+
+///
+/// Answers a request from the cassette with the first unused matching interaction, or the
+/// last matching one once all have been used.
+///
+/// # Arguments
+/// * `cassette`: `&Cassette` - The replayed cassette.
+/// * `used`: `&Mutex<Vec<bool>>` - Which interactions have been answered with.
+/// * `request`: `&RecordedRequest` - The request to answer.
+///
+/// # Returns
+/// * `Result<wreq::Response, T3Error>` - The recorded response, or `T3Error::CassetteMiss`.
+fn replay(
+    cassette: &Cassette,
+    used: &Mutex<Vec<bool>>,
+    request: &RecordedRequest,
+) -> Result<wreq::Response, T3Error> {
+    let matching: Vec<usize> = cassette
+        .interactions
+        .iter()
+        .enumerate()
+        .filter(|(_, recorded)| {
+            recorded.request.method == request.method
+                && recorded.request.path == request.path
+                && recorded.request.body_hash == request.body_hash
+        })
+        .map(|(index, _)| index)
+        .collect();
+    let index = {
+        let mut used = used.lock().unwrap();
+        let index = matching
+            .iter()
+            .copied()
+            .find(|index| !used[*index])
+            .or(matching.last().copied());
+        if let Some(index) = index {
+            used[index] = true;
+        }
+        index
+    };
+    let Some(index) = index else {
+        let closest = cassette
+            .interactions
+            .iter()
+            .max_by_key(|recorded| request.closeness(&recorded.request))
+            .map(|recorded| recorded.request.describe())
+            .unwrap_or_else(|| "none, the cassette is empty".to_string());
+        return Err(T3Error::CassetteMiss {
+            request: request.describe(),
+            closest,
+        });
+    };
+    let recorded = &cassette.interactions[index].response;
+    let bytes = if recorded.base64 {
+        general_purpose::STANDARD
+            .decode(&recorded.body)
+            .map_err(|_| T3Error::Parse {
+                raw: "recorded response body is not valid base64".to_string(),
+            })?
+    } else {
+        recorded.body.clone().into_bytes()
+    };
+    to_response(recorded, bytes)
+}
+
+///
+/// Builds a response from a recorded status and headers and the body bytes.
+///
+/// # Arguments
+/// * `recorded`: `&RecordedResponse` - The recorded response.
+/// * `bytes`: `Vec<u8>` - The body.
+///
+/// # Returns
+/// * `Result<wreq::Response, T3Error>` - The response, or `T3Error::InvalidHeader` for a
+///   recorded header that is not valid.
+fn to_response(recorded: &RecordedResponse, bytes: Vec<u8>) -> Result<wreq::Response, T3Error> {
+    let mut builder = http::Response::builder().status(recorded.status);
+    for (name, value) in &recorded.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    let response = builder
+        .body(bytes)
+        .map_err(|err| T3Error::InvalidHeader(err.to_string()))?;
+    Ok(wreq::Response::from(response))
+}
+
+///
+/// Describes a request the way it is stored in a cassette, with secrets redacted and the
+/// fields that change on every send left out of the body.
+///
+/// # Arguments
+/// * `method`: `&Method` - The request method.
+/// * `url`: `&str` - The request URL.
+/// * `headers`: `&[(&str, &str)]` - The headers set on the request.
+/// * `body`: `Option<&Value>` - The JSON body, if any.
+///
+/// # Returns
+/// * `RecordedRequest` - The request as recorded.
+fn recorded_request(
+    method: &Method,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&Value>,
+) -> RecordedRequest {
+    let host_and_path = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let path = host_and_path
+        .find('/')
+        .map_or("/", |start| &host_and_path[start..]);
+    let body = body.map(stable_body);
+    RecordedRequest {
+        method: method.as_str().to_string(),
+        path: path.to_string(),
+        body_hash: body.as_ref().map(|body| fnv1a(body.to_string().as_bytes())),
+        headers: headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), redact(name, value).to_string()))
+            .collect(),
+        body,
+    }
+}
+
+///
+/// Removes the fields of a request body that change on every send: the volatile top-level
+/// fields and the ID of each message.
+///
+/// # Arguments
+/// * `body`: `&Value` - The request body.
+///
+/// # Returns
+/// * `Value` - The body that is hashed and recorded.
+fn stable_body(body: &Value) -> Value {
+    let mut body = body.clone();
+    if let Some(fields) = body.as_object_mut() {
+        for field in VOLATILE_FIELDS {
+            fields.remove(field);
+        }
+        if let Some(Value::Array(messages)) = fields.get_mut("messages") {
+            for message in messages {
+                if let Some(message) = message.as_object_mut() {
+                    message.remove("id");
+                }
+            }
+        }
+    }
+    body
+}
+
+///
+/// Hashes bytes with 64-bit FNV-1a, which unlike `std`'s hasher is stable across Rust
+/// versions, so cassettes keep matching.
+///
+/// # Arguments
+/// * `bytes`: `&[u8]` - The bytes to hash.
+///
+/// # Returns
+/// * `String` - The hash as 16 hex digits.
+fn fnv1a(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
@@ -0,0 +1,165 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, Utc};
+use t3router::t3::client::{Client, SessionRefresh};
+use t3router::t3::clock::Clock;
+use t3router::t3::config::{DelayPolicy, RetryOn, RetryPolicy, TrimStrategy};
+use t3router::t3::events::Usage;
+use t3router::t3::message::{Message, Type};
+use t3router::t3::testing::MockClock;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+const REPLY: &str = concat!(
+    "data: {\"type\":\"text-delta\",\"id\":\"0\",\"delta\":\"Noted\"}\n\n",
+    "data: {\"type\":\"finish\",\"messageMetadata\":{\"finishReason\":\"stop\",",
+    "\"usage\":{\"inputTokens\":10,\"outputTokens\":5}}}\n\n",
+    "data: [DONE]\n\n",
+);
+
+const TURNS: usize = 12;
+
+/// A `wos-session` token that expires an hour after `now`.
+fn session_token(now: DateTime<Utc>) -> String {
+    let claims = format!("{{\"exp\":{}}}", now.timestamp() + 3600);
+    format!(
+        "{}.{}.signature",
+        URL_SAFE_NO_PAD.encode("{\"alg\":\"none\"}"),
+        URL_SAFE_NO_PAD.encode(claims)
+    )
+}
+
+/// Rotates the session to one that expires an hour after the simulated time.
+struct RotateSession(MockClock);
+
+impl Respond for RotateSession {
+    fn respond(&self, _: &Request) -> ResponseTemplate {
+        let token = session_token(self.0.utc_now());
+        ResponseTemplate::new(200).insert_header("x-workos-session", token.as_str())
+    }
+}
+
+/// Replies to every chat request, except that the fourth is rejected as unauthorized and the
+/// seventh is rate limited.
+#[derive(Default)]
+struct Chat(AtomicUsize);
+
+impl Respond for Chat {
+    fn respond(&self, _: &Request) -> ResponseTemplate {
+        match self.0.fetch_add(1, Ordering::SeqCst) {
+            3 => ResponseTemplate::new(401),
+            6 => ResponseTemplate::new(429).insert_header("Retry-After", "90"),
+            _ => ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(REPLY),
+        }
+    }
+}
+
+async fn requests_to(server: &MockServer, route: &str) -> Vec<Request> {
+    let requests = server.received_requests().await.unwrap();
+    requests.into_iter().filter(|request| request.url.path() == route).collect()
+}
+
+fn sent_thread_id(request: &Request) -> String {
+    let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+    body["threadMetadata"]["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn a_simulated_session_runs_for_hours() {
+    let server = MockServer::start().await;
+    let clock = MockClock::new();
+    Mock::given(method("GET"))
+        .and(path("/api/trpc/auth.getActiveSessions"))
+        .respond_with(RotateSession(clock.clone()))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(Chat::default())
+        .mount(&server)
+        .await;
+
+    let cookies = format!(
+        "wos-session={}; convex-session-id=test",
+        session_token(clock.utc_now())
+    );
+    let mut client = Client::builder(cookies, "test-session".to_string())
+        .base_url(&server.uri())
+        .retry_policy(RetryPolicy {
+            retry_on: RetryOn {
+                rate_limits: true,
+                expired_sessions: true,
+                ..RetryOn::default()
+            },
+            ..RetryPolicy::default()
+        })
+        .build()
+        .unwrap();
+    client.set_clock(clock.clone());
+    client.set_inter_request_delay(Some(DelayPolicy::Fixed(Duration::from_secs(30))));
+    client.set_context_limit(60, TrimStrategy::KeepLastExchanges(2));
+    client.set_system_prompt("Keep notes.".to_string());
+    let autosave = std::env::temp_dir().join(format!(
+        "t3router-agent-session-{}.json",
+        std::process::id()
+    ));
+
+    let mut usage = Usage::default();
+    let mut refreshes = 0;
+    let mut finished = None;
+    for turn in 0..TURNS {
+        let reply = client
+            .send_detailed(
+                "gemini-2.5-flash-lite",
+                Some(Message::new(Type::User, format!("note {turn}"))),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(reply.message.content(), "Noted");
+        usage += reply.usage.as_ref().unwrap();
+        client.save_conversation(&autosave).unwrap();
+        if turn == TURNS / 2 - 1 {
+            finished = Some(client.roll_over(2));
+        }
+        if client.idle(Duration::from_secs(20 * 60)).await.unwrap() == SessionRefresh::Refreshed {
+            refreshes += 1;
+        }
+    }
+
+    // Four hours passed, and the session was kept alive through them.
+    assert!(clock.elapsed() >= Duration::from_secs(4 * 3600));
+    assert!(refreshes >= 3, "{refreshes}");
+    assert!(client.session_expires_at().unwrap() > clock.utc_now());
+    // The rejected and the rate limited request were each sent again, after a refresh and after
+    // the retry-after.
+    let chats = requests_to(&server, "/api/chat").await;
+    assert_eq!(chats.len(), TURNS + 2);
+    assert_eq!(sent_thread_id(&chats[3]), sent_thread_id(&chats[4]));
+    assert!(clock.sleeps().contains(&Duration::from_secs(90)));
+    assert!(requests_to(&server, "/api/trpc/auth.getActiveSessions").await.len() > refreshes);
+    assert_eq!(usage.input_tokens, Some(10 * TURNS as u64));
+    assert_eq!(usage.output_tokens, Some(5 * TURNS as u64));
+
+    // The rollover moved the second half to a new thread that starts from the last exchange.
+    let finished = finished.unwrap();
+    let first_thread = finished.thread_id.unwrap().to_string();
+    let last_thread = client.get_thread_id().unwrap().to_string();
+    assert_ne!(first_thread, last_thread);
+    assert!(chats[..8].iter().all(|chat| sent_thread_id(chat) == first_thread));
+    assert!(chats[8..].iter().all(|chat| sent_thread_id(chat) == last_thread));
+    assert!(String::from_utf8_lossy(&chats[8].body).contains("note 5"));
+    // The history stayed within the context limit.
+    assert!(!client.archived_messages().is_empty());
+
+    let mut restarted = Client::new(String::new(), String::new());
+    restarted.load_conversation(&autosave).unwrap();
+    std::fs::remove_file(&autosave).unwrap();
+    assert_eq!(restarted.get_thread_id(), client.get_thread_id());
+    assert_eq!(restarted.get_messages().len(), client.get_messages().len());
+}
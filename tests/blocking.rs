@@ -0,0 +1,156 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::Engine as _;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use t3router::t3::blocking::Client;
+use t3router::t3::client::{self, SessionRefresh, StreamEvent};
+use t3router::t3::error::T3Error;
+use t3router::t3::message::{Message, Type};
+use tokio::runtime::Runtime;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const SSE_BODY: &str = concat!(
+    "data: {\"type\":\"start\",\"messageId\":\"msg-1\"}\n\n",
+    "data: {\"type\":\"text-delta\",\"id\":\"0\",\"delta\":\"Hello\"}\n\n",
+    "data: {\"type\":\"text-delta\",\"id\":\"0\",\"delta\":\", world\"}\n\n",
+    "data: {\"type\":\"finish\",\"finishReason\":\"stop\"}\n\n",
+    "data: [DONE]\n\n",
+);
+
+/// The start of a PNG file.
+const PNG_BYTES: &[u8] = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR";
+
+/// A mock server running on its own multi-thread runtime, so the tests themselves run on a
+/// plain thread like a `fn main` without `#[tokio::main]`.
+struct Server {
+    // Dropped first, while the runtime still runs, to verify the mocks' expectations.
+    server: MockServer,
+    runtime: Runtime,
+}
+
+impl Server {
+    fn start() -> Self {
+        let runtime = Runtime::new().unwrap();
+        let server = runtime.block_on(MockServer::start());
+        Server { server, runtime }
+    }
+
+    fn mount(&self, mock: Mock) {
+        self.runtime.block_on(mock.mount(&self.server));
+    }
+
+    fn chat(&self, response: ResponseTemplate) {
+        self.mount(Mock::given(method("POST")).and(path("/api/chat")).respond_with(response));
+    }
+
+    fn client(&self, cookies: &str) -> Client {
+        let inner = client::Client::builder(cookies, "test-session".to_string())
+            .base_url(&self.server.uri())
+            .build()
+            .unwrap();
+        Client::from_async(inner).unwrap()
+    }
+}
+
+fn sse(body: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200)
+        .insert_header("content-type", "text/event-stream")
+        .set_body_string(body)
+}
+
+fn hello() -> Option<Message> {
+    Some(Message::new(Type::User, "Hi".to_string()))
+}
+
+#[test]
+fn sends_from_a_thread_without_a_runtime() {
+    assert!(tokio::runtime::Handle::try_current().is_err());
+    let server = Server::start();
+    server.chat(sse(SSE_BODY));
+    let mut client = server.client("wos-session=abc");
+
+    let reply = client.send("gemini-2.5-flash-lite", hello(), None).unwrap();
+    assert_eq!(reply.content(), "Hello, world");
+    let detailed = client
+        .send_detailed("gemini-2.5-flash-lite", hello(), None)
+        .unwrap();
+    assert_eq!(detailed.message.content(), "Hello, world");
+    assert_eq!(detailed.finish_reason.as_deref(), Some("stop"));
+
+    let mut streamed = String::new();
+    client
+        .send_with_callback("gemini-2.5-flash-lite", hello(), None, |event| {
+            if let StreamEvent::Text(text) = event {
+                streamed.push_str(&text);
+            }
+        })
+        .unwrap();
+    assert_eq!(streamed, "Hello, world");
+    // The conversation is the async client's, with every exchange recorded.
+    assert_eq!(client.get_messages().len(), 6);
+    assert_eq!(client.inner().get_messages().len(), 6);
+}
+
+#[test]
+fn errors_are_the_async_client_errors() {
+    let server = Server::start();
+    server.chat(ResponseTemplate::new(401));
+    let mut client = server.client("wos-session=abc");
+
+    let error = client.send("gemini-2.5-flash-lite", hello(), None).unwrap_err();
+    assert!(matches!(error, T3Error::SessionExpired { .. }), "{error:?}");
+    assert!(client.get_messages().is_empty());
+}
+
+#[test]
+fn images_are_downloaded_next_to_the_reply() {
+    let server = Server::start();
+    let fixture = include_str!("fixtures/tool_output_array.sse")
+        .replace("https://files.example.com", &server.server.uri());
+    server.chat(sse(&fixture));
+    for name in ["cat-1.png", "cat-2.png"] {
+        server.mount(
+            Mock::given(method("GET"))
+                .and(path(format!("/generated/{}", name)))
+                .respond_with(ResponseTemplate::new(200).set_body_bytes(PNG_BYTES.to_vec())),
+        );
+    }
+    let mut client = server.client("wos-session=abc");
+
+    let dir = std::env::temp_dir().join(format!("t3router-blocking-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let reply = client
+        .send_with_image_download("gpt-image-1", hello(), None, Some(&dir))
+        .unwrap();
+    assert_eq!(reply.images().count(), 2);
+    for name in ["image_1.png", "image_2.png"] {
+        assert_eq!(std::fs::read(dir.join(name)).unwrap(), PNG_BYTES);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+fn session_token(expires_in: u64) -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    format!(
+        "{}.{}.signature",
+        URL_SAFE_NO_PAD.encode("{\"alg\":\"none\"}"),
+        URL_SAFE_NO_PAD.encode(format!("{{\"exp\":{}}}", now + expires_in))
+    )
+}
+
+#[test]
+fn sessions_refresh_from_a_thread_without_a_runtime() {
+    let server = Server::start();
+    let rotated = session_token(3600);
+    server.mount(
+        Mock::given(method("GET"))
+            .and(path("/api/trpc/auth.getActiveSessions"))
+            .respond_with(ResponseTemplate::new(200).insert_header("x-workos-session", &rotated))
+            .expect(1),
+    );
+    let mut client = server.client(&format!("wos-session={}", session_token(60)));
+
+    assert_eq!(client.refresh_session().unwrap(), SessionRefresh::Refreshed);
+    assert!(client.inner().cookies().contains(&rotated));
+}
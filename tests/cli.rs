@@ -0,0 +1,54 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn t3(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_t3"))
+        .args(args)
+        .env_clear()
+        .env("HOME", env!("CARGO_TARGET_TMPDIR"))
+        .output()
+        .unwrap()
+}
+
+fn snapshot(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err))
+}
+
+#[test]
+fn help_matches_snapshot() {
+    let output = t3(&["--help"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), snapshot("t3_help.txt"));
+}
+
+#[test]
+fn ask_help_matches_snapshot() {
+    let output = t3(&["ask", "--help"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        snapshot("t3_ask_help.txt")
+    );
+}
+
+#[test]
+fn missing_credentials_exit_with_the_auth_code() {
+    let output = t3(&["ask", "hello"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no credentials"));
+}
+
+#[test]
+fn missing_config_file_is_a_plain_failure() {
+    let output = t3(&["--config", "/nonexistent/t3router.toml", "models"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn invalid_effort_is_a_usage_error() {
+    let output = t3(&["ask", "--effort", "extreme", "hello"]);
+    assert_eq!(output.status.code(), Some(2));
+}
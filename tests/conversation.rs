@@ -0,0 +1,101 @@
+use t3router::t3::client::Client;
+use t3router::t3::error::T3Error;
+use t3router::t3::message::{Message, Type};
+use t3router::t3::thread::ThreadId;
+
+fn texts(client: &Client) -> Vec<&str> {
+    client.get_messages().iter().map(|msg| msg.content()).collect()
+}
+
+/// A client resumed into a thread with a system prompt and one exchange.
+fn resumed(thread_id: &ThreadId) -> Client {
+    let mut client = Client::new("", String::new());
+    client.resume_conversation(
+        thread_id,
+        vec![
+            Message::new(Type::System, "sys".to_string()),
+            Message::new(Type::User, "question".to_string()),
+            Message::new(Type::Assistant, "answer".to_string()),
+        ],
+    );
+    client
+}
+
+#[test]
+fn history_edits_keep_the_thread() {
+    let thread_id = ThreadId::default();
+    let mut client = resumed(&thread_id);
+
+    assert_eq!(client.pop_message().unwrap().content(), "answer");
+    client.append_message(Message::new(Type::Assistant, "again".to_string()));
+    let question = client.get_messages()[1].id.clone();
+    assert_eq!(client.remove_message(&question).unwrap().content(), "question");
+    assert_eq!(texts(&client), ["sys", "again"]);
+    client.truncate_messages(1);
+    assert_eq!(texts(&client), ["sys"]);
+    // Truncating to more messages than there are does nothing.
+    client.truncate_messages(5);
+    assert_eq!(texts(&client), ["sys"]);
+    client.replace_messages(vec![Message::new(Type::User, "new".to_string())]);
+    assert_eq!(texts(&client), ["new"]);
+
+    assert_eq!(client.get_thread_id(), Some(&thread_id));
+}
+
+#[test]
+fn empty_histories_and_unknown_ids() {
+    let mut client = Client::new("", String::new());
+    assert!(client.pop_message().is_none());
+    client.truncate_messages(0);
+    client.clear_messages();
+    assert!(client.get_messages().is_empty());
+
+    client.append_message(Message::new(Type::User, "question".to_string()));
+    let error = client.remove_message("missing").unwrap_err();
+    assert!(matches!(&error, T3Error::NotFound(what) if what.contains("missing")), "{error:?}");
+    assert_eq!(texts(&client), ["question"]);
+}
+
+#[test]
+fn removing_the_system_message_forgets_the_prompt() {
+    let thread_id = ThreadId::default();
+    let removals: [fn(&mut Client); 5] = [
+        |client| client.clear_messages(),
+        |client| client.truncate_messages(0),
+        |client| {
+            let system = client.get_messages()[0].id.clone();
+            client.remove_message(&system).unwrap();
+        },
+        |client| {
+            client.truncate_messages(1);
+            client.pop_message();
+        },
+        |client| client.replace_messages(vec![Message::new(Type::User, "new".to_string())]),
+    ];
+    for (index, remove) in removals.iter().enumerate() {
+        let mut client = resumed(&thread_id);
+        client.persist_system_prompt(true);
+        remove(&mut client);
+        assert_eq!(client.get_system_prompt(), None, "removal {index}");
+        // With the prompt gone, a new conversation does not bring it back.
+        client.new_conversation();
+        assert!(client.get_messages().is_empty(), "removal {index}");
+    }
+}
+
+#[test]
+fn replaced_histories_bring_their_own_prompt() {
+    let mut client = resumed(&ThreadId::default());
+    client.persist_system_prompt(true);
+    client.replace_messages(vec![
+        Message::new(Type::System, "other sys".to_string()),
+        Message::new(Type::User, "new".to_string()),
+    ]);
+    assert_eq!(client.get_system_prompt(), Some("other sys"));
+
+    // Edits that keep the system message keep the prompt.
+    client.pop_message();
+    assert_eq!(client.get_system_prompt(), Some("other sys"));
+    client.new_conversation();
+    assert_eq!(texts(&client), ["other sys"]);
+}
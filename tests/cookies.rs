@@ -0,0 +1,146 @@
+use std::path::Path;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use t3router::t3::cookies::{CookieJar, jwt_expiry};
+use t3router::t3::error::T3Error;
+
+const FUTURE: i64 = 4_102_444_800;
+const PAST: i64 = 946_684_800;
+
+fn netscape_line(domain: &str, expires: i64, name: &str, value: &str) -> String {
+    format!("{domain}\tFALSE\t/\tTRUE\t{expires}\t{name}\t{value}")
+}
+
+#[test]
+fn header_string_round_trips() {
+    let header = "Cookie: wos-session=abc; convex-session-id=s1 ;theme=dark";
+    let jar = CookieJar::from_header_str(header);
+    assert_eq!(jar.to_header_string(), "wos-session=abc; convex-session-id=s1; theme=dark");
+    assert_eq!(jar.get("convex-session-id"), Some("s1"));
+    assert_eq!(jar.len(), 3);
+}
+
+#[test]
+fn header_pairs_without_a_name_or_equals_are_skipped() {
+    let jar = CookieJar::from_header_str("wos-session=abc; garbage; =orphan; ; flag=");
+    assert_eq!(jar.to_header_string(), "wos-session=abc; flag=");
+    // Values may themselves contain `=`, as base64 padding does.
+    let jar = CookieJar::from_header_str("token=YWJj==");
+    assert_eq!(jar.get("token"), Some("YWJj=="));
+}
+
+#[test]
+fn duplicate_names_keep_the_last_value() {
+    let jar = CookieJar::from_header_str("wos-session=old; theme=dark; wos-session=new");
+    assert_eq!(jar.to_header_string(), "theme=dark; wos-session=new");
+
+    let contents = [
+        netscape_line(".t3.chat", FUTURE, "wos-session", "old"),
+        netscape_line("t3.chat", FUTURE, "wos-session", "new"),
+    ]
+    .join("\n");
+    assert_eq!(CookieJar::from_netscape_str(&contents).get("wos-session"), Some("new"));
+
+    let json = r#"[{"name":"a","value":"1"},{"name":"a","value":"2"}]"#;
+    assert_eq!(CookieJar::from_browser_json(json).unwrap().get("a"), Some("2"));
+}
+
+#[test]
+fn netscape_files_skip_malformed_expired_and_foreign_lines() {
+    let contents = [
+        "# Netscape HTTP Cookie File".to_string(),
+        String::new(),
+        netscape_line(".t3.chat", FUTURE, "wos-session", "abc"),
+        format!("#HttpOnly_{}", netscape_line("t3.chat", 0, "convex-session-id", "s1")),
+        netscape_line("www.t3.chat", FUTURE, "theme", "dark\r"),
+        netscape_line(".t3.chat", PAST, "expired", "x"),
+        netscape_line(".example.com", FUTURE, "tracker", "x"),
+        netscape_line("t3.chat", FUTURE, "", "nameless"),
+        "t3.chat\tFALSE\t/\tTRUE\tsoon\tbad-expiry\tx".to_string(),
+        "t3.chat\tFALSE\t/\tTRUE\t0\ttoo-few-fields".to_string(),
+        "not a cookie line at all".to_string(),
+    ]
+    .join("\n");
+    let jar = CookieJar::from_netscape_str(&contents);
+    assert_eq!(
+        jar.to_header_string(),
+        "wos-session=abc; convex-session-id=s1; theme=dark"
+    );
+}
+
+#[test]
+fn netscape_file_errors_are_reported() {
+    let error = CookieJar::from_netscape_file(Path::new("/nonexistent/cookies.txt")).unwrap_err();
+    assert!(matches!(error, T3Error::Io(_)), "{error:?}");
+}
+
+#[test]
+fn browser_exports_skip_expired_and_foreign_cookies() {
+    let json = format!(
+        r#"[
+            {{"name":"wos-session","value":"abc","domain":".t3.chat","expirationDate":{FUTURE}.5}},
+            {{"name":"session-only","value":"1","domain":"t3.chat"}},
+            {{"name":"no-domain","value":"2"}},
+            {{"name":"expired","value":"x","domain":"t3.chat","expirationDate":{PAST}}},
+            {{"name":"tracker","value":"x","domain":".example.com"}},
+            {{"name":"","value":"nameless"}}
+        ]"#
+    );
+    let jar = CookieJar::from_browser_json(&json).unwrap();
+    assert_eq!(jar.to_header_string(), "wos-session=abc; session-only=1; no-domain=2");
+}
+
+#[test]
+fn browser_exports_may_be_wrapped_and_use_expires() {
+    let json = format!(
+        r#"{{"cookies":[{{"name":"wos-session","value":"abc","expires":{PAST}}},
+            {{"name":"theme","value":"dark","expires":{FUTURE}}}]}}"#
+    );
+    let jar = CookieJar::from_browser_json(&json).unwrap();
+    assert_eq!(jar.to_header_string(), "theme=dark");
+}
+
+#[test]
+fn malformed_browser_exports_are_errors() {
+    for json in ["not json", r#"{"name":"a"}"#, r#"[{"value":"no name"}]"#] {
+        let error = CookieJar::from_browser_json(json).unwrap_err();
+        assert!(matches!(error, T3Error::Json(_)), "{json}: {error:?}");
+    }
+}
+
+#[test]
+fn validate_names_missing_cookies() {
+    assert!(CookieJar::from_header_str("wos-session=abc").validate().is_ok());
+    for header in ["theme=dark", "wos-session=", ""] {
+        match CookieJar::from_header_str(header).validate() {
+            Err(T3Error::MissingCookies(missing)) => assert_eq!(missing, ["wos-session"]),
+            other => panic!("{header}: expected MissingCookies, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn session_expiry_comes_from_the_jwt() {
+    let claims = URL_SAFE_NO_PAD.encode(format!(r#"{{"sub":"u1","exp":{FUTURE}}}"#));
+    let token = format!("eyJhbGciOiJIUzI1NiJ9.{claims}.sig");
+    let jar = CookieJar::from_header_str(&format!("wos-session={token}"));
+    assert_eq!(jar.session_expires_at().unwrap().timestamp(), FUTURE);
+    // Percent-encoded tokens, as some exports write them, decode the same.
+    let encoded = token.replace('.', "%2E");
+    assert_eq!(jwt_expiry(&encoded).unwrap().timestamp(), FUTURE);
+
+    assert!(jwt_expiry("not-a-jwt").is_none());
+    assert!(jwt_expiry("a.!!!.c").is_none());
+    assert!(CookieJar::from_header_str("theme=dark").session_expires_at().is_none());
+}
+
+#[test]
+fn jars_convert_to_and_from_header_strings() {
+    let header = "wos-session=abc; theme=dark";
+    let jar: CookieJar = header.into();
+    assert_eq!(jar, CookieJar::from(header.to_string()));
+    assert_eq!(String::from(jar.clone()), header);
+    assert_eq!(jar.to_string(), header);
+    assert!(CookieJar::default().is_empty());
+}
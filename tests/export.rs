@@ -0,0 +1,366 @@
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use t3router::t3::attachment::Attachment;
+use t3router::t3::client::Client;
+use t3router::t3::config::{Config, DelayPolicy, ReasoningEffort, SearchOptions};
+use t3router::t3::conversation::Conversation;
+use t3router::t3::events::Usage;
+use t3router::t3::export::{ConversationExport, ExportOptions};
+use t3router::t3::message::{Citation, GeneratedImage, Message, Part, Type};
+use t3router::t3::models::{ModelCost, ModelInfo};
+
+const FIRST: &str = "https://files.example.com/generated/cat-1.png";
+const SECOND: &str = "https://files.example.com/generated/cat-2.png";
+
+fn two_images() -> Conversation {
+    let mut conversation = Conversation::new();
+    conversation.append_message(Message::new(Type::User, "Two cats".to_string()));
+    conversation.append_message(
+        Message::builder(Type::Assistant)
+            .text("Here are your images.")
+            .image(FIRST, Some("Y2F0LTE=".to_string()))
+            .image(SECOND, Some("Y2F0LTI=".to_string()))
+            .build(),
+    );
+    conversation
+}
+
+#[test]
+fn export_without_image_data_strips_every_image() {
+    let conversation = two_images();
+    let export = conversation.export(ExportOptions {
+        include_image_data: false,
+        ..ExportOptions::default()
+    });
+    let reply = &export.messages[1];
+    assert_eq!(reply.base64(), None);
+    assert_eq!(
+        reply.images().cloned().collect::<Vec<_>>(),
+        [
+            GeneratedImage {
+                url: FIRST.to_string(),
+                base64: None,
+                path: None,
+            },
+            GeneratedImage {
+                url: SECOND.to_string(),
+                base64: None,
+                path: None,
+            },
+        ]
+    );
+    let json = export.to_json_string().unwrap();
+    assert!(!json.contains("Y2F0"), "{}", json);
+
+    // The conversation itself keeps the data.
+    let second = conversation.messages()[1].images().nth(1).unwrap();
+    assert_eq!(second.base64.as_deref(), Some("Y2F0LTI="));
+}
+
+#[test]
+fn export_keeps_image_data_by_default() {
+    let export = two_images().export(ExportOptions::default());
+    let base64: Vec<Option<&str>> = export.messages[1]
+        .images()
+        .map(|image| image.base64.as_deref())
+        .collect();
+    assert_eq!(base64, [Some("Y2F0LTE="), Some("Y2F0LTI=")]);
+    assert_eq!(export.messages[1].base64(), Some("Y2F0LTE="));
+}
+
+#[test]
+fn export_import_export_is_identical() {
+    let mut client = Client::new("", String::new());
+    client.append_message(Message::new(Type::System, "Be brief.".to_string()));
+    client.append_message(Message::new(Type::User, "Draw two cats".to_string()));
+    client.append_message(two_images().messages()[1].clone());
+    client.append_note("Sent from the train".to_string());
+    client.set_thread_id("5f0c8c7e-1d2b-4c3a-9e8f-0a1b2c3d4e5f".parse().unwrap());
+    let json = client.export_conversation().to_json_string().unwrap();
+
+    let mut restored = Client::new("", String::new());
+    restored.import_conversation(ConversationExport::from_json_str(&json).unwrap());
+    assert_eq!(restored.export_conversation().to_json_string().unwrap(), json);
+    assert_eq!(restored.get_system_prompt(), Some("Be brief."));
+}
+
+#[test]
+fn import_replaces_the_previous_conversation() {
+    let mut client = Client::new("", String::new());
+    client.set_system_prompt("Old prompt".to_string());
+    client.append_message(Message::new(Type::User, "Old question".to_string()));
+
+    let export = ConversationExport::new(
+        None,
+        vec![Message::new(Type::User, "New question".to_string())],
+    );
+    client.import_conversation(export);
+    assert_eq!(client.get_system_prompt(), None);
+    assert_eq!(client.get_messages().len(), 1);
+    assert!(client.get_thread_id().is_none());
+    assert!(client.archived_messages().is_empty());
+}
+
+fn usage(input: u64, output: u64) -> Usage {
+    Usage {
+        input_tokens: Some(input),
+        output_tokens: Some(output),
+        ..Usage::default()
+    }
+}
+
+fn reply(model: &str, text: &str, usage: Option<Usage>) -> Message {
+    let builder = Message::builder(Type::Assistant).text(text).model(model);
+    match usage {
+        Some(usage) => builder.usage(usage).build(),
+        None => builder.build(),
+    }
+}
+
+/// Two priced models, one reply without usage and a note.
+fn billed() -> Conversation {
+    let mut conversation = Conversation::new();
+    conversation.append_message(Message::new(Type::User, "Hi".to_string()));
+    conversation.append_message(reply("gpt-4o", "Hello", Some(usage(100, 20))));
+    conversation.append_message(Message::new(Type::User, "More".to_string()));
+    conversation.append_message(reply("gpt-4o", "Sure", Some(usage(150, 30))));
+    conversation.append_message(Message::new(Type::User, "Again".to_string()));
+    conversation.append_message(reply("claude-4-sonnet", "Okay", None));
+    conversation.append_message(Message::new_note("Check <this>".to_string()));
+    conversation
+}
+
+fn catalog() -> Vec<ModelInfo> {
+    vec![
+        ModelInfo {
+            id: "gpt-4o".to_string(),
+            cost: ModelCost {
+                input: Some(0.000_002),
+                output: Some(0.000_01),
+                ..ModelCost::default()
+            },
+            credit_amount: Some(1),
+            ..ModelInfo::default()
+        },
+        ModelInfo {
+            id: "claude-4-sonnet".to_string(),
+            credit_amount: Some(3),
+            ..ModelInfo::default()
+        },
+    ]
+}
+
+#[test]
+fn cost_report_totals_tokens_and_credits_per_model() {
+    let report = billed().cost_report(&catalog());
+    assert_eq!(report.prompt_tokens, Some(250));
+    assert_eq!(report.completion_tokens, Some(50));
+    assert_eq!(report.replies_without_usage, 1);
+
+    let gpt = &report.models[0];
+    assert_eq!(gpt.model.as_deref(), Some("gpt-4o"));
+    assert_eq!((gpt.replies, gpt.credits), (2, Some(2)));
+    assert_eq!((gpt.prompt_tokens, gpt.completion_tokens), (Some(250), Some(50)));
+    assert!((gpt.usd.unwrap() - 0.001).abs() < 1e-12);
+
+    let claude = &report.models[1];
+    assert_eq!((claude.replies, claude.credits), (1, Some(3)));
+    assert_eq!((claude.prompt_tokens, claude.usd), (None, None));
+
+    assert_eq!(report.messages.len(), 7);
+    assert_eq!(report.messages[1].summary(), "100 prompt / 20 completion tokens");
+    assert_eq!(report.messages[0].summary(), "n/a prompt / n/a completion tokens");
+}
+
+#[test]
+fn cost_report_without_usage_or_catalog_shows_na() {
+    let mut conversation = Conversation::new();
+    conversation.append_message(Message::new(Type::User, "Hi".to_string()));
+    conversation.append_message(Message::new(Type::Assistant, "Hello".to_string()));
+    let report = conversation.cost_report(&[]);
+    assert_eq!(
+        report.summary_lines(),
+        [
+            "Prompt tokens: n/a",
+            "Completion tokens: n/a",
+            "unknown model: 1 reply, n/a credits, n/a",
+            "Replies without usage: 1",
+        ]
+    );
+}
+
+#[test]
+fn json_export_carries_the_cost_report() {
+    let conversation = billed();
+    let plain = conversation.export(ExportOptions::default());
+    assert!(!plain.to_json_string().unwrap().contains("\"cost\""));
+
+    let export = conversation.export_with_cost(ExportOptions::default(), &catalog());
+    let cost = export.cost.as_ref().unwrap();
+    assert_eq!(cost.prompt_tokens, Some(250));
+    assert!(cost.messages.is_empty());
+
+    let json = export.to_json_string().unwrap();
+    let restored = ConversationExport::from_json_str(&json).unwrap();
+    assert_eq!(restored.cost, export.cost);
+    assert_eq!(restored.messages[1].usage, Some(usage(100, 20)));
+    assert_eq!(restored.messages[1].model.as_deref(), Some("gpt-4o"));
+
+    let options = ExportOptions {
+        include_message_tokens: true,
+        ..ExportOptions::default()
+    };
+    let export = conversation.export_with_cost(options, &catalog());
+    assert_eq!(export.cost.unwrap().messages.len(), 7);
+}
+
+#[test]
+fn markdown_export_has_a_cost_header() {
+    let conversation = billed();
+    let markdown = conversation
+        .export_with_cost(ExportOptions::default(), &catalog())
+        .to_markdown();
+    let header = markdown.split("\n## ").next().unwrap();
+    assert!(header.contains("- Prompt tokens: 250\n"), "{}", header);
+    assert!(header.contains("- gpt-4o: 2 replies, 2 credits, $0.0010\n"), "{}", header);
+    assert!(header.contains("- claude-4-sonnet: 1 reply, 3 credits, n/a\n"), "{}", header);
+    assert!(markdown.contains("\n## Assistant\n\nHello\n"), "{}", markdown);
+    assert!(markdown.contains("\n## User (local)\n\nCheck <this>\n"), "{}", markdown);
+
+    let options = ExportOptions {
+        include_message_tokens: true,
+        ..ExportOptions::default()
+    };
+    let markdown = conversation.export_with_cost(options, &catalog()).to_markdown();
+    assert!(markdown.contains("## Assistant (100 prompt / 20 completion tokens)"));
+    assert!(markdown.contains("## Assistant (n/a prompt / n/a completion tokens)"));
+
+    // Without a report there is no summary, and an old export still renders.
+    let markdown = conversation.export(ExportOptions::default()).to_markdown();
+    assert!(!markdown.contains("Prompt tokens"));
+}
+
+#[test]
+fn html_export_has_a_cost_footer() {
+    let options = ExportOptions {
+        include_message_tokens: true,
+        ..ExportOptions::default()
+    };
+    let html = billed().export_with_cost(options, &catalog()).to_html();
+    let footer = &html[html.find("<footer class=\"cost\">").unwrap()..];
+    assert!(footer.contains("<li>Completion tokens: 50</li>"), "{}", footer);
+    assert!(footer.contains("<li>Replies without usage: 1</li>"), "{}", footer);
+    assert!(html.contains(concat!(
+        "<article class=\"message assistant\" ",
+        "data-prompt-tokens=\"150\" data-completion-tokens=\"30\">"
+    )));
+    assert!(html.contains("<p>Check &lt;this&gt;</p>"), "{}", html);
+    assert!(html.ends_with("</footer>\n</body>\n</html>\n"));
+}
+
+#[test]
+fn message_round_trips_through_serde() {
+    let attachment = Attachment::from_bytes(b"hello", "text/plain", "notes.txt").unwrap();
+    let mut message = Message::builder(Type::Assistant)
+        .text("Two cats, as sourced.")
+        .image(FIRST, Some("Y2F0LTE=".to_string()))
+        .image(SECOND, Some("Y2F0LTI=".to_string()))
+        .attachment(attachment)
+        .reasoning("The user wants cats.")
+        .created_at(Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap())
+        .model("gpt-image-1")
+        .usage(usage(7, 9))
+        .build();
+    message.citations.push(Citation {
+        url: "https://example.com/cats".to_string(),
+        title: Some("Cats".to_string()),
+        snippet: None,
+        index: 1,
+    });
+    message.completed_at = Some(Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 5).unwrap());
+
+    let json = serde_json::to_string(&message).unwrap();
+    let restored: Message = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, message);
+    assert_eq!(restored.id, message.id);
+    assert_eq!(restored.created_at, message.created_at);
+    assert_eq!(restored.completed_at, message.completed_at);
+    assert_eq!(restored.model, message.model);
+    assert_eq!(restored.usage, message.usage);
+    assert_eq!(restored.base64(), Some("Y2F0LTE="));
+    assert!(restored.attachments().eq(message.attachments()));
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["role"], "assistant");
+    assert_eq!(value["content_type"], "image");
+}
+
+#[test]
+fn message_from_an_older_version_gets_defaults() {
+    let json = r#"{
+        "id": "msg-1",
+        "role": "user",
+        "content": "Hi",
+        "content_type": "text",
+        "image_url": null,
+        "base64_data": null
+    }"#;
+    let message: Message = serde_json::from_str(json).unwrap();
+    assert_eq!(message, Message::new(Type::User, "Hi".to_string()));
+    assert_eq!(message.image_url(), None);
+    assert!(message.citations.is_empty());
+    assert_eq!((message.model, message.usage, message.completed_at), (None, None, None));
+}
+
+#[test]
+fn image_message_from_an_older_version_keeps_its_image() {
+    let json = r#"{
+        "id": "msg-1",
+        "role": "assistant",
+        "content": "https://example.com/cat.png",
+        "content_type": "image",
+        "image_url": "https://example.com/cat.png",
+        "base64_data": "Y2F0LTE="
+    }"#;
+    let message: Message = serde_json::from_str(json).unwrap();
+    assert_eq!(message.text(), "");
+    assert_eq!(
+        message.parts(),
+        [Part::Image(GeneratedImage::new("https://example.com/cat.png", Some("Y2F0LTE=".into())))]
+    );
+
+    // Image fields on a text message never turn it into an image message.
+    let json = json.replace("\"image\"", "\"text\"");
+    let message: Message = serde_json::from_str(&json).unwrap();
+    assert_eq!(message.parts(), [Part::Text("https://example.com/cat.png".to_string())]);
+}
+
+#[test]
+fn reasoning_effort_uses_its_string_form() {
+    for effort in [ReasoningEffort::Low, ReasoningEffort::Medium, ReasoningEffort::High] {
+        let json = serde_json::to_string(&effort).unwrap();
+        assert_eq!(json, format!("\"{}\"", effort.as_str()));
+        assert_eq!(serde_json::from_str::<ReasoningEffort>(&json).unwrap(), effort);
+    }
+    assert!(serde_json::from_str::<ReasoningEffort>("\"extreme\"").is_err());
+}
+
+#[test]
+fn config_round_trips_through_serde() {
+    let config = Config::default()
+        .with_reasoning_effort(ReasoningEffort::High)
+        .with_search(SearchOptions::enabled().with_max_results(3))
+        .with_inter_request_delay(DelayPolicy::Fixed(Duration::from_millis(250)))
+        .with_thread_title("Cats");
+    let json = serde_json::to_value(&config).unwrap();
+    let restored: Config = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&restored).unwrap(), json);
+    assert_eq!(restored.reasoning_effort, ReasoningEffort::High);
+    assert_eq!(restored.search.max_results, Some(3));
+
+    // Missing fields take their defaults.
+    let partial: Config = serde_json::from_str(r#"{"ephemeral": true}"#).unwrap();
+    assert!(partial.ephemeral);
+    assert_eq!(partial.reasoning_effort, ReasoningEffort::Low);
+}
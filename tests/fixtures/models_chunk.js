@@ -0,0 +1 @@
+"use strict";(self.webpackChunk_N_E=self.webpackChunk_N_E||[]).push([[1234],{5678:(e,t,a)=>{a.d(t,{M:()=>n});let n={"gemini-2.5-flash-lite":{id:`gemini-2.5-flash-lite`,name:`Gemini 2.5 Flash Lite`,provider:`openrouter`,developer:`Google`,shortDescription:`Fast and cheap`,fullDescription:`Google's fastest model, for high-volume tasks.`,requiresPro:false,premium:!1,features:new Set([`vision`,`reasoning`,`search`]),searchTags:[`fast`,`cheap`],cost:{input:.1/1e6,output:.4/1e6},cacheRead:.025/1e6,cacheWrite:.1/1e6,limits:{app:{maxInputTokens:1000000,maxOutputTokens:65536},provider:{maxInputTokens:1048576,maxOutputTokens:65536}},apiKeySupport:e.OPENROUTER,addedOn:`2025-06-17`,knowledgeCutoffDate:`2025-01`,disabled:false,legacy:false},"gpt-image-1":{id:`gpt-image-1`,name:`GPT ImageGen`,provider:`openai`,developer:`OpenAI`,shortDescription:`Image generation`,fullDescription:`OpenAI's image generation model.`,requiresPro:true,premium:!0,features:new Set([`imageGeneration`]),creditAmount:8,disabled:false,legacy:false},"claude-4-sonnet":{id:`claude-4-sonnet`,name:`Claude 4 Sonnet`,provider:`anthropic`,developer:`Anthropic`,shortDescription:`Smart and fast`,fullDescription:`Anthropic's balanced model.`,requiresPro:!0,premium:!0,features:new Set([`vision`,`reasoning`]),disabled:!1,legacy:!1},"template":{id:`${r}/${s}`,name:`Template`,provider:`none`,developer:`none`,shortDescription:``,fullDescription:``,requiresPro:false,disabled:false,legacy:false}}}}]);
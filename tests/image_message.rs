@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use t3router::t3::error::T3Error;
+use t3router::t3::message::{ContentType, GeneratedImage, Message, Part, Type};
+
+/// 1x1 images, one per supported format.
+const PNG: &str =
+    "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAAC0lEQVR4nGNgAAIAAAUAAXpeqz8AAAAASUVORK5CYII=";
+const JPEG: &str = "/9j/4AAQSkZJRgABAQEASABIAAD/2wBDAP//////////////////////////////////////////////////////////////////////////////////////wgALCAABAAEBAREA/8QAFBABAAAAAAAAAAAAAAAAAAAAAP/aAAgBAQABPxA=";
+const WEBP: &str = "UklGRhoAAABXRUJQVlA4TA0AAAAvAAAAEAcQERGIiP4HAA==";
+const GIF: &str = "R0lGODlhAQABAIAAAP///wAAACH5BAEAAAAALAAAAAABAAEAAAICRAEAOw==";
+
+fn write_temp(name: &str, bytes: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("t3router-{}-{}", std::process::id(), name));
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+#[test]
+fn image_files_become_inline_image_messages() {
+    for (name, data, mime_type) in [
+        ("pixel.png", PNG, "image/png"),
+        ("pixel.jpg", JPEG, "image/jpeg"),
+        ("pixel.webp", WEBP, "image/webp"),
+        // The extension is ignored; the format comes from the contents.
+        ("pixel.bin", GIF, "image/gif"),
+    ] {
+        let path = write_temp(name, &STANDARD.decode(data).unwrap());
+        let message = Message::new_image_from_file(Type::User, &path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(message.base64(), Some(data), "{}", name);
+        assert_eq!(
+            message.image_url(),
+            Some(format!("data:{};base64,{}", mime_type, data).as_str()),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn image_bytes_must_match_the_expected_type() {
+    let png = STANDARD.decode(PNG).unwrap();
+    let message = Message::new_image_from_bytes(Type::User, &png, Some("image/png")).unwrap();
+    assert_eq!(message.images().count(), 1);
+
+    let err = Message::new_image_from_bytes(Type::User, &png, Some("image/jpeg")).unwrap_err();
+    assert!(err.to_string().contains("image/png"), "{}", err);
+}
+
+#[test]
+fn text_file_is_rejected_with_its_detected_type() {
+    let path = write_temp("notes.png", b"not an image, just notes\n");
+    let err = Message::new_image_from_file(Type::User, &path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+
+    match err {
+        T3Error::UnsupportedAttachment(reason) => {
+            assert!(reason.contains("detected text/plain"), "{}", reason)
+        }
+        other => panic!("expected UnsupportedAttachment, got {:?}", other),
+    }
+}
+
+#[test]
+fn parts_carry_the_data_of_their_kind() {
+    let url = format!("data:image/png;base64,{}", PNG);
+    let image = Message::new_image(Type::Assistant, url.clone(), Some(PNG.to_string()));
+    let expected = GeneratedImage::new(url.clone(), Some(PNG.to_string()));
+    assert_eq!(image.parts(), [Part::Image(expected)]);
+    assert_eq!(image.text(), "");
+    assert_eq!(image.image_url(), Some(url.as_str()));
+
+    let text = Message::new(Type::Assistant, "A red square.".to_string());
+    assert_eq!(text.parts(), [Part::Text("A red square.".to_string())]);
+    assert_eq!(text.image_url(), None);
+    assert_eq!(text.base64(), None);
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_getters_read_the_parts() {
+    let image = Message::new_image(Type::Assistant, "https://a.png".to_string(), None);
+    assert_eq!(image.content_type(), ContentType::Image);
+    assert_eq!(image.base64_data(), None);
+
+    let text = Message::new(Type::Assistant, "A red square.".to_string());
+    assert_eq!(text.content_type(), ContentType::Text);
+}
@@ -0,0 +1,48 @@
+use std::sync::{Arc, Mutex};
+
+use t3router::t3::client::Client;
+use t3router::t3::conversation::Conversation;
+use t3router::t3::manager::ConversationManager;
+use t3router::t3::message::{Message, Type};
+
+fn conversation(text: &str) -> Conversation {
+    let mut conversation = Conversation::new();
+    conversation.append_message(Message::new(Type::User, text.to_string()));
+    conversation
+}
+
+#[test]
+fn least_recently_used_conversations_are_evicted_to_the_callback() {
+    let evicted = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&evicted);
+    let manager = ConversationManager::new(Client::new("", String::new()))
+        .with_max_conversations(2)
+        .on_evict(move |name, conversation| {
+            let text = conversation.messages()[0].content().to_string();
+            sink.lock().unwrap().push((name, text));
+        });
+
+    manager.insert("a", conversation("first"));
+    manager.insert("b", conversation("second"));
+    manager.insert("a", conversation("first again"));
+    manager.insert("c", conversation("third"));
+
+    assert_eq!(manager.list(), ["c", "a"]);
+    assert_eq!(
+        *evicted.lock().unwrap(),
+        [("b".to_string(), "second".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn reset_forgets_a_conversation() {
+    let manager = ConversationManager::new(Client::new("", String::new()));
+    manager.insert("channel-123", conversation("hello"));
+    assert_eq!(manager.len(), 1);
+    assert!(manager.get("channel-123").await.is_some());
+
+    assert!(manager.reset("channel-123"));
+    assert!(!manager.reset("channel-123"));
+    assert!(manager.is_empty());
+    assert!(manager.get("channel-123").await.is_none());
+}
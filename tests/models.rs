@@ -0,0 +1,269 @@
+use std::path::Path;
+
+use t3router::t3::client::Client;
+use t3router::t3::config::{BaseUrls, TrimStrategy};
+use t3router::t3::error::T3Error;
+use t3router::t3::message::{Message, Type};
+use t3router::t3::models::{ModelInfo, ModelsClient, find_model_in, suggest_models};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn model(id: &str, name: &str, provider: &str) -> ModelInfo {
+    ModelInfo {
+        id: id.to_string(),
+        name: name.to_string(),
+        provider: provider.to_string(),
+        ..ModelInfo::default()
+    }
+}
+
+fn catalog() -> Vec<ModelInfo> {
+    vec![
+        model("gpt-4o", "GPT-4o", "openai"),
+        model("gpt-4o-mini", "GPT-4o mini", "openai"),
+        model("gpt-4.1", "GPT 4.1", "openai"),
+        model("claude-4-sonnet", "Claude 4 Sonnet", "anthropic"),
+        model("claude-4-opus", "Claude 4 Opus", "anthropic"),
+        model("gemini-2.5-pro", "Gemini 2.5 Pro", "google"),
+    ]
+}
+
+fn found<'a>(models: &'a [ModelInfo], query: &str) -> Option<&'a str> {
+    find_model_in(models, query).map(|model| model.id.as_str())
+}
+
+#[test]
+fn exact_id_wins() {
+    let models = catalog();
+    assert_eq!(found(&models, "gpt-4o"), Some("gpt-4o"));
+    assert_eq!(found(&models, "gpt-4o-mini"), Some("gpt-4o-mini"));
+}
+
+#[test]
+fn id_and_name_match_case_insensitively() {
+    let models = catalog();
+    assert_eq!(found(&models, "GPT-4O-MINI"), Some("gpt-4o-mini"));
+    assert_eq!(found(&models, "claude 4 opus"), Some("claude-4-opus"));
+    assert_eq!(found(&models, "  Gemini 2.5 Pro  "), Some("gemini-2.5-pro"));
+}
+
+#[test]
+fn punctuation_and_spaces_are_ignored() {
+    let models = catalog();
+    assert_eq!(found(&models, "gpt41"), Some("gpt-4.1"));
+    assert_eq!(found(&models, "Claude4Sonnet"), Some("claude-4-sonnet"));
+}
+
+#[test]
+fn a_case_insensitive_match_beats_a_normalized_one() {
+    let models = vec![model("o3", "o3", "openai"), model("o-3", "O 3", "openai")];
+    assert_eq!(found(&models, "O-3"), Some("o-3"));
+    assert_eq!(found(&models, "o 3"), Some("o-3"));
+}
+
+#[test]
+fn substring_ties_go_to_the_shortest_then_first_id() {
+    let models = catalog();
+    assert_eq!(found(&models, "4o"), Some("gpt-4o"));
+    assert_eq!(found(&models, "claude"), Some("claude-4-opus"));
+    assert_eq!(found(&models, "SONNET"), Some("claude-4-sonnet"));
+}
+
+#[test]
+fn ties_do_not_depend_on_catalog_order() {
+    let mut models = catalog();
+    models.reverse();
+    assert_eq!(found(&models, "4o"), Some("gpt-4o"));
+    assert_eq!(found(&models, "claude"), Some("claude-4-opus"));
+}
+
+#[test]
+fn no_match_and_empty_queries_find_nothing() {
+    let models = catalog();
+    assert_eq!(found(&models, "llama-3"), None);
+    assert_eq!(found(&models, ""), None);
+    assert_eq!(found(&models, "   "), None);
+    assert_eq!(found(&[], "gpt-4o"), None);
+}
+
+async fn serve_catalog(server: &MockServer) {
+    let chunk = std::fs::read_to_string(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/models_chunk.js"),
+    )
+    .unwrap();
+    serve_chunk(server, chunk, 1).await;
+}
+
+/// Serves a homepage that loads `chunk`, expecting `times` catalog fetches.
+async fn serve_chunk(server: &MockServer, chunk: String, times: u64) {
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"<html><head><script src="/assets/main-abc123.js"></script></head></html>"#,
+        ))
+        .expect(times)
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/assets/main-abc123.js"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(chunk))
+        .expect(times)
+        .mount(server)
+        .await;
+}
+
+fn ids(models: &[ModelInfo]) -> Vec<&str> {
+    models.iter().map(|model| model.id.as_str()).collect()
+}
+
+#[tokio::test]
+async fn helpers_filter_one_fetched_catalog() {
+    let server = MockServer::start().await;
+    serve_catalog(&server).await;
+    let models = ModelsClient::new("session=abc".to_string(), "test-session".to_string())
+        .with_base_urls(BaseUrls::new(&server.uri()));
+
+    let sonnet = models.find_model("Claude 4 Sonnet").await.unwrap().unwrap();
+    assert_eq!(sonnet.id, "claude-4-sonnet");
+    assert!(models.find_model("llama-3").await.unwrap().is_none());
+    let openai = models.models_by_provider("OpenAI").await.unwrap();
+    assert_eq!(ids(&openai), ["gpt-image-1"]);
+    let free = models.free_models().await.unwrap();
+    assert_eq!(ids(&free), ["gemini-2.5-flash-lite"]);
+    let premium = models.premium_models().await.unwrap();
+    assert_eq!(ids(&premium), ["claude-4-sonnet", "gpt-image-1"]);
+}
+
+fn catalog_ids() -> Vec<String> {
+    catalog().into_iter().map(|model| model.id).collect()
+}
+
+#[test]
+fn suggestions_rank_by_edit_distance() {
+    let ids = catalog_ids();
+    assert_eq!(suggest_models(&ids, "gpt-4", 3), ["gpt-4o", "gpt-4.1"]);
+    assert_eq!(
+        suggest_models(&ids, "claude-4-sonet", 3),
+        ["claude-4-sonnet", "claude-4-opus"]
+    );
+    assert_eq!(suggest_models(&ids, "gemni-25-pro", 3), ["gemini-2.5-pro"]);
+}
+
+#[test]
+fn suggestion_ties_are_alphabetical_and_limited() {
+    let ids = vec!["o3-mini".to_string(), "o1-mini".to_string(), "o4-mini".to_string()];
+    assert_eq!(suggest_models(&ids, "o2-mini", 3), ["o1-mini", "o3-mini", "o4-mini"]);
+    assert_eq!(suggest_models(&ids, "o2-mini", 2), ["o1-mini", "o3-mini"]);
+    assert!(suggest_models(&ids, "o2-mini", 0).is_empty());
+}
+
+#[test]
+fn suggestions_ignore_case_and_distant_ids() {
+    let ids = catalog_ids();
+    assert_eq!(suggest_models(&ids, "GPT-4O-MINY", 3), ["gpt-4o-mini"]);
+    assert!(suggest_models(&ids, "llama-3-70b", 3).is_empty());
+    assert!(suggest_models(&[], "gpt-4o", 3).is_empty());
+}
+
+fn validating_client(server: &MockServer, validate: bool) -> Client {
+    let mut client = Client::builder("session=abc", "test-session".to_string())
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    client.set_validate_models(validate);
+    client.append_message(Message::new(Type::User, "Hi".to_string()));
+    client
+}
+
+async fn serve_chat(server: &MockServer, times: u64) {
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(concat!(
+                    "data: {\"type\":\"text-delta\",\"id\":\"0\",\"delta\":\"Hello\"}\n\n",
+                    "data: [DONE]\n\n",
+                )),
+        )
+        .expect(times)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn unknown_models_fail_before_sending_with_suggestions() {
+    let server = MockServer::start().await;
+    serve_catalog(&server).await;
+    serve_chat(&server, 1).await;
+    let mut client = validating_client(&server, true);
+
+    let error = client.send("claude-4-sonet", None, None).await.unwrap_err();
+    match error {
+        T3Error::UnknownModel { given, suggestions } => {
+            assert_eq!(given, "claude-4-sonet");
+            assert_eq!(suggestions, ["claude-4-sonnet"]);
+        }
+        other => panic!("expected UnknownModel, got {other:?}"),
+    }
+    // The catalog is fetched once and the listed model goes through.
+    client.send("claude-4-sonnet", None, None).await.unwrap();
+}
+
+#[tokio::test]
+async fn models_are_not_checked_unless_validation_is_on() {
+    let server = MockServer::start().await;
+    serve_chat(&server, 1).await;
+    let mut client = validating_client(&server, false);
+
+    let reply = client.send("model-launched-today", None, None).await.unwrap();
+    assert_eq!(reply.content(), "Hello");
+}
+
+/// A catalog with one model whose context fits a single short exchange.
+const SMALL_CONTEXT_CHUNK: &str = concat!(
+    "let n={\"tiny\":{id:`tiny`,name:`Tiny`,provider:`openai`,requiresPro:false,",
+    "contextLength:40,disabled:false,legacy:false}};",
+);
+
+fn long_history(client: &mut Client) {
+    for turn in 0..3 {
+        let text = format!("Turn {turn}: {}", "words ".repeat(20));
+        client.append_message(Message::new(Type::User, text.clone()));
+        client.append_message(Message::new(Type::Assistant, text));
+    }
+}
+
+#[tokio::test]
+async fn the_catalog_context_length_trims_history_without_validation() {
+    let server = MockServer::start().await;
+    serve_chunk(&server, SMALL_CONTEXT_CHUNK.to_string(), 1).await;
+    serve_chat(&server, 2).await;
+    let mut client = validating_client(&server, false);
+    long_history(&mut client);
+
+    client.send("tiny", None, None).await.unwrap();
+    // The catalog is fetched on the first send only.
+    client.send("tiny", Some(Message::new(Type::User, "Again".to_string())), None).await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let chats: Vec<_> = requests.iter().filter(|r| r.url.path() == "/api/chat").collect();
+    let first: serde_json::Value = serde_json::from_slice(&chats[0].body).unwrap();
+    let sent = first["messages"].as_array().unwrap();
+    assert!(sent.len() < 7, "{} messages sent", sent.len());
+    assert!(sent.last().unwrap()["parts"][0]["text"].as_str().unwrap().starts_with("Turn 2"));
+    assert_eq!(client.archived_messages()[0].content(), "Hi");
+}
+
+#[tokio::test]
+async fn an_explicit_context_limit_skips_the_catalog() {
+    let server = MockServer::start().await;
+    serve_chunk(&server, SMALL_CONTEXT_CHUNK.to_string(), 0).await;
+    serve_chat(&server, 1).await;
+    let mut client = validating_client(&server, false);
+    client.set_context_limit(100_000, TrimStrategy::DropOldest);
+    long_history(&mut client);
+
+    client.send("tiny", None, None).await.unwrap();
+    assert!(client.archived_messages().is_empty());
+}
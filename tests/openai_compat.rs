@@ -0,0 +1,259 @@
+use futures_util::StreamExt;
+use serde_json::{Value, json};
+use t3router::t3::client::Client;
+use t3router::t3::config::{Config, ReasoningEffort};
+use t3router::t3::error::T3Error;
+use t3router::t3::message::{Message, Type};
+use t3router::t3::openai_compat::{
+    ChatCompletionRequest, ChatContent, ChatMessage, ChatRole, openai_finish_reason,
+};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A chat-completions request as OpenAI tooling sends it, with parameters t3.chat ignores.
+const REQUEST: &str = r#"{
+    "model": "gpt-4o",
+    "messages": [
+        {"role": "system", "content": "Be brief."},
+        {"role": "developer", "content": "Answer in English."},
+        {"role": "user", "content": [{"type": "text", "text": "Say hello."}]},
+        {"role": "assistant", "content": "Hello!", "reasoning_content": "Easy."},
+        {"role": "user", "name": "ada", "content": "Again."}
+    ],
+    "reasoning_effort": "high",
+    "temperature": 0.5,
+    "max_tokens": 64,
+    "user": "user-1",
+    "seed": 7,
+    "response_format": {"type": "text"}
+}"#;
+
+const SSE_BODY: &str = concat!(
+    "data: {\"type\":\"start\",\"messageId\":\"msg-1\"}\n\n",
+    "data: {\"type\":\"reasoning-delta\",\"id\":\"r\",\"delta\":\"Greeting.\"}\n\n",
+    "data: {\"type\":\"text-delta\",\"id\":\"0\",\"delta\":\"Hello\"}\n\n",
+    "data: {\"type\":\"text-delta\",\"id\":\"0\",\"delta\":\", world\"}\n\n",
+    "data: {\"type\":\"finish\",\"finishReason\":\"stop\"}\n\n",
+    "data: [DONE]\n\n",
+);
+
+fn request() -> ChatCompletionRequest {
+    serde_json::from_str(REQUEST).unwrap()
+}
+
+#[test]
+fn requests_round_trip_and_keep_unknown_fields() {
+    let request = request();
+    assert_eq!(request.model, "gpt-4o");
+    assert_eq!(request.messages.len(), 5);
+    assert_eq!(request.reasoning_effort, Some(ReasoningEffort::High));
+    assert_eq!(request.max_tokens, Some(64));
+    assert!(!request.stream);
+    assert_eq!(request.extra["seed"], 7);
+
+    let original: Value = serde_json::from_str(REQUEST).unwrap();
+    let mut written = serde_json::to_value(&request).unwrap();
+    // `stream` defaults to false and is always written.
+    assert_eq!(written.as_object_mut().unwrap().remove("stream"), Some(json!(false)));
+    assert_eq!(written, original);
+}
+
+#[test]
+fn messages_convert_to_t3_messages() {
+    let request = request();
+    let messages: Vec<_> = request
+        .messages
+        .iter()
+        .map(|message| message.to_message().unwrap())
+        .collect();
+    let roles: Vec<Type> = messages.iter().map(|message| message.role.clone()).collect();
+    assert_eq!(
+        roles,
+        [Type::System, Type::System, Type::User, Type::Assistant, Type::User]
+    );
+    assert_eq!(messages[2].content(), "Say hello.");
+    assert_eq!(messages[4].content(), "Again.");
+
+    let empty = ChatMessage {
+        role: ChatRole::Assistant,
+        content: None,
+        name: None,
+        reasoning_content: None,
+    };
+    assert_eq!(empty.to_message().unwrap().content(), "");
+}
+
+#[test]
+fn tool_messages_and_non_text_parts_are_rejected() {
+    let tool: ChatMessage =
+        serde_json::from_value(json!({"role": "tool", "content": "{}"})).unwrap();
+    assert!(matches!(tool.to_message(), Err(T3Error::InvalidRequest(_))));
+
+    let image: ChatContent = serde_json::from_value(json!([
+        {"type": "text", "text": "What is this?"},
+        {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}}
+    ]))
+    .unwrap();
+    match image.to_text() {
+        Err(T3Error::InvalidRequest(reason)) => assert!(reason.contains("image_url"), "{reason}"),
+        other => panic!("expected InvalidRequest, got {other:?}"),
+    }
+}
+
+#[test]
+fn reasoning_effort_and_search_map_onto_the_config() {
+    let base = Config::default();
+    let mut request = request();
+    let config = request.to_config(&base);
+    assert_eq!(config.reasoning_effort, ReasoningEffort::High);
+    assert!(!config.search.enabled);
+
+    request.reasoning_effort = None;
+    request.web_search_options = Some(json!({}));
+    let config = request.to_config(&base);
+    assert_eq!(config.reasoning_effort, base.reasoning_effort);
+    assert!(config.search.enabled);
+}
+
+#[test]
+fn finish_reasons_use_the_openai_names() {
+    let reasons = [
+        (Some("stop"), "stop"),
+        (Some("length"), "length"),
+        (Some("content-filter"), "content_filter"),
+        (Some("tool-calls"), "tool_calls"),
+        (Some("other"), "stop"),
+        (None, "stop"),
+    ];
+    for (reason, expected) in reasons {
+        assert_eq!(openai_finish_reason(reason), expected, "{reason:?}");
+    }
+}
+
+async fn chat_server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(SSE_BODY),
+        )
+        .mount(&server)
+        .await;
+    server
+}
+
+fn client_for(server: &MockServer) -> Client {
+    let mut client = Client::builder("wos-session=abc", "test-session".to_string())
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    client.append_message(Message::new(Type::User, "An earlier conversation.".to_string()));
+    client
+}
+
+/// The texts and roles of the messages in the chat request the server received.
+async fn sent_messages(server: &MockServer) -> Vec<(String, String)> {
+    let requests = server.received_requests().await.unwrap();
+    let chat = requests
+        .iter()
+        .find(|request| request.url.path() == "/api/chat")
+        .unwrap();
+    let body: Value = serde_json::from_slice(&chat.body).unwrap();
+    body["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| {
+            let role = message["role"].as_str().unwrap().to_string();
+            let text = message["parts"][0]["text"].as_str().unwrap().to_string();
+            (role, text)
+        })
+        .collect()
+}
+
+fn expected_history() -> Vec<(String, String)> {
+    [
+        ("system", "Be brief.\n\nAnswer in English."),
+        ("user", "Say hello."),
+        ("assistant", "Hello!"),
+        ("user", "Again."),
+    ]
+    .iter()
+    .map(|(role, text)| (role.to_string(), text.to_string()))
+    .collect()
+}
+
+#[tokio::test]
+async fn send_openai_replaces_the_conversation_and_returns_a_completion() {
+    let server = chat_server().await;
+    let mut client = client_for(&server);
+
+    let completion = client.send_openai(request()).await.unwrap();
+    assert_eq!(sent_messages(&server).await, expected_history());
+
+    let completion = serde_json::to_value(&completion).unwrap();
+    assert!(completion["id"].as_str().unwrap().starts_with("chatcmpl-"));
+    assert_eq!(completion["object"], "chat.completion");
+    assert_eq!(completion["model"], "gpt-4o");
+    assert_eq!(
+        completion["choices"],
+        json!([{
+            "index": 0,
+            "message": {
+                "role": "assistant",
+                "content": "Hello, world",
+                "reasoning_content": "Greeting."
+            },
+            "finish_reason": "stop"
+        }])
+    );
+    // The reply can be sent back as the next request's history.
+    let reply: ChatMessage =
+        serde_json::from_value(completion["choices"][0]["message"].clone()).unwrap();
+    assert_eq!(reply.to_message().unwrap().content(), "Hello, world");
+}
+
+#[tokio::test]
+async fn empty_requests_are_rejected_before_sending() {
+    let server = chat_server().await;
+    let mut client = client_for(&server);
+    let mut request = request();
+    request.messages.clear();
+
+    let error = client.send_openai(request).await.unwrap_err();
+    assert!(matches!(error, T3Error::EmptyConversation), "{error:?}");
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn streamed_requests_yield_completion_chunks() {
+    let server = chat_server().await;
+    let mut client = client_for(&server);
+
+    let chunks: Vec<Value> = client
+        .send_openai_stream(request())
+        .map(|chunk| serde_json::to_value(chunk.unwrap()).unwrap())
+        .collect()
+        .await;
+    assert_eq!(sent_messages(&server).await, expected_history());
+
+    let id = chunks[0]["id"].as_str().unwrap();
+    assert!(id.starts_with("chatcmpl-"));
+    for chunk in &chunks {
+        assert_eq!(chunk["id"], id);
+        assert_eq!(chunk["object"], "chat.completion.chunk");
+        assert_eq!(chunk["created"], chunks[0]["created"]);
+        assert_eq!(chunk["model"], "gpt-4o");
+    }
+    let choices: Vec<&Value> = chunks.iter().map(|chunk| &chunk["choices"][0]).collect();
+    assert_eq!(choices[0]["delta"], json!({"role": "assistant"}));
+    assert_eq!(choices[1]["delta"], json!({"reasoning_content": "Greeting."}));
+    assert_eq!(choices[2]["delta"], json!({"content": "Hello"}));
+    assert_eq!(choices[3]["delta"], json!({"content": ", world"}));
+    let last = choices.last().unwrap();
+    assert_eq!(last["delta"], json!({}));
+    assert_eq!(last["finish_reason"], "stop");
+    assert!(choices[..choices.len() - 1].iter().all(|choice| choice["finish_reason"].is_null()));
+}
@@ -0,0 +1,53 @@
+use serde_json::json;
+use t3router::t3::client::Client;
+use t3router::t3::error::T3Error;
+use t3router::t3::message::{Message, Type};
+
+const TRANSCRIPT: &str = r#"[
+    {"role": "system", "content": "Be brief."},
+    {"role": "user", "content": [
+        {"type": "text", "text": "What is in this picture?"},
+        {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}}
+    ]},
+    {"role": "assistant", "content": null, "tool_calls": [{"id": "call_1", "type": "function"}]},
+    {"role": "tool", "tool_call_id": "call_1", "content": "{}"},
+    {"role": "assistant", "content": "A cat."}
+]"#;
+
+#[test]
+fn transcript_is_appended_and_tool_entries_skipped() {
+    let mut client = Client::new("", String::new());
+    let import = client.import_openai_messages(TRANSCRIPT).unwrap();
+    assert_eq!(import.imported, 3);
+    assert_eq!(import.skipped, [2, 3]);
+
+    let messages = client.get_messages();
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[0], Message::new(Type::System, "Be brief.".to_string()));
+    assert_eq!(messages[1].text(), "What is in this picture?");
+    assert_eq!(messages[1].image_url(), Some("https://example.com/cat.png"));
+    assert_eq!(messages[2], Message::new(Type::Assistant, "A cat.".to_string()));
+}
+
+#[test]
+fn malformed_entry_names_its_index_and_imports_nothing() {
+    let mut client = Client::new("", String::new());
+    let json = r#"{"messages": [{"role": "user", "content": "hi"}, {"content": "no role"}]}"#;
+    match client.import_openai_messages(json) {
+        Err(T3Error::InvalidTranscript { index, reason }) => {
+            assert_eq!(index, 1);
+            assert_eq!(reason, "missing role");
+        }
+        other => panic!("expected InvalidTranscript, got {:?}", other),
+    }
+    assert!(client.get_messages().is_empty());
+}
+
+#[test]
+fn from_openai_value_keeps_reasoning() {
+    let value = json!({"role": "assistant", "content": "4", "reasoning_content": "2+2"});
+    let message = Message::from_openai_value(&value).unwrap().unwrap();
+    assert_eq!(message.role, Type::Assistant);
+    assert_eq!(message.content(), "4");
+    assert_eq!(message.reasoning.as_deref(), Some("2+2"));
+}
@@ -0,0 +1,272 @@
+use std::path::Path;
+use std::time::Duration;
+
+use t3router::t3::client::Client;
+use t3router::t3::error::T3Error;
+use t3router::t3::message::GeneratedImage;
+
+fn fixture(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err))
+}
+
+async fn parse(name: &str) -> Result<(String, Option<String>, Option<String>), T3Error> {
+    let client = Client::new("", String::new());
+    client.parse_response(&fixture(name)).await
+}
+
+#[tokio::test]
+async fn text_only() {
+    let parsed = parse("text_only.sse").await.unwrap();
+    assert_eq!(
+        parsed,
+        ("The capital of France is Paris.".to_string(), None, None)
+    );
+}
+
+#[tokio::test]
+async fn object_deltas_and_content_arrays() {
+    let parsed = parse("text_shapes.sse").await.unwrap();
+    assert_eq!(
+        parsed,
+        ("Object text content array".to_string(), None, None)
+    );
+}
+
+#[tokio::test]
+async fn reasoning_is_not_part_of_the_text() {
+    let parsed = parse("reasoning.sse").await.unwrap();
+    assert_eq!(parsed, ("4".to_string(), None, None));
+}
+
+#[tokio::test]
+async fn image_gen_with_url() {
+    let parsed = parse("image_gen_url.sse").await.unwrap();
+    assert_eq!(
+        parsed,
+        (
+            String::new(),
+            Some("https://files.example.com/generated/cat.png".to_string()),
+            None
+        )
+    );
+}
+
+#[tokio::test]
+async fn image_gen_with_inline_data_url() {
+    let data = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mP8z8BQDwAEhQGAhKmMIQAAAABJRU5ErkJggg==";
+    let parsed = parse("image_gen_data_url.sse").await.unwrap();
+    assert_eq!(
+        parsed,
+        (
+            String::new(),
+            Some(format!("data:image/png;base64,{}", data)),
+            Some(data.to_string())
+        )
+    );
+}
+
+#[tokio::test]
+async fn tool_output_array_returns_first_image() {
+    let parsed = parse("tool_output_array.sse").await.unwrap();
+    assert_eq!(
+        parsed,
+        (
+            "Here are your images.".to_string(),
+            Some("https://files.example.com/generated/cat-1.png".to_string()),
+            None
+        )
+    );
+}
+
+#[tokio::test]
+async fn tool_output_array_keeps_every_image() {
+    let client = Client::new("", String::new());
+    let message = client
+        .parse_message(&fixture("tool_output_array.sse"))
+        .await
+        .unwrap();
+    let image = |url: &str| GeneratedImage {
+        url: url.to_string(),
+        base64: None,
+        path: None,
+    };
+    assert_eq!(
+        message.images().cloned().collect::<Vec<_>>(),
+        [
+            image("https://files.example.com/generated/cat-1.png"),
+            image("https://files.example.com/generated/cat-2.png"),
+        ]
+    );
+    assert_eq!(message.image_url(), Some("https://files.example.com/generated/cat-1.png"));
+    assert_eq!(message.text(), "Here are your images.");
+    assert_eq!(message.id, "00000000-0000-4000-8000-000000000006");
+}
+
+#[tokio::test]
+async fn tool_output_with_single_url() {
+    let parsed = parse("tool_output_url.sse").await.unwrap();
+    assert_eq!(
+        parsed,
+        (
+            String::new(),
+            Some("https://files.example.com/generated/dog.webp".to_string()),
+            None
+        )
+    );
+}
+
+#[tokio::test]
+async fn crlf_line_endings() {
+    let parsed = parse("crlf.sse").await.unwrap();
+    assert_eq!(parsed, ("Windows line endings".to_string(), None, None));
+}
+
+#[tokio::test]
+async fn data_without_a_space() {
+    let parsed = parse("data_without_space.sse").await.unwrap();
+    assert_eq!(parsed, ("No space".to_string(), None, None));
+}
+
+#[tokio::test]
+async fn event_names_give_the_type() {
+    let parsed = parse("event_names.sse").await.unwrap();
+    assert_eq!(parsed, ("Named events".to_string(), None, None));
+}
+
+#[tokio::test]
+async fn comment_lines_are_skipped() {
+    let parsed = parse("comments.sse").await.unwrap();
+    assert_eq!(parsed, ("Still here".to_string(), None, None));
+}
+
+#[tokio::test]
+async fn multi_line_data_is_joined() {
+    let parsed = parse("multiline_data.sse").await.unwrap();
+    assert_eq!(parsed, ("Split across lines".to_string(), None, None));
+}
+
+#[tokio::test]
+async fn empty_stream_is_an_error() {
+    let err = parse("empty.sse").await.unwrap_err();
+    assert!(matches!(err, T3Error::EmptyResponse), "{:?}", err);
+}
+
+#[tokio::test]
+async fn garbage_is_an_error() {
+    let err = parse("garbage.sse").await.unwrap_err();
+    assert!(matches!(err, T3Error::EmptyResponse), "{:?}", err);
+}
+
+#[tokio::test]
+async fn rate_limit_event_is_an_error() {
+    let err = parse("rate_limited.sse").await.unwrap_err();
+    match err {
+        T3Error::RateLimited {
+            retry_after,
+            message,
+        } => {
+            assert_eq!(retry_after, Some(Duration::from_secs(30)));
+            assert_eq!(message, "Rate limit exceeded, try again later");
+        }
+        other => panic!("expected RateLimited, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn requires_pro_event_is_a_server_error() {
+    let err = parse("requires_pro.sse").await.unwrap_err();
+    match err {
+        T3Error::Server { code, message } => {
+            assert_eq!(code, None);
+            assert_eq!(
+                message,
+                "This model requires a Pro subscription. Upgrade at https://t3.chat/settings/subscription to use it."
+            );
+        }
+        other => panic!("expected Server, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn byok_event_is_byok_required() {
+    let err = parse("byok_required.sse").await.unwrap_err();
+    match err {
+        T3Error::ByokRequired { provider, message } => {
+            assert_eq!(provider.as_deref(), Some("anthropic"));
+            assert!(message.starts_with("Claude 4 Opus requires your own API key."));
+        }
+        other => panic!("expected ByokRequired, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn content_filter_event_is_a_server_error() {
+    let err = parse("content_filter.sse").await.unwrap_err();
+    match err {
+        T3Error::Server { code, message } => {
+            assert_eq!(code.as_deref(), Some("content_filter"));
+            assert_eq!(
+                message,
+                "Your message was flagged by the provider's content filter and could not be processed."
+            );
+        }
+        other => panic!("expected Server, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn search_citations_keep_their_indexes() {
+    let client = Client::new("", String::new());
+    let message = client
+        .parse_message(&fixture("search_citations.sse"))
+        .await
+        .unwrap();
+    // Inline markers are left for callers to resolve against the citation indexes.
+    assert_eq!(
+        message.content(),
+        "Rust 1.80 stabilized LazyCell and LazyLock [1][2]. \
+         It also allows exclusive ranges in patterns [4], see the release notes [3]."
+    );
+    let citations: Vec<(usize, &str, Option<&str>)> = message
+        .citations
+        .iter()
+        .map(|c| (c.index, c.url.as_str(), c.title.as_deref()))
+        .collect();
+    assert_eq!(
+        citations,
+        [
+            (1, "https://blog.example.com/rust-1-80", Some("Announcing Rust 1.80.0")),
+            (
+                2,
+                "https://docs.example.com/std/sync/struct.LazyLock.html",
+                Some("LazyLock in std::sync")
+            ),
+            (3, "https://github.example.com/rust-lang/rust/releases/tag/1.80.0", None),
+            (4, "https://blog.example.com/rust-1-80", Some("Announcing Rust 1.80.0")),
+        ]
+    );
+    let snippets: Vec<Option<&str>> = message
+        .citations
+        .iter()
+        .map(|c| c.snippet.as_deref())
+        .collect();
+    assert_eq!(
+        snippets,
+        [
+            None,
+            Some("A value which is initialized on the first access."),
+            None,
+            Some("Exclusive ranges in patterns."),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn citations_do_not_change_the_parsed_text() {
+    let parsed = parse("search_citations.sse").await.unwrap();
+    assert!(parsed.0.ends_with("see the release notes [3]."));
+    assert_eq!((parsed.1, parsed.2), (None, None));
+}
@@ -0,0 +1,247 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use t3router::t3::client::Client;
+use t3router::t3::events::{SseParser, parse_events};
+use t3router::t3::models::{Plan, parse_model_chunk, parse_model_chunk_regex};
+
+/// Mutations per seed. Each run is seeded, so a failure reproduces.
+const MUTATIONS: usize = 300;
+
+/// Mutations of the model chunk, which compiles its regexes on every parse.
+const MODEL_MUTATIONS: usize = 50;
+
+/// How long one parse may take on inputs of a few kilobytes, generous for debug builds.
+const SLOW: Duration = Duration::from_secs(2);
+
+/// Multi-byte characters spliced into inputs to land slices on non-boundaries.
+const WIDE_CHARS: [&str; 4] = ["é", "世", "👋", "\u{feff}"];
+
+fn seeds(extension: &str) -> Vec<Vec<u8>> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut paths: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| std::fs::read(path).unwrap())
+        .collect()
+}
+
+fn mutate(rng: &mut fastrand::Rng, input: &[u8]) -> Vec<u8> {
+    let mut bytes = input.to_vec();
+    for _ in 0..rng.usize(1..4) {
+        let at = rng.usize(0..=bytes.len());
+        match rng.u8(0..6) {
+            0 => bytes.truncate(at),
+            1 if at < bytes.len() => bytes[at] = rng.u8(..),
+            2 => {
+                let wide = WIDE_CHARS[rng.usize(0..WIDE_CHARS.len())].as_bytes();
+                bytes.splice(at..at, wide[..rng.usize(1..=wide.len())].iter().copied());
+            }
+            3 => {
+                let end = rng.usize(at..=bytes.len());
+                bytes.drain(at..end);
+            }
+            4 => {
+                let end = rng.usize(at..=bytes.len().min(at + 64));
+                let copy = bytes[at..end].to_vec();
+                bytes.splice(at..at, copy);
+            }
+            _ => {
+                let noise: Vec<u8> = (0..rng.usize(1..16)).map(|_| rng.u8(..)).collect();
+                bytes.splice(at..at, noise);
+            }
+        }
+    }
+    bytes
+}
+
+fn random_bytes(rng: &mut fastrand::Rng) -> Vec<u8> {
+    let alphabet = b"data: {}[]\",:\\\n\r event[DONE]type-text-delta base64,";
+    (0..rng.usize(0..2048))
+        .map(|_| match rng.u8(0..4) {
+            0 => rng.u8(..),
+            _ => alphabet[rng.usize(0..alphabet.len())],
+        })
+        .collect()
+}
+
+fn timed<T>(label: &str, input: &[u8], parse: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = parse();
+    assert!(
+        start.elapsed() < SLOW,
+        "{} took {:?} on {:?}",
+        label,
+        start.elapsed(),
+        String::from_utf8_lossy(input)
+    );
+    result
+}
+
+async fn parse_sse(client: &Client, input: &[u8], split: usize) {
+    let text = String::from_utf8_lossy(input);
+    timed("parse_events", input, || parse_events(&text));
+    let _ = timed("parse_response", input, || {
+        futures_util::FutureExt::now_or_never(client.parse_response(&text))
+    });
+    timed("SseParser::feed", input, || {
+        let mut parser = SseParser::new();
+        let split = split.min(input.len());
+        parser.feed(&input[..split]);
+        parser.feed(&input[split..]);
+        parser.finish();
+    });
+}
+
+#[tokio::test]
+async fn mutated_sse_fixtures_do_not_panic() {
+    let client = Client::new("", String::new());
+    let mut rng = fastrand::Rng::with_seed(1083);
+    for seed in seeds("sse") {
+        for _ in 0..MUTATIONS {
+            let input = mutate(&mut rng, &seed);
+            let split = rng.usize(0..=input.len());
+            parse_sse(&client, &input, split).await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn random_bytes_do_not_panic_the_sse_parsers() {
+    let client = Client::new("", String::new());
+    let mut rng = fastrand::Rng::with_seed(83);
+    for _ in 0..MUTATIONS * 4 {
+        let input = random_bytes(&mut rng);
+        let split = rng.usize(0..=input.len());
+        parse_sse(&client, &input, split).await;
+    }
+}
+
+#[test]
+fn model_chunk_fixture_parses() {
+    let chunk = String::from_utf8(seeds("js").remove(0)).unwrap();
+    let models = parse_model_chunk(&chunk).unwrap();
+    let ids: Vec<&str> = models.iter().map(|model| model.id.as_str()).collect();
+    assert_eq!(ids, ["gemini-2.5-flash-lite", "gpt-image-1", "claude-4-sonnet"]);
+    assert_eq!(models[0].limits.app_max_input_tokens, Some(1_000_000));
+    assert!(models[0].supports_vision);
+    assert!(models[1].requires_pro);
+    assert_eq!(models[1].credit_amount, Some(8));
+}
+
+#[test]
+fn model_chunk_flags_pro_and_premium_models() {
+    let chunk = String::from_utf8(seeds("js").remove(0)).unwrap();
+    let models = parse_model_chunk(&chunk).unwrap();
+    let flags: Vec<(&str, bool, bool)> = models
+        .iter()
+        .map(|model| (model.id.as_str(), model.requires_pro, model.premium))
+        .collect();
+    // claude-4-sonnet uses the minified `!0` / `!1` literals.
+    assert_eq!(
+        flags,
+        [
+            ("gemini-2.5-flash-lite", false, false),
+            ("gpt-image-1", true, true),
+            ("claude-4-sonnet", true, true),
+        ]
+    );
+    assert!(!models[2].disabled && !models[2].legacy);
+    assert!(models[0].accessible_with(Plan::Free));
+    assert!(!models[1].accessible_with(Plan::Free));
+    assert!(models[1].accessible_with(Plan::Pro));
+}
+
+#[test]
+fn mutated_model_chunks_do_not_panic() {
+    let mut rng = fastrand::Rng::with_seed(1083);
+    for seed in seeds("js") {
+        for _ in 0..MODEL_MUTATIONS {
+            let input = mutate(&mut rng, &seed);
+            let text = String::from_utf8_lossy(&input);
+            timed("parse_model_chunk", &input, || parse_model_chunk(&text)).unwrap();
+        }
+    }
+}
+
+#[test]
+fn unterminated_multiline_data_is_linear() {
+    let mut stream = String::from("data: [\n");
+    for _ in 0..50_000 {
+        stream.push_str("data: {},\n");
+    }
+    let input = stream.into_bytes();
+    let events = timed("parse_events", &input, || {
+        parse_events(&String::from_utf8_lossy(&input))
+    });
+    assert!(events.is_empty());
+}
+
+#[test]
+fn many_unmatched_model_entries_are_not_pathological() {
+    let chunk = "{id:`a`,name:`b`,".repeat(20_000);
+    let models = timed("parse_model_chunk", chunk.as_bytes(), || {
+        parse_model_chunk(&chunk)
+    });
+    assert!(models.unwrap().is_empty());
+}
+
+#[test]
+fn structured_and_regex_model_parsers_agree() {
+    let chunk = String::from_utf8(seeds("js").remove(0)).unwrap();
+    let structured = serde_json::to_value(parse_model_chunk(&chunk).unwrap()).unwrap();
+    let regex = serde_json::to_value(parse_model_chunk_regex(&chunk).unwrap()).unwrap();
+    assert_eq!(structured, regex);
+}
+
+#[test]
+fn model_fields_may_come_in_any_order() {
+    let chunk = concat!(
+        "let n={\"o3-pro\":{id:`o3-pro`,premium:!0,legacy:!1,",
+        "limits:{provider:{maxOutputTokens:100000,maxInputTokens:200000}},",
+        "rollout:{regions:[`us`,`eu`],percent:50},name:`o3 Pro`,",
+        "cost:{output:80/1e6,input:20/1e6},provider:`openai`,",
+        "fullDescription:`Thinks \\`harder\\`, {slower}.`,features:new Set([`reasoning`])}};",
+    );
+    let models = parse_model_chunk(chunk).unwrap();
+    assert_eq!(models.len(), 1);
+    let model = &models[0];
+    assert_eq!((model.id.as_str(), model.name.as_str()), ("o3-pro", "o3 Pro"));
+    assert!(model.premium && !model.requires_pro && !model.legacy);
+    assert_eq!(model.full_description, "Thinks `harder`, {slower}.");
+    assert_eq!(model.context_length, Some(200_000));
+    assert_eq!(model.max_output_tokens, Some(100_000));
+    assert_eq!(model.cost.input, Some(20.0 / 1e6));
+    assert!(model.supports_reasoning);
+    // The regex scan needs requiresPro, disabled and legacy in catalog order.
+    assert!(parse_model_chunk_regex(chunk).unwrap().is_empty());
+}
+
+#[test]
+fn large_model_chunk_finds_every_model() {
+    let mut chunk = String::new();
+    let mut count = 0;
+    while chunk.len() < 2 << 20 {
+        chunk.push_str(concat!(
+            "function q(e,t){return e.replace(/\"/g,\"&quot;\").replace(/[{}]/g,\"\")",
+            "+`${t?`{`:\"}\"}`}/* } */",
+        ));
+        if chunk.len() / 20_000 > count {
+            chunk.push_str(&format!(
+                "m[{0}]={{id:`model-{0}`,name:`Model {0}`,provider:`openai`,requiresPro:!1,\
+                 cost:{{input:.5/1e6,output:1.5/1e6}},features:new Set([`vision`])}};",
+                count
+            ));
+            count += 1;
+        }
+    }
+    // Its speed is measured by `benches/model_chunk.rs`.
+    let models = parse_model_chunk(&chunk).unwrap();
+    assert_eq!(models.len(), count);
+    assert!(models.iter().all(|model| model.supports_vision));
+}
@@ -0,0 +1,149 @@
+use std::path::Path;
+
+use t3router::t3::events::{SseEvent, SseParser, parse_events};
+
+const FIXTURES: [&str; 9] = [
+    "text_only.sse",
+    "text_shapes.sse",
+    "reasoning.sse",
+    "tool_output_array.sse",
+    "crlf.sse",
+    "event_names.sse",
+    "comments.sse",
+    "multiline_data.sse",
+    "unicode.sse",
+];
+
+fn fixture(name: &str) -> Vec<u8> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    std::fs::read(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err))
+}
+
+fn feed_chunks<'a>(chunks: impl IntoIterator<Item = &'a [u8]>) -> Vec<SseEvent> {
+    let mut parser = SseParser::new();
+    let mut events: Vec<SseEvent> = chunks
+        .into_iter()
+        .flat_map(|chunk| parser.feed(chunk))
+        .collect();
+    events.extend(parser.finish());
+    events
+}
+
+fn whole(body: &[u8]) -> Vec<SseEvent> {
+    parse_events(std::str::from_utf8(body).unwrap())
+}
+
+#[test]
+fn split_at_every_offset_matches_whole_body() {
+    for name in FIXTURES {
+        let body = fixture(name);
+        let expected = whole(&body);
+        assert!(!expected.is_empty(), "{}", name);
+        for offset in 0..=body.len() {
+            let (head, tail) = body.split_at(offset);
+            assert_eq!(
+                feed_chunks([head, tail]),
+                expected,
+                "{} split at {}",
+                name,
+                offset
+            );
+        }
+    }
+}
+
+#[test]
+fn one_byte_chunks_match_whole_body() {
+    for name in FIXTURES {
+        let body = fixture(name);
+        assert_eq!(feed_chunks(body.chunks(1)), whole(&body), "{}", name);
+    }
+}
+
+#[test]
+fn three_way_splits_around_line_ends() {
+    for name in FIXTURES {
+        let body = fixture(name);
+        let expected = whole(&body);
+        let newlines: Vec<usize> = body
+            .iter()
+            .enumerate()
+            .filter(|(_, byte)| **byte == b'\n')
+            .map(|(index, _)| index)
+            .collect();
+        for &first in &newlines {
+            for second in [first + 1, first + 2, body.len()] {
+                let second = second.min(body.len());
+                let chunks = [&body[..first], &body[first..second], &body[second..]];
+                assert_eq!(
+                    feed_chunks(chunks),
+                    expected,
+                    "{} at {}/{}",
+                    name,
+                    first,
+                    second
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn character_split_between_chunks_is_kept_whole() {
+    let body = fixture("unicode.sse");
+    let text = std::str::from_utf8(&body).unwrap();
+    let inside: Vec<usize> = (0..body.len())
+        .filter(|offset| !text.is_char_boundary(*offset))
+        .collect();
+    assert!(!inside.is_empty());
+    for offset in inside {
+        let (head, tail) = body.split_at(offset);
+        let deltas: String = feed_chunks([head, tail])
+            .into_iter()
+            .filter_map(|event| match event {
+                SseEvent::TextDelta(text) => Some(text),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(deltas, "Grüße, 世界 👋", "split at {}", offset);
+    }
+}
+
+#[test]
+fn last_line_without_a_line_ending_is_read_by_finish() {
+    let mut parser = SseParser::new();
+    assert!(
+        parser
+            .feed(b"data: {\"type\":\"text-delta\",\"delta\":\"tail\"}")
+            .is_empty()
+    );
+    assert_eq!(
+        parser.finish(),
+        Some(SseEvent::TextDelta("tail".to_string()))
+    );
+}
+
+#[test]
+fn brackets_and_quotes_inside_strings_do_not_end_a_split_event() {
+    let mut parser = SseParser::new();
+    assert!(parser.push_line("data: {\"type\":\"text-delta\",").is_none());
+    assert!(parser.push_line("data: \"delta\":\"} ] \\\" {\"").is_none());
+    assert_eq!(
+        parser.push_line("data: }"),
+        Some(SseEvent::TextDelta("} ] \" {".to_string()))
+    );
+}
+
+#[test]
+fn a_broken_value_gives_way_to_the_next_event() {
+    let mut parser = SseParser::new();
+    assert!(parser.push_line("data: {\"type\":").is_none());
+    assert!(parser.push_line("data: ]}").is_none());
+    assert!(parser.push_line("data: \"text-delta\"}").is_none());
+    assert_eq!(
+        parser.push_line("data: {\"type\":\"text-delta\",\"delta\":\"next\"}"),
+        Some(SseEvent::TextDelta("next".to_string()))
+    );
+}
@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+use t3router::t3::client::Client;
+use t3router::t3::error::T3Error;
+use t3router::t3::message::{Message, Type};
+use t3router::t3::store::ConversationStore;
+use t3router::t3::thread::ThreadId;
+
+fn db_path(name: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn client_with_image() -> Client {
+    let mut client = Client::new("", String::new());
+    client.set_thread_id(ThreadId::new());
+    client.set_system_prompt("Be brief.".to_string());
+    client.append_message(Message::new(Type::User, "Draw a cat.".to_string()));
+    client.append_message(Message::new_image(
+        Type::Assistant,
+        "https://files.example.com/cat.png".to_string(),
+        Some("iVBORw0KGgo=".to_string()),
+    ));
+    client
+}
+
+#[test]
+fn conversation_with_an_image_round_trips_through_a_file() {
+    let path = db_path("store_round_trip.db");
+    let client = client_with_image();
+    let thread_id = ConversationStore::open(&path).unwrap().save(&client).unwrap();
+
+    let store = ConversationStore::open(&path).unwrap();
+    let messages = store.load(&thread_id).unwrap();
+    assert_eq!(&messages, client.get_messages());
+    assert_eq!(messages[2].base64(), Some("iVBORw0KGgo="));
+    assert_eq!(
+        messages.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(),
+        client.get_messages().iter().map(|m| m.id.as_str()).collect::<Vec<_>>()
+    );
+    assert_eq!(messages[0].created_at, client.get_messages()[0].created_at);
+
+    let threads = store.list().unwrap();
+    assert_eq!(threads.len(), 1);
+    assert_eq!(threads[0].thread_id, thread_id);
+    assert_eq!(threads[0].message_count, 3);
+    assert_eq!(threads[0].system_prompt.as_deref(), Some("Be brief."));
+}
+
+#[test]
+fn saving_again_replaces_the_thread() {
+    let store = ConversationStore::open(&db_path("store_resave.db")).unwrap();
+    let mut client = client_with_image();
+    let thread_id = store.save(&client).unwrap();
+    client.pop_message();
+    store.save(&client).unwrap();
+    assert_eq!(store.load(&thread_id).unwrap().len(), 2);
+    assert_eq!(store.list().unwrap().len(), 1);
+}
+
+#[test]
+fn delete_removes_the_thread() {
+    let store = ConversationStore::open_in_memory().unwrap();
+    let thread_id = store.save(&client_with_image()).unwrap();
+    assert!(store.delete(&thread_id).unwrap());
+    assert!(!store.delete(&thread_id).unwrap());
+    assert!(matches!(store.load(&thread_id), Err(T3Error::NotFound(_))));
+    assert!(store.list().unwrap().is_empty());
+}
+
+#[test]
+fn unsent_conversation_cannot_be_saved() {
+    let store = ConversationStore::open_in_memory().unwrap();
+    let client = Client::new("", String::new());
+    assert!(matches!(store.save(&client), Err(T3Error::InvalidRequest(_))));
+}
@@ -0,0 +1,140 @@
+use futures_util::StreamExt;
+use t3router::t3::backend::ChatBackend;
+use t3router::t3::client::{Client, StreamDelta};
+use t3router::t3::config::{Config, ReasoningEffort};
+use t3router::t3::error::T3Error;
+use t3router::t3::message::{Message, Type};
+use t3router::t3::testing::MockBackend;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Application code written against the trait, as in the README.
+async fn summarize(backend: &mut dyn ChatBackend, text: &str) -> String {
+    let prompt = Message::new(Type::User, format!("Summarize: {text}"));
+    backend.send("gpt-4o".into(), Some(prompt), None).await.unwrap().content().to_string()
+}
+
+fn user(text: &str) -> Option<Message> {
+    Some(Message::new(Type::User, text.to_string()))
+}
+
+fn contents(messages: &[Message]) -> Vec<&str> {
+    messages.iter().map(|message| message.content()).collect()
+}
+
+#[tokio::test]
+async fn summarize_sends_prompt() {
+    let mut mock = MockBackend::new().with_reply("Short.");
+    assert_eq!(summarize(&mut mock, "a long text").await, "Short.");
+    assert_eq!(mock.sent()[0].new_message.as_ref().unwrap().content(), "Summarize: a long text");
+    assert_eq!(mock.sent()[0].model.as_str(), "gpt-4o");
+    assert_eq!(contents(mock.messages()), ["Summarize: a long text", "Short."]);
+}
+
+#[tokio::test]
+async fn replies_are_used_in_order_and_requests_recorded() {
+    let mut mock = MockBackend::new().with_reply("One").with_reply("Two");
+    let config = Config::default().with_reasoning_effort(ReasoningEffort::High);
+
+    assert_eq!(mock.send("gpt-4o".into(), user("a"), None).await.unwrap().content(), "One");
+    let reply = mock.send("o3".into(), user("b"), Some(config)).await.unwrap();
+    assert_eq!(reply, Message::new(Type::Assistant, "Two".to_string()));
+
+    let sent = mock.sent();
+    assert_eq!(sent.len(), 2);
+    assert!(sent[0].config.is_none());
+    assert_eq!(sent[1].model.as_str(), "o3");
+    assert_eq!(sent[1].config.as_ref().unwrap().reasoning_effort, ReasoningEffort::High);
+    // Each request carries the conversation as it was sent.
+    assert_eq!(contents(&sent[0].messages), ["a"]);
+    assert_eq!(contents(&sent[1].messages), ["a", "One", "b"]);
+}
+
+#[tokio::test]
+async fn scripted_errors_leave_the_conversation_unchanged() {
+    let mut mock = MockBackend::new().with_reply("One");
+    mock.push_error(T3Error::RateLimited {
+        retry_after: None,
+        message: "slow down".to_string(),
+    });
+    mock.send("gpt-4o".into(), user("a"), None).await.unwrap();
+
+    let error = mock.send("gpt-4o".into(), user("b"), None).await.unwrap_err();
+    assert!(matches!(error, T3Error::RateLimited { .. }), "{error:?}");
+    // With no replies left, sends fail too.
+    let error = mock.send("gpt-4o".into(), user("c"), None).await.unwrap_err();
+    assert!(matches!(error, T3Error::EmptyResponse), "{error:?}");
+
+    assert_eq!(contents(mock.messages()), ["a", "One"]);
+    assert_eq!(mock.sent().len(), 3);
+    assert_eq!(contents(&mock.sent()[2].messages), ["a", "One", "c"]);
+}
+
+#[tokio::test]
+async fn streams_end_with_the_reply() {
+    let mut mock = MockBackend::new().with_reply("Streamed");
+    let deltas: Vec<StreamDelta> = mock
+        .send_stream("gpt-4o".into(), user("a"), None)
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    assert!(matches!(&deltas[..], [StreamDelta::Text(text), StreamDelta::Done(reply)]
+        if text == "Streamed" && reply.content() == "Streamed"));
+
+    let errors: Vec<_> = mock.send_stream("gpt-4o".into(), user("b"), None).collect().await;
+    assert!(matches!(&errors[..], [Err(T3Error::EmptyResponse)]));
+}
+
+#[tokio::test]
+async fn new_conversations_clear_the_messages_and_thread() {
+    let mut mock = MockBackend::new().with_reply("One").with_reply("Two");
+    assert!(mock.thread_id().is_none());
+    mock.send("gpt-4o".into(), user("a"), None).await.unwrap();
+    let thread = mock.thread_id().unwrap();
+    mock.send("gpt-4o".into(), user("b"), None).await.unwrap();
+    assert_eq!(mock.thread_id(), Some(thread));
+
+    mock.new_conversation();
+    assert!(mock.messages().is_empty());
+    assert!(mock.thread_id().is_none());
+    // Requests made before are still recorded.
+    assert_eq!(mock.sent().len(), 2);
+}
+
+#[tokio::test]
+async fn the_client_is_a_boxed_backend() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(concat!(
+                    "data: {\"type\":\"text-delta\",\"id\":\"0\",\"delta\":\"Hello\"}\n\n",
+                    "data: [DONE]\n\n",
+                )),
+        )
+        .mount(&server)
+        .await;
+    let client = Client::builder("wos-session=abc", "test-session".to_string())
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    let mut backend: Box<dyn ChatBackend> = Box::new(client);
+
+    assert_eq!(summarize(backend.as_mut(), "a long text").await, "Hello");
+    assert!(backend.thread_id().is_some());
+    assert_eq!(contents(backend.messages()), ["Summarize: a long text", "Hello"]);
+
+    let deltas: Vec<StreamDelta> = backend
+        .send_stream("gpt-4o".into(), user("Again"), None)
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    assert!(matches!(deltas.last(), Some(StreamDelta::Done(reply)) if reply.content() == "Hello"));
+    assert_eq!(backend.messages().len(), 4);
+
+    backend.new_conversation();
+    assert!(backend.messages().is_empty());
+    assert!(backend.thread_id().is_none());
+}
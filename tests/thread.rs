@@ -0,0 +1,110 @@
+use serde_json::json;
+use t3router::t3::client::Client;
+use t3router::t3::config::BaseUrls;
+use t3router::t3::message::{Message, Type};
+use t3router::t3::thread::ThreadId;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const ID: &str = "0b7c5a0e-2f5b-4a55-9a0c-3c1f9d7e8a21";
+
+#[test]
+fn parses_and_displays_a_uuid() {
+    let id: ThreadId = ID.parse().unwrap();
+    assert_eq!(id.to_string(), ID);
+    assert_eq!(format!(" {} ", ID).parse::<ThreadId>().unwrap(), id);
+}
+
+#[test]
+fn rejects_non_uuid_strings() {
+    for input in ["", "thread-1", "0b7c5a0e-2f5b-4a55-9a0c", "j57d8x6p5k9w0q1r2s3t4u5v6w7x8y9z"] {
+        let err = input.parse::<ThreadId>().unwrap_err();
+        assert_eq!(err.input, input);
+    }
+}
+
+#[test]
+fn from_url_reads_the_chat_segment() {
+    let id: ThreadId = ID.parse().unwrap();
+    for url in [
+        format!("https://t3.chat/chat/{}", ID),
+        format!("https://t3.chat/chat/{}/", ID),
+        format!("https://t3.chat/chat/{}?model=gpt-5#latest", ID),
+    ] {
+        assert_eq!(ThreadId::from_url(&url).unwrap(), id);
+    }
+}
+
+#[test]
+fn from_url_rejects_urls_without_a_thread() {
+    for url in [
+        format!("https://t3.chat/{}", ID),
+        format!("https://t3.chat/share/{}", ID),
+        "https://t3.chat/chat/".to_string(),
+        "https://t3.chat/chat/not-a-thread".to_string(),
+    ] {
+        let err = ThreadId::from_url(&url).unwrap_err();
+        assert_eq!(err.input, url);
+    }
+}
+
+#[test]
+fn serde_round_trips_and_rejects_bad_ids() {
+    let id: ThreadId = ID.parse().unwrap();
+    let encoded = serde_json::to_string(&id).unwrap();
+    assert_eq!(encoded, format!("\"{}\"", ID));
+    assert_eq!(serde_json::from_str::<ThreadId>(&encoded).unwrap(), id);
+
+    let err = serde_json::from_str::<ThreadId>("\"thread-1\"").unwrap_err();
+    assert!(err.to_string().contains("thread-1"));
+    assert!(serde_json::from_str::<ThreadId>("42").is_err());
+}
+
+#[test]
+fn resume_conversation_takes_a_thread_id() {
+    let id: ThreadId = ID.parse().unwrap();
+    let mut client = Client::new("", String::new());
+    client.append_message(Message::new(Type::User, "Old".to_string()));
+    client.resume_conversation(&id, vec![Message::new(Type::User, "Saved".to_string())]);
+    assert_eq!(client.get_thread_id(), Some(&id));
+    assert_eq!(client.get_messages().len(), 1);
+    assert_eq!(client.get_messages()[0].content(), "Saved");
+}
+
+#[test]
+#[allow(deprecated)]
+fn get_thread_id_str_formats_the_thread_id() {
+    let mut client = Client::new("", String::new());
+    assert!(client.get_thread_id_str().is_none());
+    client.set_thread_id(ID.parse().unwrap());
+    assert_eq!(client.get_thread_id_str().as_deref(), Some(ID));
+}
+#[tokio::test]
+async fn list_threads_skips_entries_without_a_valid_id() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/query"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "status": "success",
+            "value": {
+                "page": [
+                    { "threadId": ID, "title": "Kept", "pinned": true },
+                    { "threadId": "local-draft", "title": "Dropped" }
+                ],
+                "isDone": true,
+                "continueCursor": null
+            }
+        })))
+        .mount(&server)
+        .await;
+    let client = Client::builder("wos-session=test-session", "test-session".to_string())
+        .base_urls(BaseUrls::new(&server.uri()).with_convex(&server.uri()))
+        .build()
+        .unwrap();
+
+    let threads = client.list_threads(None).await.unwrap();
+    assert_eq!(threads.len(), 1);
+    assert_eq!(threads[0].id, ID.parse().unwrap());
+    assert_eq!(threads[0].title, "Kept");
+    assert!(threads[0].pinned);
+}
@@ -0,0 +1,44 @@
+use t3router::t3::client::Client;
+use t3router::t3::message::{Message, Type};
+use t3router::t3::tokens::{
+    HeuristicCounter, MESSAGE_OVERHEAD_TOKENS, TokenCounter, estimate_tokens, message_tokens,
+};
+
+#[test]
+fn estimate_tokens_on_empty_ascii_and_multibyte_text() {
+    assert_eq!(estimate_tokens(""), 0);
+    assert_eq!(estimate_tokens("a"), 1);
+    assert_eq!(estimate_tokens("abcd"), 1);
+    assert_eq!(estimate_tokens("abcde"), 2);
+    assert_eq!(estimate_tokens("The quick brown fox jumps over the lazy dog"), 11);
+
+    // Characters are counted, not bytes.
+    assert_eq!(estimate_tokens("héllo"), 2);
+    assert_eq!(estimate_tokens("日本語です"), 2);
+    assert_eq!(estimate_tokens("🦀🦀🦀🦀"), 1);
+    assert_eq!(HeuristicCounter.count("🦀🦀🦀🦀"), estimate_tokens("🦀🦀🦀🦀"));
+}
+
+#[test]
+fn message_estimates_agree_with_message_tokens() {
+    let message = Message::new(Type::User, "What is the capital of France?".to_string());
+    assert_eq!(message.estimated_tokens(), 8);
+    assert_eq!(
+        message_tokens(&HeuristicCounter, &message),
+        message.estimated_tokens() + MESSAGE_OVERHEAD_TOKENS
+    );
+
+    let note = Message::new_note("Remember to ask about Lyon".to_string());
+    assert_eq!(note.estimated_tokens(), 0);
+    assert_eq!(message_tokens(&HeuristicCounter, &note), 0);
+}
+
+#[test]
+fn conversation_tokens_sums_sent_messages() {
+    let mut client = Client::new("wos-session=test".to_string(), "test".to_string());
+    assert_eq!(client.conversation_tokens(), 0);
+    client.append_message(Message::new(Type::System, "Be brief.".to_string()));
+    client.append_message(Message::new(Type::User, "Hello there".to_string()));
+    client.append_note("not sent".to_string());
+    assert_eq!(client.conversation_tokens(), (3 + 3) + 2 * MESSAGE_OVERHEAD_TOKENS);
+}
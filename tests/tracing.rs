@@ -0,0 +1,126 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use futures_util::StreamExt;
+use t3router::t3::client::{Client, GenerationHandle};
+use t3router::t3::message::{Message, Type};
+use tracing::Level;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const SSE_BODY: &str = concat!(
+    "data: {\"type\":\"start\",\"messageId\":\"msg-1\"}\n\n",
+    "data: {\"type\":\"text-delta\",\"id\":\"0\",\"delta\":\"Hello\"}\n\n",
+    "data: {\"type\":\"finish\",\"finishReason\":\"stop\"}\n\n",
+    "data: [DONE]\n\n",
+);
+
+/// Collects the formatted log lines.
+#[derive(Clone, Default)]
+struct Logs(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Logs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Logs {
+    /// The lines of the "chat response" event logged for each request.
+    fn chat_responses(&self) -> Vec<String> {
+        let logs = String::from_utf8(self.0.lock().unwrap().clone()).unwrap();
+        logs.lines()
+            .filter(|line| line.contains("chat response"))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Logs the current thread's events to `Logs` until the guard is dropped. The tests run on
+/// a current-thread runtime, so every send is polled on this thread.
+fn capture() -> (Logs, tracing::subscriber::DefaultGuard) {
+    let logs = Logs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .with_max_level(Level::DEBUG)
+        .finish();
+    (logs, tracing::subscriber::set_default(subscriber))
+}
+
+async fn client() -> (MockServer, Client) {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(SSE_BODY),
+        )
+        .mount(&server)
+        .await;
+    let client = Client::builder("wos-session=abc", "test-session".to_string())
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    (server, client)
+}
+
+fn hello() -> Option<Message> {
+    Some(Message::new(Type::User, "Hi".to_string()))
+}
+
+/// Checks that the request was logged inside exactly one `send` span naming the model.
+fn assert_in_one_send_span(logs: &Logs) {
+    let lines = logs.chat_responses();
+    assert_eq!(lines.len(), 1, "{lines:?}");
+    assert_eq!(lines[0].matches("send{").count(), 1, "{}", lines[0]);
+    assert!(lines[0].contains("send{model=\"gpt-4o\""), "{}", lines[0]);
+    assert!(lines[0].contains("thread_id="), "{}", lines[0]);
+}
+
+#[tokio::test]
+async fn sends_run_in_a_send_span() {
+    let (_server, mut client) = client().await;
+    let (logs, _guard) = capture();
+    client.send("gpt-4o", hello(), None).await.unwrap();
+    assert_in_one_send_span(&logs);
+}
+
+#[tokio::test]
+async fn streams_run_in_a_send_span() {
+    let (_server, mut client) = client().await;
+    let (logs, _guard) = capture();
+    let deltas: Vec<_> = client.send_stream("gpt-4o", hello(), None).collect().await;
+    assert!(deltas.iter().all(Result::is_ok));
+    assert_in_one_send_span(&logs);
+}
+
+#[tokio::test]
+async fn callbacks_run_in_a_single_send_span() {
+    let (_server, mut client) = client().await;
+    let (logs, _guard) = capture();
+    client
+        .send_with_callback("gpt-4o", hello(), None, |_| {})
+        .await
+        .unwrap();
+    assert_in_one_send_span(&logs);
+}
+
+#[tokio::test]
+async fn cancellable_sends_run_in_a_send_span() {
+    let (_server, mut client) = client().await;
+    let (logs, _guard) = capture();
+    let handle = GenerationHandle::new();
+    client
+        .send_cancellable("gpt-4o", hello(), None, &handle)
+        .await
+        .unwrap();
+    assert_in_one_send_span(&logs);
+}
@@ -0,0 +1,111 @@
+use std::path::{Path, PathBuf};
+
+use t3router::t3::client::Client;
+use t3router::t3::error::T3Error;
+use t3router::t3::message::{Message, Type};
+use t3router::t3::vcr::Cassette;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const COOKIES: &str = "wos-session=test-session; convex-session-id=test";
+
+fn cassette(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/cassettes")
+        .join(name)
+}
+
+fn question() -> Option<Message> {
+    Some(Message::new(
+        Type::User,
+        "What is the capital of France?".to_string(),
+    ))
+}
+
+#[tokio::test]
+async fn send_replays_from_cassette() {
+    let mut client = Client::builder(COOKIES, "test-session".to_string())
+        .replay_from(cassette("send.json"))
+        .build()
+        .unwrap();
+
+    let reply = client
+        .send("gemini-2.5-flash-lite", question(), None)
+        .await
+        .unwrap();
+
+    assert_eq!(reply.content(), "The capital of France is Paris.");
+    assert_eq!(client.get_messages().len(), 2);
+}
+
+#[tokio::test]
+async fn unmatched_request_names_the_closest_recording() {
+    let mut client = Client::builder(COOKIES, "test-session".to_string())
+        .replay_from(cassette("send.json"))
+        .build()
+        .unwrap();
+
+    let err = client
+        .send(
+            "gemini-2.5-flash-lite",
+            Some(Message::new(
+                Type::User,
+                "What is the capital of Spain?".to_string(),
+            )),
+            None,
+        )
+        .await
+        .unwrap_err();
+
+    match err {
+        T3Error::CassetteMiss { request, closest } => {
+            assert!(request.starts_with("POST /api/chat (body "));
+            assert!(closest.starts_with("POST /api/chat (body "));
+            assert_ne!(request, closest);
+        }
+        other => panic!("expected a cassette miss, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn recorded_cassette_replays_without_the_server() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(include_str!("fixtures/text_only.sse")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    let path = std::env::temp_dir().join(format!("t3router-vcr-{}.json", std::process::id()));
+
+    let mut recorder = Client::builder(COOKIES, "test-session".to_string())
+        .base_url(&server.uri())
+        .record_to(path.clone())
+        .build()
+        .unwrap();
+    let recorded = recorder
+        .send("gemini-2.5-flash-lite", question(), None)
+        .await
+        .unwrap();
+    drop(server);
+
+    let saved = std::fs::read_to_string(&path).unwrap();
+    assert!(!saved.contains("test-session"));
+    assert_eq!(Cassette::load(&path).unwrap().interactions.len(), 1);
+
+    let mut player = Client::builder(COOKIES, "test-session".to_string())
+        .base_url("http://127.0.0.1:9")
+        .replay_from(path.clone())
+        .build()
+        .unwrap();
+    let replayed = player
+        .send("gemini-2.5-flash-lite", question(), None)
+        .await
+        .unwrap();
+    assert_eq!(replayed.content(), recorded.content());
+    std::fs::remove_file(&path).unwrap();
+}